@@ -0,0 +1,27 @@
+//! Merges the `stats.json` files of several bot instances (see
+//! [`AppStats::merge`]) and outputs the result to stdout as json, for bots run
+//! in multiple places. For example,
+//!
+//! ```text
+//! cargo run --example merge_stats -- a/stats.json b/stats.json >stats.json
+//! ```
+
+use anyhow::{bail, Context};
+use usaco_standings_bot::database::AppStats;
+
+fn main() -> anyhow::Result<()> {
+    let paths = std::env::args().skip(1).collect::<Vec<_>>();
+    if paths.len() < 2 {
+        bail!("usage: merge_stats <stats.json> <stats.json>...");
+    }
+
+    let mut merged = AppStats::default();
+    for path in paths {
+        let stats = std::fs::read_to_string(&path).with_context(|| format!("reading {path}"))?;
+        merged.merge(serde_json::from_str(&stats).with_context(|| format!("parsing {path}"))?);
+    }
+
+    serde_json::to_writer(std::io::stdout(), &merged)?;
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+//! Rendering [`ParticipantProfile`]s as PNG images, for the `cardimg` command.
+//! Text is drawn with the public domain 8x8 bitmap font from `font8x8`, scaled
+//! up, so no font files are needed.
+
+use crate::database::{MedalCounts, ParticipantProfile};
+use font8x8::{UnicodeFonts, BASIC_FONTS, LATIN_FONTS};
+use tiny_skia::{Color, ColorU8, Paint, Pixmap, Rect, Transform};
+use usaco_standings_scraper::{Division, Graduation};
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 320;
+/// Space between the contents of the card and its edges.
+const MARGIN: f32 = 24.;
+/// Width of a character at scale 1, including spacing.
+const CHAR_WIDTH: f32 = 8.;
+
+const BACKGROUND: ColorU8 = rgb(0x2b, 0x2d, 0x31);
+const TEXT: ColorU8 = rgb(0xf2, 0xf3, 0xf5);
+const MUTED_TEXT: ColorU8 = rgb(0xb5, 0xba, 0xc1);
+const BADGE_BACKGROUND: ColorU8 = rgb(0x40, 0x44, 0x4b);
+const CHART_BACKGROUND: ColorU8 = rgb(0x1e, 0x1f, 0x22);
+
+const fn rgb(r: u8, g: u8, b: u8) -> ColorU8 {
+    ColorU8::from_rgba(r, g, b, 255)
+}
+
+/// The color some `division` is drawn in, based on its namesake.
+fn division_color(division: Division) -> ColorU8 {
+    match division {
+        Division::Bronze => rgb(0xcd, 0x7f, 0x32),
+        Division::Silver => rgb(0xc0, 0xc0, 0xc0),
+        Division::Gold => rgb(0xff, 0xd7, 0x00),
+        Division::Platinum => rgb(0x7f, 0xd6, 0xe8),
+    }
+}
+
+/// A thin wrapper around a [`Pixmap`] for drawing rectangles and text.
+struct Canvas {
+    pixmap: Pixmap,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        let mut pixmap = Pixmap::new(WIDTH, HEIGHT).expect("card size should be valid");
+        pixmap.fill(Color::from_rgba8(
+            BACKGROUND.red(),
+            BACKGROUND.green(),
+            BACKGROUND.blue(),
+            BACKGROUND.alpha(),
+        ));
+
+        Self { pixmap }
+    }
+
+    /// Fills the rectangle, doing nothing if it's empty.
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, color: ColorU8) {
+        let Some(rect) = Rect::from_xywh(x, y, w, h) else {
+            return;
+        };
+
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(color.red(), color.green(), color.blue(), color.alpha());
+        paint.anti_alias = false;
+        self.pixmap
+            .fill_rect(rect, &paint, Transform::identity(), None);
+    }
+
+    /// Draws `text` with its top left corner at `(x, y)`, with each pixel of
+    /// the font `scale` pixels wide. Characters the font doesn't have are drawn
+    /// as `?`. Returns the width of the drawn text.
+    fn text(&mut self, x: f32, y: f32, text: &str, scale: f32, color: ColorU8) -> f32 {
+        let mut cx = x;
+
+        for c in text.chars() {
+            let glyph = BASIC_FONTS
+                .get(c)
+                .or_else(|| LATIN_FONTS.get(c))
+                .or_else(|| BASIC_FONTS.get('?'))
+                .unwrap_or_default();
+
+            for (row, bits) in glyph.into_iter().enumerate() {
+                for col in 0..8 {
+                    // the lowest bit is the leftmost pixel
+                    if bits & (1 << col) != 0 {
+                        self.rect(
+                            cx + col as f32 * scale,
+                            y + row as f32 * scale,
+                            scale,
+                            scale,
+                            color,
+                        );
+                    }
+                }
+            }
+
+            cx += CHAR_WIDTH * scale;
+        }
+
+        cx - x
+    }
+
+    /// Draws a badge with `label` on it at `(x, y)`, returning its width.
+    fn badge(&mut self, x: f32, y: f32, label: &str, color: ColorU8) -> f32 {
+        const PADDING: f32 = 6.;
+        const SCALE: f32 = 2.;
+
+        let width = label.chars().count() as f32 * CHAR_WIDTH * SCALE + 2. * PADDING;
+        self.rect(x, y, width, 8. * SCALE + 2. * PADDING, BADGE_BACKGROUND);
+        // a strip of color on the left, like a label
+        self.rect(x, y, 3., 8. * SCALE + 2. * PADDING, color);
+        self.text(x + PADDING, y + PADDING, label, SCALE, TEXT);
+
+        width
+    }
+}
+
+/// Describes IOI or EGOI medals like "IOI 2G 1S", or `None` if there were no
+/// appearances.
+fn medal_label(comp: &str, m: MedalCounts) -> Option<String> {
+    if m.appearances == 0 {
+        return None;
+    }
+
+    let mut label = comp.to_string();
+    for (count, letter) in [(m.gold, 'G'), (m.silver, 'S'), (m.bronze, 'B')] {
+        if count > 0 {
+            label += &format!(" {count}{letter}");
+        }
+    }
+    if m.gold + m.silver + m.bronze == 0 {
+        label += &format!(" x{}", m.appearances);
+    }
+
+    Some(label)
+}
+
+/// Renders `profile` as a PNG image card, with the participant's name (unless
+/// `hide_name`), badges for their best division, camps, and IOI and EGOI
+/// results, and a bar chart of their contest scores colored by division.
+pub fn render_profile_card(profile: &ParticipantProfile, hide_name: bool) -> Vec<u8> {
+    /// The highest score of recent contests, which the chart is scaled to
+    /// unless someone scored higher.
+    const CHART_MAX_SCORE: u16 = 1000;
+
+    let mut canvas = Canvas::new();
+    let summary = &profile.summary;
+
+    let accent = summary.best_division.map_or(MUTED_TEXT, division_color);
+    canvas.rect(0., 0., WIDTH as f32, 6., accent);
+
+    // names that are too long get cut off rather than running off the card
+    let max_chars = ((WIDTH as f32 - 2. * MARGIN) / (CHAR_WIDTH * 3.)) as usize;
+    let name = if hide_name {
+        "[name hidden]".to_string()
+    } else {
+        profile.id.name.chars().take(max_chars).collect()
+    };
+    canvas.text(MARGIN, MARGIN, &name, 3., TEXT);
+
+    let details = format!(
+        "{}, {}",
+        profile.id.country,
+        match profile.id.graduation {
+            Graduation::HighSchool { year } => format!("class of {year}"),
+            Graduation::Observer => "observer".to_string(),
+        }
+    );
+    canvas.text(MARGIN, MARGIN + 36., &details, 2., MUTED_TEXT);
+
+    let mut badges = vec![];
+    if let Some(division) = summary.best_division {
+        badges.push((division.to_string(), division_color(division)));
+    }
+    if summary.camps > 0 {
+        badges.push((format!("camp x{}", summary.camps), TEXT));
+    }
+    badges.extend(medal_label("IOI", summary.ioi).map(|l| (l, division_color(Division::Gold))));
+    badges.extend(medal_label("EGOI", summary.egoi).map(|l| (l, division_color(Division::Gold))));
+
+    let mut x = MARGIN;
+    for (label, color) in badges {
+        x += canvas.badge(x, MARGIN + 64., &label, color) + 8.;
+    }
+
+    let (chart_x, chart_y) = (MARGIN, MARGIN + 112.);
+    let (chart_w, chart_h) = (WIDTH as f32 - 2. * MARGIN, HEIGHT as f32 - chart_y - MARGIN);
+    canvas.rect(chart_x, chart_y, chart_w, chart_h, CHART_BACKGROUND);
+
+    if profile.contests.is_empty() {
+        canvas.text(
+            chart_x + 12.,
+            chart_y + 12.,
+            "no contest records",
+            2.,
+            MUTED_TEXT,
+        );
+    } else {
+        let max_score = profile
+            .contests
+            .iter()
+            .map(|c| c.record.score)
+            .max()
+            .unwrap_or(0)
+            .max(CHART_MAX_SCORE) as f32;
+        let slot = chart_w / profile.contests.len() as f32;
+        // leave gaps between bars when there's room for them
+        let gap = if slot >= 4. { slot / 4. } else { 0. };
+
+        for (i, c) in profile.contests.iter().enumerate() {
+            let h = (chart_h - 8.) * c.record.score as f32 / max_score;
+            canvas.rect(
+                chart_x + i as f32 * slot + gap / 2.,
+                chart_y + chart_h - h,
+                slot - gap,
+                h,
+                division_color(c.record.division),
+            );
+        }
+    }
+
+    canvas
+        .pixmap
+        .encode_png()
+        .expect("encoding an in-memory pixmap should not fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{NameQueryResult, Participant, ParticipantContestRecord, ParticipantId};
+    use usaco_standings_scraper::{Month, MonthYear};
+
+    #[test]
+    fn test_render_profile_card() {
+        let result = NameQueryResult {
+            participants: vec![Participant {
+                id: ParticipantId {
+                    name: "Jane Doe".to_string(),
+                    graduation: Graduation::HighSchool { year: 2025 },
+                    country: "USA".to_string(),
+                },
+                contests: [(Division::Gold, 700), (Division::Platinum, 1000)]
+                    .into_iter()
+                    .map(|(division, score)| ParticipantContestRecord {
+                        contest_time: MonthYear {
+                            year: 2024,
+                            month: Month::Open,
+                        },
+                        division,
+                        score,
+                        percentile: None,
+                        rank: None,
+                        solved: vec![],
+                    })
+                    .collect(),
+                camps: vec![],
+            }],
+            ioi: vec![],
+            egoi: vec![],
+        };
+        let profile = &result.profiles()[0];
+
+        let png = render_profile_card(profile, false);
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let image = Pixmap::decode_png(&png).unwrap();
+        assert_eq!((image.width(), image.height()), (WIDTH, HEIGHT));
+        // the accent strip is in the color of the best division
+        let top = image.pixel(WIDTH / 2, 0).unwrap();
+        let platinum = division_color(Division::Platinum);
+        assert_eq!(
+            (top.red(), top.green(), top.blue()),
+            (platinum.red(), platinum.green(), platinum.blue())
+        );
+
+        // the name is drawn, so hiding it changes the card
+        assert_ne!(render_profile_card(profile, true), png);
+    }
+}
@@ -1,15 +1,22 @@
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use poise::serenity_prelude as serenity;
 use regex::Regex;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serenity::UserId;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
+    io::{self, Read},
+    ops::RangeInclusive,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
 };
-use tracing::error;
+use tracing::{error, info, warn};
 use usaco_standings_scraper::{
-    CampParticipant, ContestParticipant, Division, Graduation, IntlHistory, IntlParticipant,
-    MonthYear, UsacoData,
+    normalize_country, placements, CampParticipant, ContestParticipant, Division, Graduation,
+    IntlCompetition, IntlHistory, IntlMedal, IntlParticipant, MonthYear, TestcaseResult, UsacoData,
 };
 
 /// A (name, country, graduation year) tuple that is a best effort to identify
@@ -49,12 +56,39 @@ pub struct ParticipantContestRecord {
     pub contest_time: MonthYear,
     pub division: Division,
     pub score: u16,
+    /// Percentage of the contest's participants that scored at most `score`.
+    /// Only participants listed on the results page count, which in recent
+    /// seasons is only those who promoted. `None` for records from db files
+    /// saved before this was computed.
+    #[serde(default)]
+    pub percentile: Option<f32>,
+    /// Rank within the contest, where ties share the best rank (see
+    /// [`usaco_standings_scraper::placements`]), along with the number of
+    /// participants listed. Like `percentile`, this only counts participants
+    /// on the results page, and is `None` for old db files.
+    #[serde(default)]
+    pub rank: Option<(u32, u32)>,
+    /// Whether each problem was fully solved (every testcase correct), in the
+    /// order they're listed on the results page. Empty for records from db
+    /// files saved before this was kept.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solved: Vec<bool>,
 }
 
 /// The record of a USACO camp for a specific participant.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParticipantCampRecord {
     pub camp_year: u16,
+    /// The school and state listed on the finalists page. Empty for records
+    /// from db files saved before these were kept.
+    #[serde(default)]
+    pub school: String,
+    #[serde(default)]
+    pub state: String,
+    /// Whether they were invited as an EGOI finalist. `false` for records from
+    /// db files saved before this was kept.
+    #[serde(default)]
+    pub is_egoi: bool,
 }
 
 /// The contests and camp data associated with a specific participant (based on
@@ -62,17 +96,475 @@ pub struct ParticipantCampRecord {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Participant {
     pub id: ParticipantId,
+    // empty vecs are left out to keep saved dbs small
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub contests: Vec<ParticipantContestRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub camps: Vec<ParticipantCampRecord>,
 }
 
+impl Participant {
+    /// This participant's contest records grouped by division. Records within
+    /// each division are in chronological order.
+    pub fn contests_by_division(&self) -> BTreeMap<Division, Vec<&ParticipantContestRecord>> {
+        let mut res = BTreeMap::<_, Vec<_>>::new();
+
+        for c in &self.contests {
+            res.entry(c.division).or_default().push(c);
+        }
+
+        for records in res.values_mut() {
+            records.sort_by_key(|c| c.contest_time);
+        }
+
+        res
+    }
+
+    /// This participant's records in the highest division they competed in,
+    /// in chronological order. Empty if they have no contest records.
+    pub fn peak_division_records(&self) -> Vec<&ParticipantContestRecord> {
+        self.contests_by_division()
+            .pop_last()
+            .map(|(_, records)| records)
+            .unwrap_or_default()
+    }
+
+    /// The number of contests this participant took in `from` before first
+    /// competing in a higher division. `None` if they never competed in `from`,
+    /// or never advanced past it.
+    pub fn contests_until_promotion(&self, from: Division) -> Option<usize> {
+        let mut contests = self.contests.iter().collect::<Vec<_>>();
+        contests.sort_by_key(|c| (c.contest_time, c.division));
+
+        let mut taken = 0;
+        for c in contests {
+            if c.division == from {
+                taken += 1;
+            } else if c.division > from && taken > 0 {
+                return Some(taken);
+            }
+        }
+
+        None
+    }
+
+    /// Whether this participant's graduation year doesn't fit their records,
+    /// since it would put them outside of grades 1 to 12 when they competed or
+    /// went to camp. This is usually a typo in the graduation year on a results
+    /// page. Always `false` for observers.
+    pub fn has_implausible_graduation(&self) -> bool {
+        let graduation = self.id.graduation;
+
+        self.contests
+            .iter()
+            .map(|c| c.contest_time.season())
+            .chain(self.camps.iter().map(|c| c.camp_year))
+            .any(|season| {
+                graduation
+                    .grade_at(season)
+                    .is_some_and(|g| !(1..=12).contains(&g))
+            })
+    }
+
+    /// This participant's progress over time as `(season, division, score)`
+    /// points in chronological order, suitable for charting. Each season is
+    /// collapsed to a single point: the best score in the highest division
+    /// competed in that season.
+    pub fn score_trajectory(&self) -> Vec<(u16, Division, u16)> {
+        let mut best = BTreeMap::<u16, (Division, u16)>::new();
+
+        for c in &self.contests {
+            let point = (c.division, c.score);
+            best.entry(c.contest_time.season())
+                .and_modify(|p| *p = (*p).max(point))
+                .or_insert(point);
+        }
+
+        best.into_iter()
+            .map(|(season, (division, score))| (season, division, score))
+            .collect()
+    }
+
+    /// The (population) standard deviation of this participant's scores in
+    /// `division`, where lower means more consistent. We don't return its
+    /// inverse since that would be infinite for perfectly consistent
+    /// participants. `None` if they have fewer than two records in `division`.
+    pub fn consistency(&self, division: Division) -> Option<f64> {
+        let scores = self
+            .contests
+            .iter()
+            .filter(|c| c.division == division)
+            .map(|c| c.score as f64)
+            .collect::<Vec<_>>();
+        if scores.len() < 2 {
+            return None;
+        }
+
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+
+        Some(variance.sqrt())
+    }
+
+    /// The largest score increase from one of this participant's contests to
+    /// their next one, as `(from, to, increase)`. Ties go to the earliest
+    /// jump. `None` if they never improved, including if they took fewer than
+    /// two contests.
+    ///
+    /// Scores are compared as is, even across divisions.
+    pub fn biggest_jump(&self) -> Option<(MonthYear, MonthYear, i32)> {
+        let mut contests = self.contests.iter().collect::<Vec<_>>();
+        contests.sort_by_key(|c| (c.contest_time, c.division));
+
+        contests
+            .windows(2)
+            .map(|w| {
+                (
+                    w[0].contest_time,
+                    w[1].contest_time,
+                    w[1].score as i32 - w[0].score as i32,
+                )
+            })
+            .filter(|&(_, _, delta)| delta > 0)
+            .rev()
+            .max_by_key(|&(_, _, delta)| delta)
+    }
+
+    /// This participant's average [`ParticipantContestRecord::percentile`]
+    /// within each division they competed in, which shows where they
+    /// plateaued. Records from old db files without a percentile have it
+    /// computed from the other records of that contest in `db`, and are left
+    /// out if their contest isn't in `db`.
+    pub fn percentile_by_division(&self, db: &UsacoDb) -> HashMap<Division, f32> {
+        // only built if some record is missing its percentile
+        let mut by_contest = None;
+        let mut res = HashMap::new();
+
+        for (division, records) in self.contests_by_division() {
+            let percentiles = records
+                .iter()
+                .filter_map(|c| {
+                    c.percentile.or_else(|| {
+                        let scores = by_contest
+                            .get_or_insert_with(|| db.by_contest())
+                            .get(&(c.contest_time, c.division))?;
+                        let at_most = scores.iter().filter(|&&(_, s)| s <= c.score).count();
+
+                        Some(at_most as f32 / scores.len() as f32 * 100.)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            if !percentiles.is_empty() {
+                res.insert(
+                    division,
+                    percentiles.iter().sum::<f32>() / percentiles.len() as f32,
+                );
+            }
+        }
+
+        res
+    }
+}
+
+/// Normalizes a name for comparison by lowercasing it and getting rid of
+/// duplicate whitespace.
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The words of `school`, lowercased, without punctuation, and with common
+/// abbreviations spelled out, so that "Lynbrook H.S." and "lynbrook high
+/// school" give the same words. A leading "the" and any "and"s are dropped.
+fn school_words(school: &str) -> Vec<String> {
+    let school = school
+        .to_lowercase()
+        // "h.s." is one abbreviation, while "-" and "," separate words
+        .replace('.', "")
+        .replace(|c: char| !c.is_alphanumeric(), " ");
+
+    let mut words = vec![];
+    for word in school.split_whitespace() {
+        match word {
+            "hs" => words.extend(["high", "school"]),
+            "ms" => words.extend(["middle", "school"]),
+            "sch" | "schl" => words.push("school"),
+            "acad" => words.push("academy"),
+            "sr" => words.push("senior"),
+            "st" => words.push("saint"),
+            // "&" is punctuation, so is already dropped
+            "and" => {}
+            word => words.push(word),
+        }
+    }
+    if words.first() == Some(&"the") {
+        words.remove(0);
+    }
+
+    words.into_iter().map(str::to_string).collect()
+}
+
+/// Whether the normalized names `query` and `intl` (from an IOI or EGOI result)
+/// could be the same person. Besides exact matches, names match if they have
+/// the same last name, and one first name is short for the other (such as
+/// "Ben Qi" and "Benjamin Qi", see `is_short_for`). Middle names are ignored,
+/// so "Jane Doe" also matches "Jane Mary Doe".
+fn intl_name_matches(query: &str, intl: &str) -> bool {
+    if query == intl {
+        return true;
+    }
+
+    let (query, intl) = (
+        query.split(' ').collect::<Vec<_>>(),
+        intl.split(' ').collect::<Vec<_>>(),
+    );
+    let (&[query_first, .., query_last], &[intl_first, .., intl_last]) = (&*query, &*intl) else {
+        // single word names only match exactly
+        return false;
+    };
+
+    query_last == intl_last
+        && (query_first == intl_first
+            || is_short_for(query_first, intl_first)
+            || is_short_for(intl_first, query_first))
+}
+
+/// Whether the first name `short` could be a shortened form of `long`, like
+/// "Ben" for "Benjamin". There's no list of nicknames, so this only checks for
+/// a prefix, which has to be at least 3 letters. The prefix also has to be
+/// missing at least 2 letters, since names a letter apart, like "Eric" and
+/// "Erica", are usually different names rather than nicknames.
+fn is_short_for(short: &str, long: &str) -> bool {
+    let (short_len, long_len) = (short.chars().count(), long.chars().count());
+
+    short_len >= 3 && long_len >= short_len + 2 && long.starts_with(short)
+}
+
+/// Whether `name` looks like UTF-8 that was mistakenly decoded as Windows-1252
+/// (or Latin-1), such as "JosÃ©" for "José". Every non-ASCII character in UTF-8
+/// is a lead byte followed by continuation bytes between 0x80 and 0xBF, so
+/// this looks for:
+///
+/// - "Ã" or "Â" (the lead bytes 0xC3 and 0xC2, which cover accented Latin
+///   letters) followed by a character a continuation byte decodes to, and
+/// - "â€", from the bytes 0xE2 0x80 that start punctuation like curly quotes
+///   and dashes.
+///
+/// Names with legitimate accents, even "Ã" on its own, aren't flagged.
+fn looks_mis_decoded(name: &str) -> bool {
+    // the Windows-1252 characters for the bytes 0x80 to 0x9F. bytes 0xA0 to 0xBF
+    // decode to the same codepoint
+    const CP1252_CONTINUATIONS: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+    let is_continuation =
+        |c: char| ('\u{80}'..='\u{bf}').contains(&c) || CP1252_CONTINUATIONS.contains(c);
+
+    name.contains("â€")
+        || name
+            .chars()
+            .zip(name.chars().skip(1))
+            .any(|(a, b)| matches!(a, 'Ã' | 'Â') && is_continuation(b))
+}
+
+/// A manual fix for a participant whose identity was scraped wrong, such as a
+/// misspelled name or an incorrect graduation year. All records under `from`
+/// are moved to `to`, merging with `to`'s records if they already exist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Correction {
+    pub from: ParticipantId,
+    pub to: ParticipantId,
+}
+
+/// Maps alternate spellings of a name to the canonical name, such as "Ben Qi"
+/// to "Benjamin Qi". Unlike [`Correction`]s, aliases apply to every person with
+/// that name regardless of country or graduation year, as well as to IOI and
+/// EGOI records. Alternate names are matched case-insensitively.
+pub type Aliases = HashMap<String, String>;
+
+/// Weights of each achievement in a [`DecorationScore`].
+#[derive(Debug, Copy, Clone)]
+pub struct DecorationWeights {
+    /// Per platinum contest taken.
+    pub platinum: f64,
+    /// Per camp attended.
+    pub camp: f64,
+    /// Per IOI or EGOI attended, regardless of medal.
+    pub intl_appearance: f64,
+    /// Per IOI or EGOI medal, on top of `intl_appearance`.
+    pub bronze: f64,
+    pub silver: f64,
+    pub gold: f64,
+}
+
+impl Default for DecorationWeights {
+    /// Making camp is worth about 5 platinum contests, and an IOI gold
+    /// medal is worth about 5 camps.
+    fn default() -> Self {
+        Self {
+            platinum: 1.,
+            camp: 5.,
+            intl_appearance: 5.,
+            bronze: 5.,
+            silver: 10.,
+            gold: 20.,
+        }
+    }
+}
+
+/// A participant's achievements, weighted into a single comparable `total`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecorationScore {
+    pub platinum_contests: usize,
+    pub camps: usize,
+    pub ioi: MedalCounts,
+    pub egoi: MedalCounts,
+    pub total: f64,
+}
+
+/// Weights of each part of a season's competitiveness. See
+/// [`UsacoDb::season_competitiveness_with`].
+#[derive(Debug, Copy, Clone)]
+pub struct CompetitivenessWeights {
+    /// Per distinct participant in the season.
+    pub participants: f64,
+    /// Per point of the average top score.
+    pub top_score: f64,
+    /// How many of the top scores of each contest are averaged.
+    pub top_n: usize,
+}
+
+impl Default for CompetitivenessWeights {
+    /// A season with 100 more participants is about as competitive as one
+    /// where the top 10 of each contest score 100 points higher on average.
+    fn default() -> Self {
+        Self {
+            participants: 1.,
+            top_score: 1.,
+            top_n: 10,
+        }
+    }
+}
+
+/// Thresholds for [`UsacoDb::redemption_stories_with`]. Percentiles are as in
+/// [`ParticipantContestRecord::percentile`], from 0 to 100.
+#[derive(Debug, Copy, Clone)]
+pub struct RedemptionThresholds {
+    /// How many of a participant's first contests have to be low-percentile.
+    pub early_contests: usize,
+    /// The highest percentile that counts as scoring poorly.
+    pub low_percentile: f32,
+    /// The lowest percentile that counts as excelling. Reaching platinum
+    /// counts too.
+    pub high_percentile: f32,
+}
+
+impl Default for RedemptionThresholds {
+    /// Bottom quartile in the first two contests, and top 10% (or platinum)
+    /// later on.
+    fn default() -> Self {
+        Self {
+            early_contests: 2,
+            low_percentile: 25.,
+            high_percentile: 90.,
+        }
+    }
+}
+
+/// Tunes which pairs of participants [`UsacoDb::suggest_merges`] suggests.
+#[derive(Debug, Copy, Clone)]
+pub struct MergeConfig {
+    /// The minimum similarity of two names, from 0 to 1, for them to be
+    /// considered the same. 1 only allows names that are the same ignoring
+    /// case and duplicate whitespace.
+    pub min_name_similarity: f64,
+    /// Whether to only suggest merging participants with the same graduation
+    /// year. Otherwise, observers and students with different graduation years
+    /// can be merged too.
+    pub require_same_graduation: bool,
+    /// Whether to only suggest merging participants from the same country.
+    pub require_same_country: bool,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            min_name_similarity: 0.9,
+            require_same_graduation: false,
+            require_same_country: false,
+        }
+    }
+}
+
+/// Tunes which participants [`UsacoDb::merge_graduation_corrections`] merges.
+#[derive(Debug, Copy, Clone)]
+pub struct GraduationMergeConfig {
+    /// The most two graduation years can differ by for them to be considered
+    /// the same person's. 0 disables merging.
+    pub max_year_difference: u16,
+}
+
+impl Default for GraduationMergeConfig {
+    /// Years off by one, which is what a corrected grade looks like.
+    fn default() -> Self {
+        Self {
+            max_year_difference: 1,
+        }
+    }
+}
+
+/// Two participants who might be the same person.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeSuggestion {
+    pub first: ParticipantId,
+    pub second: ParticipantId,
+    /// How likely the two are to be the same person, from 0 to 1.
+    pub confidence: f64,
+}
+
+/// The records added to a [`UsacoDb`] by an update. See [`UsacoDb::diff`].
+#[derive(Debug, Clone, Default)]
+pub struct DbDiff {
+    /// Participants with new records, each holding only their new records.
+    /// Participants new to the db come with all of their records.
+    pub participants: Vec<Participant>,
+}
+
+/// The lowest score [`UsacoDb::query_name_fuzzy`] returns matches for. Names
+/// scoring lower are usually unrelated.
+pub const MIN_FUZZY_SCORE: u32 = 80;
+
 /// Stores USACO data and answers queries.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SerializedUsacoDb")]
 pub struct UsacoDb {
-    pub participants: Vec<Participant>,
+    participants: Vec<Participant>,
+    intl_history: IntlHistory,
+    /// Maps lowercased names to the indices of the participants with that
+    /// name, for [`Self::query_name`]. Rebuilt by [`Self::reindex`] whenever
+    /// `participants` changes, rather than saved.
+    #[serde(skip)]
+    name_index: HashMap<String, Vec<usize>>,
+    /// See [`Self::promotion_data_cutoff`]. Also rebuilt by [`Self::reindex`].
+    #[serde(skip)]
+    promotion_data_cutoff: Option<MonthYear>,
+}
+
+/// What's saved of a [`UsacoDb`], which is everything but the index.
+#[derive(Deserialize)]
+struct SerializedUsacoDb {
+    participants: Vec<Participant>,
     intl_history: IntlHistory,
 }
 
+impl From<SerializedUsacoDb> for UsacoDb {
+    fn from(value: SerializedUsacoDb) -> Self {
+        Self::new(value.participants, value.intl_history)
+    }
+}
+
 /// Result from querying a specific name.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NameQueryResult {
@@ -84,45 +576,437 @@ pub struct NameQueryResult {
     pub egoi: Vec<IntlParticipant>,
 }
 
+/// Medal tally across a set of IOI or EGOI records.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MedalCounts {
+    /// Number of records, including ones without a medal.
+    pub appearances: usize,
+    pub gold: usize,
+    pub silver: usize,
+    pub bronze: usize,
+}
+
+impl MedalCounts {
+    fn from_records<'a>(records: impl IntoIterator<Item = &'a IntlParticipant>) -> Self {
+        let mut counts = Self::default();
+
+        for r in records {
+            counts.appearances += 1;
+            match r.result {
+                IntlMedal::Gold => counts.gold += 1,
+                IntlMedal::Silver => counts.silver += 1,
+                IntlMedal::Bronze => counts.bronze += 1,
+                IntlMedal::NoMedal | IntlMedal::VisaIssue => {}
+            }
+        }
+
+        counts
+    }
+}
+
+/// Aggregates over every record in a [`NameQueryResult`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NameSummary {
+    /// Number of distinct people found under the name.
+    pub people: usize,
+    /// The highest division any of the people competed in.
+    pub best_division: Option<Division>,
+    /// The highest score in [`best_division`](Self::best_division).
+    pub best_score: Option<u16>,
+    pub contests: usize,
+    pub camps: usize,
+    pub ioi: MedalCounts,
+    pub egoi: MedalCounts,
+}
+
+impl NameQueryResult {
+    /// Aggregates the records of this result, treating all people found as
+    /// one.
+    pub fn name_summary(&self) -> NameSummary {
+        let best = self
+            .participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .map(|c| (c.division, c.score))
+            .max();
+
+        NameSummary {
+            people: self.participants.len(),
+            best_division: best.map(|(division, _)| division),
+            best_score: best.map(|(_, score)| score),
+            contests: self.participants.iter().map(|p| p.contests.len()).sum(),
+            camps: self.participants.iter().map(|p| p.camps.len()).sum(),
+            ioi: MedalCounts::from_records(&self.ioi),
+            egoi: MedalCounts::from_records(&self.egoi),
+        }
+    }
+
+    /// This result with only the records matching the filters. `division`
+    /// only applies to contest records, while `years` applies to contest,
+    /// camp, and IOI and EGOI records alike, comparing the calendar year the
+    /// contest or event was held in. People left without any contest or camp
+    /// records are dropped.
+    pub fn filtered(
+        mut self,
+        division: Option<Division>,
+        years: Option<RangeInclusive<u16>>,
+    ) -> Self {
+        let in_years = |year| years.as_ref().is_none_or(|y| y.contains(&year));
+
+        for p in &mut self.participants {
+            p.contests.retain(|c| {
+                division.is_none_or(|d| c.division == d) && in_years(c.contest_time.year)
+            });
+            p.camps.retain(|c| in_years(c.camp_year));
+        }
+        self.participants
+            .retain(|p| !p.contests.is_empty() || !p.camps.is_empty());
+        self.ioi.retain(|r| in_years(r.year));
+        self.egoi.retain(|r| in_years(r.year));
+
+        self
+    }
+
+    /// The contest records of every person in this result merged into one
+    /// chronological list, each tagged with the person it came from. Records
+    /// from the same contest are ordered by division, then by person.
+    pub fn all_contests_chronological(&self) -> Vec<(&ParticipantId, &ParticipantContestRecord)> {
+        let mut res = self
+            .participants
+            .iter()
+            .flat_map(|p| p.contests.iter().map(move |c| (&p.id, c)))
+            .collect::<Vec<_>>();
+        res.sort_by_key(|&(id, c)| (c.contest_time, c.division, id));
+
+        res
+    }
+
+    /// A compact, fixed width "stats card" of this result, meant to be
+    /// displayed in a code block. If `hide_name`, the name will be hidden.
+    ///
+    /// Like `format_name_query_result`, the number of lines in the card is
+    /// the same regardless of `hide_name` (and in fact regardless of the
+    /// result).
+    pub fn summary_card(&self, hide_name: bool) -> String {
+        // width of the text between the borders
+        const WIDTH: usize = 28;
+
+        let summary = self.name_summary();
+
+        let name = if hide_name {
+            "[name hidden]"
+        } else {
+            self.participants
+                .first()
+                .map(|p| &p.id.name)
+                .or_else(|| self.ioi.first().map(|p| &p.name))
+                .or_else(|| self.egoi.first().map(|p| &p.name))
+                .map_or("[no records]", |n| n.as_str())
+        };
+        // names that are too long get cut off rather than breaking the layout
+        let name = name.chars().take(WIDTH).collect::<String>();
+
+        let fmt_medals = |m: MedalCounts| {
+            if m.appearances == 0 {
+                "-".to_string()
+            } else {
+                format!("{}G {}S {}B", m.gold, m.silver, m.bronze)
+            }
+        };
+
+        let border = format!("+{}+", "-".repeat(WIDTH + 2));
+        let mut lines = vec![
+            border.clone(),
+            format!("| {name:<WIDTH$} |"),
+            border.clone(),
+        ];
+
+        for (label, value) in [
+            ("People", summary.people.to_string()),
+            (
+                "Best division",
+                summary
+                    .best_division
+                    .map_or("-".to_string(), |d| d.to_string()),
+            ),
+            ("Contests", summary.contests.to_string()),
+            ("Camps", summary.camps.to_string()),
+            ("IOI", fmt_medals(summary.ioi)),
+            ("EGOI", fmt_medals(summary.egoi)),
+        ] {
+            let value_width = WIDTH - label.len() - 1;
+            lines.push(format!("| {label}:{value:>value_width$} |"));
+        }
+        lines.push(border);
+
+        lines.join("\n")
+    }
+}
+
+/// A fixed width side by side comparison of the summaries of `a` and `b`,
+/// meant to be displayed in a code block, with the names replaced by "Person
+/// 1" and "Person 2" if `hide_names`. Each result is treated as one person,
+/// like in [`NameQueryResult::name_summary`].
+pub fn comparison_table(a: &NameQueryResult, b: &NameQueryResult, hide_names: bool) -> String {
+    // width of the label and value columns
+    const LABEL_WIDTH: usize = 14;
+    const WIDTH: usize = 16;
+
+    let name = |res: &NameQueryResult, i| {
+        let name = res
+            .participants
+            .first()
+            .map(|p| &p.id.name)
+            .or_else(|| res.ioi.first().map(|p| &p.name))
+            .or_else(|| res.egoi.first().map(|p| &p.name));
+
+        match name {
+            Some(name) if !hide_names => name.chars().take(WIDTH).collect(),
+            _ => format!("Person {i}"),
+        }
+    };
+    let fmt_medals = |m: MedalCounts| {
+        if m.appearances == 0 {
+            "-".to_string()
+        } else {
+            format!("{}G {}S {}B", m.gold, m.silver, m.bronze)
+        }
+    };
+    let rows = |s: NameSummary| {
+        [
+            s.contests.to_string(),
+            s.best_division.map_or("-".to_string(), |d| d.to_string()),
+            s.best_score.map_or("-".to_string(), |s| s.to_string()),
+            s.camps.to_string(),
+            fmt_medals(s.ioi),
+            fmt_medals(s.egoi),
+        ]
+    };
+
+    let mut lines = vec![format!(
+        "{:LABEL_WIDTH$} {:<WIDTH$} {}",
+        "",
+        name(a, 1),
+        name(b, 2)
+    )];
+    let labels = [
+        "Contests",
+        "Best division",
+        "Best score",
+        "Camps",
+        "IOI",
+        "EGOI",
+    ];
+    for (label, (x, y)) in labels.into_iter().zip(
+        rows(a.name_summary())
+            .into_iter()
+            .zip(rows(b.name_summary())),
+    ) {
+        lines.push(format!("{label:LABEL_WIDTH$} {x:<WIDTH$} {y}"));
+    }
+
+    lines.join("\n")
+}
+
+/// The times of the contests in `contests` where the participant competed in a
+/// higher division than in any earlier contest. The first contest doesn't
+/// count as a promotion, and each time is only listed once, even if there are
+/// several higher division records from it. `contests` should be sorted
+/// chronologically, as in [`Participant::contests`].
+pub fn detect_promotions(contests: &[ParticipantContestRecord]) -> Vec<MonthYear> {
+    let mut res = vec![];
+    let mut highest = None;
+
+    for c in contests {
+        if highest.is_some_and(|d| c.division > d) && res.last() != Some(&c.contest_time) {
+            res.push(c.contest_time);
+        }
+        highest = highest.max(Some(c.division));
+    }
+
+    res
+}
+
+/// Whether `record` could belong to `id`, assuming the two have the same name.
+/// IOI and EGOI records only have names, so the best we can do is check that
+/// they're from the USA and were still in high school at the time.
+fn intl_plausibly_matches(id: &ParticipantId, record: &IntlParticipant) -> bool {
+    id.country == "USA"
+        && matches!(id.graduation, Graduation::HighSchool { year } if year >= record.year)
+}
+
+/// `participants` in order of their peak: the highest division they competed
+/// in, then their best score in it, both decreasing, with participants without
+/// contest records last. Ties are broken by id.
+fn sorted_by_peak<'a>(participants: impl Iterator<Item = &'a Participant>) -> Vec<&'a Participant> {
+    let mut res = participants
+        .map(|p| (p.contests.iter().map(|c| (c.division, c.score)).max(), p))
+        .collect::<Vec<_>>();
+    res.sort_unstable_by(|(peak1, p1), (peak2, p2)| {
+        peak2.cmp(peak1).then_with(|| p1.id.cmp(&p2.id))
+    });
+
+    res.into_iter().map(|(_, p)| p).collect()
+}
+
+/// A contest record along with details computed for display.
+#[derive(Debug, Clone)]
+pub struct ProfileContest {
+    pub record: ParticipantContestRecord,
+    /// `None` for observers.
+    pub grade: Option<i32>,
+}
+
+/// A camp record along with details computed for display.
+#[derive(Debug, Clone)]
+pub struct ProfileCamp {
+    pub record: ParticipantCampRecord,
+    /// `None` for observers, which shouldn't happen.
+    pub grade: Option<i32>,
+}
+
+/// Everything we know about a single person, assembled for display.
+#[derive(Debug, Clone)]
+pub struct ParticipantProfile {
+    pub id: ParticipantId,
+    /// In chronological order.
+    pub contests: Vec<ProfileContest>,
+    /// In chronological order.
+    pub camps: Vec<ProfileCamp>,
+    pub ioi: Vec<IntlParticipant>,
+    pub egoi: Vec<IntlParticipant>,
+    pub summary: NameSummary,
+}
+
+impl NameQueryResult {
+    /// Splits this result into one profile per distinct person (that is, per
+    /// [`ParticipantId`]), in the same order as `participants`.
+    ///
+    /// IOI and EGOI records only have names, so we can't tell which person
+    /// they belong to for certain. Each such record is attached to every
+    /// person from the USA who was still in high school that year; records
+    /// that no one matches are left out.
+    pub fn profiles(&self) -> Vec<ParticipantProfile> {
+        self.participants
+            .iter()
+            .map(|p| {
+                let graduation = p.id.graduation;
+                let intl_matches = |r: &&IntlParticipant| intl_plausibly_matches(&p.id, r);
+
+                let mut contests = p
+                    .contests
+                    .iter()
+                    .map(|c| ProfileContest {
+                        record: c.clone(),
+                        grade: graduation.grade_at(c.contest_time.season()),
+                    })
+                    .collect::<Vec<_>>();
+                contests.sort_by_key(|c| (c.record.contest_time, c.record.division));
+
+                let mut camps = p
+                    .camps
+                    .iter()
+                    .map(|c| ProfileCamp {
+                        record: c.clone(),
+                        grade: graduation.grade_at(c.camp_year),
+                    })
+                    .collect::<Vec<_>>();
+                camps.sort_by_key(|c| c.record.camp_year);
+
+                // the summary of just this person
+                let result = NameQueryResult {
+                    participants: vec![p.clone()],
+                    ioi: self.ioi.iter().filter(intl_matches).cloned().collect(),
+                    egoi: self.egoi.iter().filter(intl_matches).cloned().collect(),
+                };
+
+                ParticipantProfile {
+                    id: p.id.clone(),
+                    contests,
+                    camps,
+                    summary: result.name_summary(),
+                    ioi: result.ioi,
+                    egoi: result.egoi,
+                }
+            })
+            .collect()
+    }
+}
+
 impl UsacoDb {
+    fn new(participants: Vec<Participant>, intl_history: IntlHistory) -> Self {
+        let mut db = Self {
+            participants,
+            intl_history,
+            name_index: HashMap::new(),
+            promotion_data_cutoff: None,
+        };
+        db.reindex();
+
+        db
+    }
+
+    /// Rebuilds `name_index` and `promotion_data_cutoff`, which has to be done
+    /// after every change to `participants`.
+    fn reindex(&mut self) {
+        self.name_index.clear();
+
+        for (i, p) in self.participants.iter().enumerate() {
+            self.name_index
+                .entry(p.id.name.to_lowercase())
+                .or_default()
+                .push(i);
+        }
+
+        self.promotion_data_cutoff = self
+            .participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .filter(|c| c.division <= Division::Silver)
+            .map(|c| c.contest_time)
+            .max();
+    }
+
     /// Returns results under a specifc name. Currently, this just does a
     /// case-insensitive lookup with some normalization to get rid of duplicate
     /// whitespace.
     ///
+    /// IOI and EGOI results are matched more loosely (see `intl_name_matches`),
+    /// since names on the history page are sometimes written differently than
+    /// on contest results, and missing a medal is worse than showing an extra
+    /// one.
+    ///
     /// Records within each person are returned in chronological order. People
     /// are returned in order of graduation year and then country.
     ///
     /// We ignore the preferred names (the ones in parentheses) listed on the
     /// USACO camp / history pages.
     pub fn query_name(&self, name: &str) -> NameQueryResult {
-        // case-insensitive search + ignore duplicate whitespace
-        let name = name
-            .to_lowercase()
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join(" ");
-
-        // the database is currently ~20k people and growing very slowly. also this
-        // bot's usage is relatively small, so brute force should most definitely be ok.
+        let name = normalize_name(name);
+
+        // contest records are looked up in the name index. IOI and EGOI results are
+        // matched loosely, so they're scanned instead, but there are only a few
+        // hundred of them.
         let mut res = NameQueryResult {
             participants: self
-                .participants
-                .iter()
-                .filter(|p| p.id.name.to_lowercase() == name)
-                .cloned()
+                .name_index
+                .get(&name)
+                .into_iter()
+                .flatten()
+                .map(|&i| self.participants[i].clone())
                 .collect(),
             ioi: self
                 .intl_history
                 .ioi
                 .iter()
-                .filter(|p| p.name.to_lowercase() == name)
+                .filter(|p| intl_name_matches(&name, &normalize_name(&p.name)))
                 .cloned()
                 .collect(),
             egoi: self
                 .intl_history
                 .egoi
                 .iter()
-                .filter(|p| p.name.to_lowercase() == name)
+                .filter(|p| intl_name_matches(&name, &normalize_name(&p.name)))
                 .cloned()
                 .collect(),
         };
@@ -142,33 +1026,162 @@ impl UsacoDb {
         res
     }
 
-    /// Number of USACO people we know
-    pub fn people_count(&self) -> usize {
-        self.participants.len()
-    }
+    /// The participants who could be the IOI or EGOI participant named
+    /// `intl_name`, for going from a team member to their USACO history. This
+    /// is the reverse of how [`Self::query_name`] finds IOI and EGOI records,
+    /// so names are matched the same lenient way: the history page often uses
+    /// a longer or shorter first name than the results pages, or adds a middle
+    /// name.
+    ///
+    /// Names aren't unique, and the lenient matching makes collisions more
+    /// likely, so this can return several people (such as someone else who
+    /// happens to share a common name) or none (if the name is spelled
+    /// differently). Exact matches come first, then people are in order of
+    /// [`ParticipantId`], which is by name, then graduation year, then
+    /// country.
+    pub fn usaco_for_intl(&self, intl_name: &str) -> Vec<&Participant> {
+        let intl_name = normalize_name(intl_name);
 
-    /// Number of contest records we know
-    pub fn contest_count(&self) -> usize {
-        self.participants.iter().map(|p| p.contests.len()).sum()
-    }
+        let mut res = self
+            .participants
+            .iter()
+            .map(|p| (normalize_name(&p.id.name), p))
+            .filter(|(name, _)| intl_name_matches(name, &intl_name))
+            .collect::<Vec<_>>();
+        res.sort_unstable_by(|(n1, p1), (n2, p2)| {
+            (*n1 != intl_name, &p1.id).cmp(&(*n2 != intl_name, &p2.id))
+        });
 
-    /// Number of camp records we know
-    pub fn camp_count(&self) -> usize {
-        self.participants.iter().map(|p| p.camps.len()).sum()
+        res.into_iter().map(|(_, p)| p).collect()
     }
 
-    /// Number of IOI people we know
-    pub fn ioi_people_count(&self) -> usize {
-        self.intl_history
-            .ioi
+    /// The participants with names closest to `name`, for suggestions when
+    /// [`Self::query_name`] finds nothing. Each comes with a similarity score
+    /// from 0 to 100, which is the Jaro-Winkler similarity of the normalized
+    /// names, except that names matching like IOI and EGOI results do (such as
+    /// "Ben Qi" and "Benjamin Qi", see `intl_name_matches`) score at least
+    /// [`MIN_FUZZY_SCORE`] + 15. Only participants scoring at least
+    /// [`MIN_FUZZY_SCORE`] are returned, at most `max_results` of them, in
+    /// decreasing order of score.
+    pub fn query_name_fuzzy(&self, name: &str, max_results: usize) -> Vec<(u32, ParticipantId)> {
+        let name = normalize_name(name);
+
+        // similar names can't be looked up in the name index, so this scans everyone.
+        // the database is ~20k people and this only runs when a search finds
+        // nothing, so that's fine.
+        let mut res = self
+            .participants
             .iter()
-            .map(|p| &p.name)
-            .collect::<HashSet<_>>()
-            .len()
-    }
+            .filter_map(|p| {
+                let candidate = normalize_name(&p.id.name);
+                let mut score = (strsim::jaro_winkler(&name, &candidate) * 100.).round() as u32;
+                if intl_name_matches(&name, &candidate) {
+                    score = score.max(MIN_FUZZY_SCORE + 15);
+                }
 
-    /// Number of IOI contest records we know
-    pub fn ioi_records_count(&self) -> usize {
+                (score >= MIN_FUZZY_SCORE).then(|| (score, p.id.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(s1, id1), (s2, id2)| s2.cmp(s1).then_with(|| id1.cmp(id2)));
+        res.truncate(max_results);
+
+        res
+    }
+
+    /// Up to `limit` distinct participant names containing `partial`, for
+    /// autocompleting names typed in commands. Names are compared like in
+    /// [`Self::query_name`], with names starting with `partial` listed first,
+    /// and each group in alphabetical order. Names shared by several
+    /// participants (such as from different countries) are only listed once.
+    /// Empty if `partial` is blank.
+    pub fn autocomplete_names(&self, partial: &str, limit: usize) -> Vec<String> {
+        let partial = normalize_name(partial);
+        if partial.is_empty() {
+            return vec![];
+        }
+
+        let mut prefix_matches = BTreeMap::new();
+        let mut other_matches = BTreeMap::new();
+        // a linear scan, since substrings can't be looked up in the name index.
+        // every match is collected so that the alphabetically first ones are
+        // kept, not whichever come first in the db
+        for p in &self.participants {
+            let name = p.id.name.to_lowercase();
+            if name.starts_with(&partial) {
+                prefix_matches.entry(name).or_insert(&p.id.name);
+            } else if name.contains(&partial) {
+                other_matches.entry(name).or_insert(&p.id.name);
+            }
+        }
+
+        prefix_matches
+            .into_values()
+            .chain(other_matches.into_values())
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// The participant with exactly this id, if any. Unlike
+    /// [`Self::query_name`], records are returned as stored, without
+    /// sorting.
+    pub fn get(&self, id: &ParticipantId) -> Option<&Participant> {
+        self.name_index
+            .get(&id.name.to_lowercase())
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.participants[i])
+            .find(|p| &p.id == id)
+    }
+
+    /// Replaces the IOI and EGOI results with freshly scraped ones, such as
+    /// from [`usaco_standings_scraper::parse_history`]. Aliases need to be
+    /// applied again afterwards.
+    pub fn replace_intl_history(&mut self, mut intl_history: IntlHistory) {
+        strip_preferred_names(&mut intl_history);
+        self.intl_history = intl_history;
+    }
+
+    /// Number of USACO people we know
+    pub fn people_count(&self) -> usize {
+        self.participants.len()
+    }
+
+    /// Number of contest records we know
+    pub fn contest_count(&self) -> usize {
+        self.participants.iter().map(|p| p.contests.len()).sum()
+    }
+
+    /// Number of camp records we know
+    pub fn camp_count(&self) -> usize {
+        self.participants.iter().map(|p| p.camps.len()).sum()
+    }
+
+    /// The US team at the `comp` held in `year`, in the order listed on the
+    /// history page. Members who qualified but couldn't attend (see
+    /// [`IntlMedal::VisaIssue`]) are included.
+    pub fn intl_team(&self, comp: IntlCompetition, year: u16) -> Vec<IntlParticipant> {
+        let records = match comp {
+            IntlCompetition::Ioi => &self.intl_history.ioi,
+            IntlCompetition::Egoi => &self.intl_history.egoi,
+        };
+
+        records.iter().filter(|r| r.year == year).cloned().collect()
+    }
+
+    /// Number of IOI people we know
+    pub fn ioi_people_count(&self) -> usize {
+        self.intl_history
+            .ioi
+            .iter()
+            .map(|p| &p.name)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Number of IOI contest records we know
+    pub fn ioi_records_count(&self) -> usize {
         self.intl_history.ioi.len()
     }
 
@@ -186,144 +1199,4681 @@ impl UsacoDb {
     pub fn egoi_records_count(&self) -> usize {
         self.intl_history.egoi.len()
     }
-}
 
-impl Default for UsacoDb {
-    fn default() -> Self {
-        Self {
-            participants: vec![],
-            intl_history: IntlHistory {
-                ioi: vec![],
-                egoi: vec![],
-            },
+    /// All contest records held between `from` and `to` (inclusive), sorted
+    /// chronologically. [`MonthYear`]'s ordering is chronological, so this
+    /// works across the 6 and 4 contest per season eras.
+    pub fn records_in_range(
+        &self,
+        from: MonthYear,
+        to: MonthYear,
+    ) -> Vec<(&ParticipantId, &ParticipantContestRecord)> {
+        let mut res = self
+            .participants
+            .iter()
+            .flat_map(|p| p.contests.iter().map(move |c| (&p.id, c)))
+            .filter(|(_, c)| (from..=to).contains(&c.contest_time))
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(id1, c1), (id2, c2)| {
+            (c1.contest_time, c1.division, id1).cmp(&(c2.contest_time, c2.division, id2))
+        });
+
+        res
+    }
+
+    /// Groups every contest record by the contest it's from.
+    fn by_contest(&self) -> HashMap<(MonthYear, Division), Vec<(&ParticipantId, u16)>> {
+        let mut res = HashMap::<_, Vec<_>>::new();
+
+        for p in &self.participants {
+            for c in &p.contests {
+                res.entry((c.contest_time, c.division))
+                    .or_default()
+                    .push((&p.id, c.score));
+            }
         }
+
+        res
     }
-}
 
-impl From<UsacoData> for UsacoDb {
-    fn from(mut value: UsacoData) -> Self {
-        // deal with the preferred names that are in parentheses
-        let re = Regex::new(r#"\(.+\) "#).unwrap();
+    /// Pairs of participants who scored within `max_score_gap` of each other in
+    /// at least `min_shared` contests, along with the number of such contests.
+    /// Pairs are sorted by that count in decreasing order. Only contests both
+    /// took together are compared, so rivals are always from the same
+    /// division.
+    ///
+    /// This takes time proportional to the number of close pairs within each
+    /// contest, which in the worst case (e.g. a few hundred perfect scores) is
+    /// quadratic in the size of the contest. This is fine for the occasional
+    /// query but shouldn't be run on every request.
+    pub fn close_rivalries(
+        &self,
+        min_shared: usize,
+        max_score_gap: u16,
+    ) -> Vec<(ParticipantId, ParticipantId, usize)> {
+        let mut counts = HashMap::<_, usize>::new();
 
-        let mut participants = HashMap::new();
+        for mut records in self.by_contest().into_values() {
+            records.sort_unstable_by_key(|&(_, score)| score);
 
-        for contest in value.contests {
-            for p in contest.participants {
-                let id = ParticipantId::from(p.clone());
+            // everyone within the gap of participant i comes right after i
+            for (i, &(id1, score1)) in records.iter().enumerate() {
+                for &(id2, _) in records[i + 1..]
+                    .iter()
+                    .take_while(|&&(_, score2)| score2 - score1 <= max_score_gap)
+                {
+                    *counts.entry((id1.min(id2), id1.max(id2))).or_default() += 1;
+                }
+            }
+        }
 
-                participants
-                    .entry(id.clone())
-                    .or_insert_with(|| Participant {
-                        id,
-                        contests: vec![],
-                        camps: vec![],
-                    })
+        let mut res = counts
+            .into_iter()
+            .filter(|&(_, count)| count >= min_shared)
+            .map(|((id1, id2), count)| (id1.clone(), id2.clone(), count))
+            .collect::<Vec<_>>();
+        res.sort_unstable_by(|a, b| b.2.cmp(&a.2).then_with(|| (&a.0, &a.1).cmp(&(&b.0, &b.1))));
+
+        res
+    }
+
+    /// Merges participants who are likely the same student listed with
+    /// different graduation years, such as after their reported grade was
+    /// corrected, returning the merged `(from, to)` ids in order. Each merge is
+    /// also logged.
+    ///
+    /// Participants are merged when they have the same name (ignoring case and
+    /// duplicate whitespace) and country, and graduation years that differ by
+    /// at most [`GraduationMergeConfig::max_year_difference`]. The year with
+    /// the most records is kept, with ties going to the one with the most
+    /// recent records, since corrections come later. To avoid merging distinct
+    /// people who share a common name, participants who took the same contest
+    /// or went to the same camp are never merged, and every year merged into
+    /// one must be close to the kept year, rather than just to each other.
+    /// Observers are never merged.
+    pub fn merge_graduation_corrections(
+        &mut self,
+        config: GraduationMergeConfig,
+    ) -> Vec<(ParticipantId, ParticipantId)> {
+        /// When `p` competed or went to camp.
+        fn record_times(p: &Participant) -> (HashSet<MonthYear>, HashSet<u16>) {
+            (
+                p.contests.iter().map(|c| c.contest_time).collect(),
+                p.camps.iter().map(|c| c.camp_year).collect(),
+            )
+        }
+
+        if config.max_year_difference == 0 {
+            return vec![];
+        }
+
+        let mut groups = HashMap::<_, Vec<(usize, u16)>>::new();
+        for (i, p) in self.participants.iter().enumerate() {
+            if let Graduation::HighSchool { year } = p.id.graduation {
+                groups
+                    .entry((normalize_name(&p.id.name), &p.id.country))
+                    .or_default()
+                    .push((i, year));
+            }
+        }
+
+        // (index of participant to merge, id to merge it into)
+        let mut merges = vec![];
+        for mut group in groups.into_values().filter(|g| g.len() > 1) {
+            // the most records first, then the most recent, then by id so that
+            // the order doesn't depend on the order of participants
+            let key = |i: usize| {
+                let p = &self.participants[i];
+                let latest = p
                     .contests
-                    .push(ParticipantContestRecord {
-                        contest_time: contest.time,
-                        division: contest.division,
-                        score: p.score,
-                    });
+                    .iter()
+                    .map(|c| c.contest_time.season())
+                    .chain(p.camps.iter().map(|c| c.camp_year))
+                    .max();
+                (p.contests.len() + p.camps.len(), latest)
+            };
+            group.sort_unstable_by(|&(i1, _), &(i2, _)| {
+                key(i2)
+                    .cmp(&key(i1))
+                    .then_with(|| self.participants[i1].id.cmp(&self.participants[i2].id))
+            });
+
+            while !group.is_empty() {
+                let (kept, kept_year) = group.remove(0);
+                let kept = &self.participants[kept];
+                let (mut contests, mut camps) = record_times(kept);
+
+                group.retain(|&(i, year)| {
+                    let p = &self.participants[i];
+                    let (p_contests, p_camps) = record_times(p);
+                    if year.abs_diff(kept_year) > config.max_year_difference
+                        || !contests.is_disjoint(&p_contests)
+                        || !camps.is_disjoint(&p_camps)
+                    {
+                        return true;
+                    }
+
+                    contests.extend(p_contests);
+                    camps.extend(p_camps);
+                    merges.push((i, kept.id.clone()));
+                    false
+                });
             }
         }
 
-        for camp in value.camps {
-            for p in camp.participants {
-                let id = ParticipantId::from(p.clone());
+        let mut res = merges
+            .into_iter()
+            .map(|(i, to)| {
+                let from = std::mem::replace(&mut self.participants[i].id, to.clone());
+                (from, to)
+            })
+            .collect::<Vec<_>>();
+        res.sort_unstable();
+        for (from, to) in &res {
+            info!("merging {from:?} into {to:?}, likely a corrected graduation year");
+        }
 
-                participants
-                    .entry(id.clone())
-                    .or_insert_with(|| Participant {
-                        id,
-                        contests: vec![],
-                        camps: vec![],
-                    })
-                    .camps
-                    .push(ParticipantCampRecord {
-                        camp_year: camp.year,
-                    });
+        self.merge_duplicate_ids();
+
+        res
+    }
+
+    /// Merges together participants with the same id.
+    fn merge_duplicate_ids(&mut self) {
+        let mut merged = HashMap::<_, Participant>::new();
+
+        for p in std::mem::take(&mut self.participants) {
+            match merged.entry(p.id.clone()) {
+                Entry::Occupied(mut e) => {
+                    let e = e.get_mut();
+                    e.contests.extend(p.contests);
+                    e.camps.extend(p.camps);
+                }
+                Entry::Vacant(e) => {
+                    e.insert(p);
+                }
             }
         }
 
-        for comp in [&mut value.intl_history.ioi, &mut value.intl_history.egoi] {
-            for participant in comp {
-                participant.name = re.replace(&participant.name, "").to_string();
+        self.participants = merged.into_values().collect();
+        self.reindex();
+    }
+
+    /// Applies `corrections` to the participants in this db. Chains of
+    /// corrections are followed to the end, so with corrections from A to B
+    /// and from B to C, A's records are moved to C. Corrections whose `from`
+    /// isn't found, or which lead back to themselves, are logged and ignored.
+    pub fn apply_corrections(&mut self, corrections: &[Correction]) {
+        let direct = corrections
+            .iter()
+            .map(|c| (&c.from, &c.to))
+            .collect::<HashMap<_, _>>();
+        let corrections = direct
+            .iter()
+            .filter_map(|(&from, &to)| {
+                let mut seen = HashSet::from([from]);
+                let mut to = to;
+                while let Some(&next) = direct.get(to) {
+                    if !seen.insert(to) {
+                        warn!("ignoring correction for {from:?}, which leads back to itself");
+                        return None;
+                    }
+                    to = next;
+                }
+
+                Some((from, to))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut applied = HashSet::new();
+
+        for p in &mut self.participants {
+            if let Some((&from, &to)) = corrections.get_key_value(&p.id) {
+                info!("correcting {from:?} to {to:?}");
+                applied.insert(from);
+                p.id = to.clone();
             }
         }
 
-        Self {
-            participants: participants.into_values().collect(),
-            intl_history: value.intl_history,
+        for from in corrections.keys().filter(|from| !applied.contains(*from)) {
+            warn!("correction for {from:?} matched no participant");
         }
+
+        self.merge_duplicate_ids();
     }
-}
 
-/// Various statistics about the bot to be preserved across runs.
-#[derive(Clone, Serialize, Deserialize, Default)]
-pub struct AppStats {
-    /// Maps users to the number of times they have queried.
-    #[serde(default)]
-    pub users_queried: HashMap<UserId, usize>,
-    /// Amount of /search requests this bot has responded to.
-    #[serde(default)]
-    pub query_count: u32,
-}
+    /// Renames every participant and IOI / EGOI record under an alternate name
+    /// in `aliases` to its canonical name, merging participants when they end
+    /// up with the same id.
+    pub fn apply_aliases(&mut self, aliases: &Aliases) {
+        let aliases = aliases
+            .iter()
+            .map(|(alias, canonical)| (normalize_name(alias), canonical))
+            .collect::<HashMap<_, _>>();
 
-/// The data persisted by this bot.
-pub struct StoreData {
-    pub db: UsacoDb,
-    pub stats: AppStats,
-}
+        let names = self.participants.iter_mut().map(|p| &mut p.id.name).chain(
+            self.intl_history
+                .ioi
+                .iter_mut()
+                .chain(&mut self.intl_history.egoi)
+                .map(|p| &mut p.name),
+        );
+        for name in names {
+            if let Some(&canonical) = aliases.get(&normalize_name(name)) {
+                canonical.clone_into(name);
+            }
+        }
 
-/// A very simple database that saves and loads from the filesystem.
-pub struct FileStore {
-    path: PathBuf,
-}
+        self.merge_duplicate_ids();
+    }
 
-impl FileStore {
-    /// Creates a new file store that saves and loads its data from the given
-    /// `path`. `path` should point to a folder.
-    pub fn new_path(path: PathBuf) -> Self {
-        Self { path }
+    /// The average number of contests participants took in `from` before
+    /// advancing to a higher division, as computed by
+    /// [`Participant::contests_until_promotion`]. Participants who never
+    /// advanced are excluded, so this underestimates how hard it is to get out
+    /// of a division. `None` if nobody advanced from `from`.
+    pub fn avg_contests_to_promote(&self, from: Division) -> Option<f64> {
+        let (count, total) = self
+            .participants
+            .iter()
+            .filter_map(|p| p.contests_until_promotion(from))
+            .fold((0, 0), |(count, total), taken| (count + 1, total + taken));
+
+        (count > 0).then(|| total as f64 / count as f64)
     }
 
-    /// Attempts to load data from the path. Default values will be returned if
-    /// data fails to load.
-    pub async fn load(&self) -> StoreData {
-        async fn load<T: DeserializeOwned + Default>(path: impl AsRef<Path>) -> T {
-            async {
-                let data = tokio::fs::read_to_string(path.as_ref()).await?;
+    /// The `limit` participants with the highest [`DecorationScore`] using the
+    /// default [`DecorationWeights`], highest first. See
+    /// [`Self::most_decorated_with`].
+    pub fn most_decorated(&self, limit: usize) -> Vec<(ParticipantId, DecorationScore)> {
+        self.most_decorated_with(limit, DecorationWeights::default())
+    }
 
-                Ok(serde_json::from_str::<T>(&data)?)
+    /// The `limit` participants with the highest [`DecorationScore`] using
+    /// `weights`, highest first.
+    ///
+    /// IOI and EGOI records are linked to participants by normalized name,
+    /// with the same caveats as [`NameQueryResult::profiles`]. People with only
+    /// IOI or EGOI records aren't ranked.
+    pub fn most_decorated_with(
+        &self,
+        limit: usize,
+        weights: DecorationWeights,
+    ) -> Vec<(ParticipantId, DecorationScore)> {
+        fn by_name(records: &[IntlParticipant]) -> HashMap<String, Vec<&IntlParticipant>> {
+            let mut res = HashMap::<_, Vec<_>>::new();
+            for r in records {
+                res.entry(normalize_name(&r.name)).or_default().push(r);
             }
-            .await
-            .unwrap_or_else(|e: anyhow::Error| {
-                error!("failed to load data from path {:?} {e:?}", path.as_ref());
-                Default::default()
+            res
+        }
+        let ioi = by_name(&self.intl_history.ioi);
+        let egoi = by_name(&self.intl_history.egoi);
+
+        let mut res = self
+            .participants
+            .iter()
+            .map(|p| {
+                let name = normalize_name(&p.id.name);
+                let intl = |by_name: &HashMap<String, Vec<&IntlParticipant>>| {
+                    MedalCounts::from_records(
+                        by_name
+                            .get(&name)
+                            .into_iter()
+                            .flatten()
+                            .copied()
+                            .filter(|r| intl_plausibly_matches(&p.id, r)),
+                    )
+                };
+                let ioi = intl(&ioi);
+                let egoi = intl(&egoi);
+
+                let platinum_contests = p
+                    .contests
+                    .iter()
+                    .filter(|c| c.division == Division::Platinum)
+                    .count();
+                let camps = p.camps.len();
+
+                let medals = |m: MedalCounts| {
+                    m.appearances as f64 * weights.intl_appearance
+                        + m.bronze as f64 * weights.bronze
+                        + m.silver as f64 * weights.silver
+                        + m.gold as f64 * weights.gold
+                };
+                let total = platinum_contests as f64 * weights.platinum
+                    + camps as f64 * weights.camp
+                    + medals(ioi)
+                    + medals(egoi);
+
+                (
+                    p.id.clone(),
+                    DecorationScore {
+                        platinum_contests,
+                        camps,
+                        ioi,
+                        egoi,
+                        total,
+                    },
+                )
+            })
+            .filter(|(_, score)| score.total > 0.)
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(id1, s1), (id2, s2)| {
+            s2.total.total_cmp(&s1.total).then_with(|| id1.cmp(id2))
+        });
+        res.truncate(limit);
+
+        res
+    }
+
+    /// For every `division` contest, the number of participants who scored at
+    /// least `threshold`. Sorted by that number in decreasing order, so the
+    /// contests with the deepest fields come first.
+    pub fn high_performer_counts(
+        &self,
+        division: Division,
+        threshold: u16,
+    ) -> Vec<(MonthYear, usize)> {
+        let mut res = self
+            .by_contest()
+            .into_iter()
+            .filter(|&((_, d), _)| d == division)
+            .map(|((time, _), records)| {
+                (
+                    time,
+                    records
+                        .iter()
+                        .filter(|&&(_, score)| score >= threshold)
+                        .count(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(t1, c1), (t2, c2)| c2.cmp(c1).then(t1.cmp(t2)));
+
+        res
+    }
+
+    /// The `limit` highest scores in `division`, across all time or only in
+    /// `season` (as in [`MonthYear::season`]), as `(participant, score,
+    /// contest)`. Each participant is listed once, with their best score and
+    /// the earliest contest they got it in. Sorted by score in decreasing
+    /// order, with ties broken by earliest contest.
+    pub fn top_scores(
+        &self,
+        division: Division,
+        season: Option<u16>,
+        limit: usize,
+    ) -> Vec<(ParticipantId, u16, MonthYear)> {
+        let mut res = self
+            .participants
+            .iter()
+            .filter_map(|p| {
+                p.contests
+                    .iter()
+                    .filter(|c| c.division == division)
+                    .filter(|c| season.is_none_or(|s| c.contest_time.season() == s))
+                    .min_by_key(|c| (std::cmp::Reverse(c.score), c.contest_time))
+                    .map(|c| (&p.id, c.score, c.contest_time))
             })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(id1, s1, t1), (id2, s2, t2)| {
+            s2.cmp(s1).then(t1.cmp(t2)).then_with(|| id1.cmp(id2))
+        });
+
+        res.into_iter()
+            .take(limit)
+            .map(|(id, score, time)| (id.clone(), score, time))
+            .collect()
+    }
+
+    /// The Pearson correlation between participants' grades (as in
+    /// [`Graduation::grade_at`]) and their scores, over every `division`
+    /// contest record. Positive if older students tend to score higher.
+    ///
+    /// Observers have no grade, so their records are left out. Grades are
+    /// taken as listed, so the occasional out of range grade from a typo in
+    /// the graduation year is included. `None` if there are fewer than two
+    /// records, or if every grade or every score is the same.
+    pub fn grade_score_correlation(&self, division: Division) -> Option<f64> {
+        let points = self
+            .participants
+            .iter()
+            .flat_map(|p| {
+                p.contests
+                    .iter()
+                    .filter(|c| c.division == division)
+                    .filter_map(|c| {
+                        let grade = p.id.graduation.grade_at(c.contest_time.season())?;
+                        Some((grade as f64, c.score as f64))
+                    })
+            })
+            .collect::<Vec<_>>();
+        if points.len() < 2 {
+            return None;
         }
 
-        let (db, stats) = tokio::join!(
-            load(self.path.join("usaco-db.json")),
-            load(self.path.join("stats.json"))
-        );
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+
+        let (mut cov, mut var_x, mut var_y) = (0., 0., 0.);
+        for &(x, y) in &points {
+            cov += (x - mean_x) * (y - mean_y);
+            var_x += (x - mean_x).powi(2);
+            var_y += (y - mean_y).powi(2);
+        }
+        if var_x == 0. || var_y == 0. {
+            return None;
+        }
 
-        StoreData { db, stats }
+        Some(cov / (var_x * var_y).sqrt())
     }
 
-    /// Saves `db`. We require a mutable reference to prevent racing
-    /// the file system.
-    pub async fn save_db(&mut self, db: &UsacoDb) -> anyhow::Result<()> {
-        tokio::fs::write(self.path.join("usaco-db.json"), serde_json::to_string(&db)?).await?;
+    /// Participants who competed in every `division` contest held between
+    /// their first and last `division` contest, in decreasing order of how
+    /// many contests that is. Contests are only known to have been held if
+    /// someone has a record from them. People with just one `division`
+    /// contest are left out, since they trivially never missed one.
+    ///
+    /// Recent leaderboards only list promoters, who leave the division after
+    /// promoting, so this mostly finds people from seasons with complete
+    /// results.
+    pub fn perfect_attendance(&self, division: Division) -> Vec<&Participant> {
+        let held = self
+            .participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .filter(|c| c.division == division)
+            .map(|c| c.contest_time)
+            .collect::<BTreeSet<_>>();
 
-        Ok(())
+        let mut res = self
+            .participants
+            .iter()
+            .filter_map(|p| {
+                let attended = p
+                    .contests
+                    .iter()
+                    .filter(|c| c.division == division)
+                    .map(|c| c.contest_time)
+                    .collect::<BTreeSet<_>>();
+                let (&first, &last) = (attended.first()?, attended.last()?);
+
+                (attended.len() >= 2 && held.range(first..=last).count() == attended.len())
+                    .then_some((attended.len(), p))
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(c1, p1), (c2, p2)| c2.cmp(c1).then_with(|| p1.id.cmp(&p2.id)));
+
+        res.into_iter().map(|(_, p)| p).collect()
     }
 
-    /// Saves `stats`. We require a mutable reference to prevent racing
-    /// the file system.
-    pub async fn save_stats(&mut self, stats: &AppStats) -> anyhow::Result<()> {
-        tokio::fs::write(self.path.join("stats.json"), serde_json::to_string(&stats)?).await?;
+    /// Percentage of the participants of the `division` contest held at `time`
+    /// who scored at most `score`, like
+    /// [`ParticipantContestRecord::percentile`] but for any score. `None`
+    /// if there are no records from the contest.
+    pub fn percentile(&self, time: MonthYear, division: Division, score: u16) -> Option<f32> {
+        let (mut total, mut at_most) = (0, 0);
+        for c in self
+            .participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .filter(|c| c.contest_time == time && c.division == division)
+        {
+            total += 1;
+            if c.score <= score {
+                at_most += 1;
+            }
+        }
 
-        Ok(())
+        (total > 0).then(|| at_most as f32 / total as f32 * 100.)
+    }
+
+    /// The leaderboard of a contest, reconstructed from the contest records of
+    /// each participant. Sorted by score in decreasing order.
+    pub fn contest_participants(
+        &self,
+        time: MonthYear,
+        division: Division,
+    ) -> Vec<(&ParticipantId, u16)> {
+        let mut res = self
+            .participants
+            .iter()
+            .flat_map(|p| {
+                p.contests
+                    .iter()
+                    .filter(|c| c.contest_time == time && c.division == division)
+                    .map(|c| (&p.id, c.score))
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(id1, s1), (id2, s2)| s2.cmp(s1).then_with(|| id1.cmp(id2)));
+
+        res
+    }
+
+    /// The finalists of the camp held in `camp_year`, reconstructed from the
+    /// camp records of each participant. Sorted by name, then by the rest of
+    /// the id.
+    pub fn camp_roster(&self, camp_year: u16) -> Vec<(&ParticipantId, &ParticipantCampRecord)> {
+        let mut res = self
+            .participants
+            .iter()
+            .flat_map(|p| {
+                p.camps
+                    .iter()
+                    .filter(|c| c.camp_year == camp_year)
+                    .map(|c| (&p.id, c))
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by_key(|&(id, _)| id);
+
+        res
+    }
+
+    /// Participants from `school` graduating in `graduation_year`, ranked by
+    /// their best score in the highest division they reached. Schools are
+    /// compared case-insensitively, ignoring duplicate whitespace.
+    ///
+    /// Schools are only listed on camp finalist pages, so this only covers
+    /// campers, and campers without contest records are left out.
+    pub fn school_class_ranking(
+        &self,
+        school: &str,
+        graduation_year: u16,
+    ) -> Vec<(&ParticipantId, u16)> {
+        let school = normalize_name(school);
+
+        let mut res = self
+            .participants
+            .iter()
+            .filter(|p| {
+                p.id.graduation
+                    == Graduation::HighSchool {
+                        year: graduation_year,
+                    }
+            })
+            .filter(|p| p.camps.iter().any(|c| normalize_name(&c.school) == school))
+            .filter_map(|p| {
+                p.contests
+                    .iter()
+                    .max_by_key(|c| (c.division, c.score))
+                    .map(|c| (&p.id, c.division, c.score))
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(id1, d1, s1), (id2, d2, s2)| {
+            (d2, s2).cmp(&(d1, s1)).then_with(|| id1.cmp(id2))
+        });
+
+        res.into_iter().map(|(id, _, score)| (id, score)).collect()
+    }
+
+    /// The `limit` problems in `division` with the lowest full solve rates
+    /// across all contests, as `(contest, problem index, solve rate)` in
+    /// increasing order of solve rate. The solve rate is the fraction of the
+    /// contest's participants who got every testcase of the problem correct.
+    ///
+    /// The problem index is the 0-indexed position of the problem's column on
+    /// the results page, which is usually but not always the problem's
+    /// number. In particular, the problem thrown out of 2017 Open Gold
+    /// still has a column, and some participants' results for it are
+    /// missing. Like percentiles, rates only count participants listed on
+    /// the results page, so in recent seasons they're rates among those who
+    /// promoted.
+    pub fn hardest_problems(
+        &self,
+        division: Division,
+        limit: usize,
+    ) -> Vec<(MonthYear, usize, f32)> {
+        // (number of participants, number of solves of each problem) per contest
+        let mut contests = BTreeMap::<MonthYear, (usize, Vec<usize>)>::new();
+
+        for c in self
+            .participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .filter(|c| c.division == division)
+        {
+            let (count, solves) = contests.entry(c.contest_time).or_default();
+            *count += 1;
+
+            if solves.len() < c.solved.len() {
+                solves.resize(c.solved.len(), 0);
+            }
+            for (s, _) in solves
+                .iter_mut()
+                .zip(&c.solved)
+                .filter(|(_, &solved)| solved)
+            {
+                *s += 1;
+            }
+        }
+
+        let mut res = contests
+            .into_iter()
+            .flat_map(|(time, (count, solves))| {
+                solves
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, s)| (time, i, s as f32 / count as f32))
+            })
+            .collect::<Vec<_>>();
+        res.sort_by(|(t1, i1, r1), (t2, i2, r2)| r1.total_cmp(r2).then((t1, i1).cmp(&(t2, i2))));
+        res.truncate(limit);
+
+        res
+    }
+
+    /// Participants who went to camp but have no contest records, in order of
+    /// their first camp year. Since campers must have qualified through
+    /// contests, these point to gaps in the scraped data, or to names that are
+    /// spelled differently on the finalists page.
+    pub fn campers_without_contests(&self) -> Vec<&Participant> {
+        let mut res = self
+            .participants
+            .iter()
+            .filter(|p| p.contests.is_empty() && !p.camps.is_empty())
+            .collect::<Vec<_>>();
+        res.sort_unstable_by_key(|p| (p.camps.iter().map(|c| c.camp_year).min(), &p.id));
+
+        res
+    }
+
+    /// Campers whose highest division competed in is below platinum, in order
+    /// of their first camp year. Camp invitations go to top platinum
+    /// participants, so these point to platinum contests missing from the
+    /// scraped data. Campers with no contest records at all are left to
+    /// [`Self::campers_without_contests`].
+    pub fn camp_division_mismatches(&self) -> Vec<&Participant> {
+        let mut res = self
+            .participants
+            .iter()
+            .filter(|p| {
+                !p.camps.is_empty()
+                    && p.contests
+                        .iter()
+                        .map(|c| c.division)
+                        .max()
+                        .is_some_and(|d| d < Division::Platinum)
+            })
+            .collect::<Vec<_>>();
+        res.sort_unstable_by_key(|p| (p.camps.iter().map(|c| c.camp_year).min(), &p.id));
+
+        res
+    }
+
+    /// For each season in which students from `school` competed, the number of
+    /// them in each division, indexed by [`Division`] (so bronze first).
+    /// Students are counted once per season, in the highest division they
+    /// competed in that season. Seasons are in increasing order. Schools are
+    /// compared like in [`Self::school_class_ranking`].
+    ///
+    /// Schools are only listed on camp finalist pages, so this only counts
+    /// students who eventually went to camp.
+    pub fn school_division_history(&self, school: &str) -> Vec<(u16, [usize; 4])> {
+        let school = normalize_name(school);
+
+        let mut res = BTreeMap::<u16, [usize; 4]>::new();
+
+        for p in self
+            .participants
+            .iter()
+            .filter(|p| p.camps.iter().any(|c| normalize_name(&c.school) == school))
+        {
+            let mut highest = BTreeMap::new();
+            for c in &p.contests {
+                let division = highest.entry(c.contest_time.season()).or_insert(c.division);
+                *division = (*division).max(c.division);
+            }
+
+            for (season, division) in highest {
+                res.entry(season).or_default()[division as usize] += 1;
+            }
+        }
+
+        res.into_iter().collect()
+    }
+
+    /// Participants from `country`, which can be a code like "CAN" or a full
+    /// name like "Canada" (see [`normalize_country`]). Participants are in
+    /// order of their peak: the highest division they competed in, then their
+    /// best score in it, both decreasing, with participants without contest
+    /// records last. Ties are broken by name and then graduation year.
+    pub fn query_country(&self, country: &str) -> Vec<&Participant> {
+        let country = normalize_country(country);
+
+        sorted_by_peak(
+            self.participants
+                .iter()
+                // countries in dbs saved before they were normalized might not be
+                .filter(|p| normalize_country(&p.id.country) == country),
+        )
+    }
+
+    /// Participants graduating high school in `year`, in the same order as
+    /// [`Self::query_country`]. Observers have no graduation year, so are never
+    /// included. Participants whose graduation year is likely a typo are still
+    /// included, and can be told apart with
+    /// [`Participant::has_implausible_graduation`].
+    pub fn query_graduation(&self, year: u16) -> Vec<&Participant> {
+        sorted_by_peak(
+            self.participants
+                .iter()
+                .filter(|p| p.id.graduation == Graduation::HighSchool { year }),
+        )
+    }
+
+    /// A summary of `participant`'s records, with the IOI and EGOI results
+    /// that could be theirs (as in [`NameQueryResult::profiles`]). Cheaper than
+    /// going through [`Self::query_name`] for each of many participants.
+    pub fn participant_summary(&self, participant: &Participant) -> NameSummary {
+        let name = normalize_name(&participant.id.name);
+        let matching = |records: &[IntlParticipant]| {
+            records
+                .iter()
+                .filter(|r| {
+                    intl_plausibly_matches(&participant.id, r)
+                        && intl_name_matches(&name, &normalize_name(&r.name))
+                })
+                .cloned()
+                .collect()
+        };
+
+        NameQueryResult {
+            participants: vec![participant.clone()],
+            ioi: matching(&self.intl_history.ioi),
+            egoi: matching(&self.intl_history.egoi),
+        }
+        .name_summary()
+    }
+
+    /// Campers from `school`, in order of their first camp from it, and then by
+    /// id. Since most schools are spelled several ways across finalists pages,
+    /// schools are compared by their words (see below), and `school` only
+    /// needs to be some of the words of the school, in order. So "Lynbrook"
+    /// and "lynbrook HS" both match "Lynbrook High School". This can also
+    /// match other schools, such as "Lynbrook Middle School", or every school
+    /// for a query like "high school".
+    ///
+    /// Words are compared case-insensitively, ignoring punctuation, and with
+    /// common abbreviations like "HS" for "High School" and "St." for "Saint"
+    /// spelled out. A query without any words matches nothing.
+    ///
+    /// Schools are only listed on camp finalist pages, so this only finds
+    /// campers.
+    pub fn query_school(&self, school: &str) -> Vec<&Participant> {
+        let query = school_words(school);
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let matches = |school: &str| {
+            // whether `query` is a subsequence of the school's words
+            let mut words = school_words(school).into_iter();
+            query.iter().all(|q| words.any(|w| w == *q))
+        };
+
+        let mut res = self
+            .participants
+            .iter()
+            .filter_map(|p| {
+                p.camps
+                    .iter()
+                    .filter(|c| matches(&c.school))
+                    .map(|c| c.camp_year)
+                    .min()
+                    .map(|first| (first, p))
+            })
+            .collect::<Vec<_>>();
+        res.sort_unstable_by(|(y1, p1), (y2, p2)| y1.cmp(y2).then_with(|| p1.id.cmp(&p2.id)));
+
+        res.into_iter().map(|(_, p)| p).collect()
+    }
+
+    /// Pairs of participants that might be the same person under different
+    /// ids, such as someone who took a contest as an observer, moved
+    /// countries, or had their name misspelled. Suggestions are meant to be
+    /// reviewed by a maintainer and turned into [`Correction`]s. They're
+    /// returned in decreasing order of confidence, with each pair's ids in
+    /// increasing order.
+    ///
+    /// The confidence is the similarity of the two names, lowered for each of
+    /// the graduation year and country that differ. Participants who took a
+    /// contest at the same time are never suggested, since one person can't
+    /// have two results in the same month. To keep this fast, only names
+    /// sharing at least one word are compared.
+    pub fn suggest_merges(&self, config: MergeConfig) -> Vec<MergeSuggestion> {
+        let names = self
+            .participants
+            .iter()
+            .map(|p| normalize_name(&p.id.name))
+            .collect::<Vec<_>>();
+
+        let mut by_word = HashMap::<&str, Vec<usize>>::new();
+        for (i, name) in names.iter().enumerate() {
+            for word in name.split(' ').collect::<HashSet<_>>() {
+                by_word.entry(word).or_default().push(i);
+            }
+        }
+
+        // every pair of indices that share a word, with the smaller one first
+        let candidates = by_word
+            .values()
+            .flat_map(|indices| {
+                indices
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(j, &a)| indices[j + 1..].iter().map(move |&b| (a, b)))
+            })
+            .collect::<HashSet<_>>();
+
+        let mut res = vec![];
+        for (a, b) in candidates {
+            let (p1, p2) = (&self.participants[a], &self.participants[b]);
+
+            let same_graduation = p1.id.graduation == p2.id.graduation;
+            let same_country = p1.id.country == p2.id.country;
+            if (config.require_same_graduation && !same_graduation)
+                || (config.require_same_country && !same_country)
+            {
+                continue;
+            }
+
+            let similarity = strsim::jaro_winkler(&names[a], &names[b]);
+            if similarity < config.min_name_similarity {
+                continue;
+            }
+
+            let times = p1
+                .contests
+                .iter()
+                .map(|c| c.contest_time)
+                .collect::<HashSet<_>>();
+            if p2.contests.iter().any(|c| times.contains(&c.contest_time)) {
+                continue;
+            }
+
+            let mut confidence = similarity;
+            if !same_graduation {
+                confidence *= MERGE_MISMATCH_PENALTY;
+            }
+            if !same_country {
+                confidence *= MERGE_MISMATCH_PENALTY;
+            }
+
+            let (first, second) = if p1.id < p2.id {
+                (&p1.id, &p2.id)
+            } else {
+                (&p2.id, &p1.id)
+            };
+            res.push(MergeSuggestion {
+                first: first.clone(),
+                second: second.clone(),
+                confidence,
+            });
+        }
+
+        res.sort_unstable_by(|s1, s2| {
+            s2.confidence
+                .total_cmp(&s1.confidence)
+                .then_with(|| (&s1.first, &s1.second).cmp(&(&s2.first, &s2.second)))
+        });
+
+        res
+    }
+
+    /// Contests whose results page likely failed to scrape or is incomplete,
+    /// using the default thresholds. See
+    /// [`Self::suspicious_contest_gaps_with`].
+    pub fn suspicious_contest_gaps(&self) -> Vec<(MonthYear, Division)> {
+        self.suspicious_contest_gaps_with(5, 0.5)
+    }
+
+    /// Contests whose results page likely failed to scrape or is incomplete,
+    /// in increasing order of time and division.
+    ///
+    /// A participant is a regular of a contest if they competed in its
+    /// division both earlier and later in the same season, and they're absent
+    /// if they have no record at all from the contest's month. A contest is
+    /// flagged if it has at least `min_regulars` regulars, and at least
+    /// `min_absent_fraction` of them are absent. Regulars normally have no
+    /// reason to skip a contest, so this catches pages that are missing
+    /// entirely (as long as other divisions held a contest that month) as well
+    /// as partially scraped ones.
+    ///
+    /// Raising `min_regulars` ignores smaller divisions, where a few people
+    /// skipping is enough to set this off. Since only the regulars who are
+    /// listed count, this can't say anything about seasons where only
+    /// promoters are listed.
+    pub fn suspicious_contest_gaps_with(
+        &self,
+        min_regulars: usize,
+        min_absent_fraction: f64,
+    ) -> Vec<(MonthYear, Division)> {
+        let times = self
+            .participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .map(|c| c.contest_time)
+            .collect::<BTreeSet<_>>();
+
+        // (regulars, absent regulars) per contest
+        let mut counts = BTreeMap::<(MonthYear, Division), (usize, usize)>::new();
+
+        for p in &self.participants {
+            let competed = p
+                .contests
+                .iter()
+                .map(|c| c.contest_time)
+                .collect::<HashSet<_>>();
+
+            // first and last contest in each division of each season
+            let mut spans = HashMap::<(u16, Division), (MonthYear, MonthYear)>::new();
+            for c in &p.contests {
+                spans
+                    .entry((c.contest_time.season(), c.division))
+                    .and_modify(|(first, last)| {
+                        *first = (*first).min(c.contest_time);
+                        *last = (*last).max(c.contest_time);
+                    })
+                    .or_insert((c.contest_time, c.contest_time));
+            }
+
+            for ((_, division), (first, last)) in spans {
+                for &time in times.range(first..=last) {
+                    if time == first || time == last {
+                        continue;
+                    }
+
+                    let (regulars, absent) = counts.entry((time, division)).or_default();
+                    *regulars += 1;
+                    if !competed.contains(&time) {
+                        *absent += 1;
+                    }
+                }
+            }
+        }
+
+        counts
+            .into_iter()
+            .filter(|&(_, (regulars, absent))| {
+                regulars >= min_regulars && absent as f64 >= min_absent_fraction * regulars as f64
+            })
+            .map(|(contest, _)| contest)
+            .collect()
+    }
+
+    /// Every season's competitiveness using the default
+    /// [`CompetitivenessWeights`], most competitive first. See
+    /// [`Self::season_competitiveness_with`].
+    pub fn season_competitiveness(&self) -> Vec<(u16, f64)> {
+        self.season_competitiveness_with(CompetitivenessWeights::default())
+    }
+
+    /// Every season's competitiveness, most competitive first. This is
+    ///
+    /// `participants * (distinct participants) + top_score * (average top
+    /// score)`
+    ///
+    /// using `weights`, where the average top score is the mean over all of
+    /// the season's contests (in every division) of the mean of the contest's
+    /// `top_n` highest scores. Contests with fewer than `top_n` participants
+    /// average all of them.
+    ///
+    /// Like percentiles, this only counts participants listed on the results
+    /// pages, so recent seasons have fewer participants than in reality.
+    pub fn season_competitiveness_with(&self, weights: CompetitivenessWeights) -> Vec<(u16, f64)> {
+        let mut participants = BTreeMap::<u16, HashSet<&ParticipantId>>::new();
+        let mut top_scores = BTreeMap::<u16, Vec<f64>>::new();
+
+        for ((time, _), mut records) in self.by_contest() {
+            let season = time.season();
+
+            participants
+                .entry(season)
+                .or_default()
+                .extend(records.iter().map(|&(id, _)| id));
+
+            records.sort_unstable_by(|(_, s1), (_, s2)| s2.cmp(s1));
+            let top = &records[..records.len().min(weights.top_n)];
+            if !top.is_empty() {
+                let average = top.iter().map(|&(_, s)| s as f64).sum::<f64>() / top.len() as f64;
+                top_scores.entry(season).or_default().push(average);
+            }
+        }
+
+        let mut res = participants
+            .into_iter()
+            .map(|(season, ids)| {
+                let averages = top_scores
+                    .get(&season)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let top_score = if averages.is_empty() {
+                    0.
+                } else {
+                    averages.iter().sum::<f64>() / averages.len() as f64
+                };
+
+                (
+                    season,
+                    weights.participants * ids.len() as f64 + weights.top_score * top_score,
+                )
+            })
+            .collect::<Vec<_>>();
+        res.sort_by(|(y1, c1), (y2, c2)| c2.total_cmp(c1).then(y1.cmp(y2)));
+
+        res
+    }
+
+    /// The time of the most recent contest with bronze or silver results, or
+    /// `None` if there are none.
+    ///
+    /// Complete results were released through the 2013-14 season. Since
+    /// 2014-15, bronze and silver results pages only list the people who
+    /// promoted, and since 2020-21 USACO hasn't released them at all.
+    /// Rather than hardcoding that, we take the latest contest that has any
+    /// such records, since their pages are simply missing afterwards. People
+    /// who competed after this may be missing promotions. If USACO ever
+    /// releases them again, this will move forward on its own.
+    pub fn promotion_data_cutoff(&self) -> Option<MonthYear> {
+        self.promotion_data_cutoff
+    }
+
+    /// The `limit` participants who went from bronze to platinum in the fewest
+    /// contests, along with that number, fewest first. This counts contests
+    /// from their first bronze contest up to (but not including) their first
+    /// platinum contest. Only participants who competed in both bronze and
+    /// platinum are included.
+    pub fn fastest_climbers(&self, limit: usize) -> Vec<(&ParticipantId, usize)> {
+        let mut res = self
+            .participants
+            .iter()
+            .filter_map(|p| {
+                let mut contests = p.contests.iter().collect::<Vec<_>>();
+                contests.sort_by_key(|c| (c.contest_time, c.division));
+
+                let start = contests
+                    .iter()
+                    .position(|c| c.division == Division::Bronze)?;
+                let end = contests
+                    .iter()
+                    .position(|c| c.division == Division::Platinum)?;
+
+                (start < end).then_some((&p.id, end - start))
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(id1, c1), (id2, c2)| c1.cmp(c2).then_with(|| id1.cmp(id2)));
+        res.truncate(limit);
+
+        res
+    }
+
+    /// `a`'s score minus `b`'s in the contest in `division` at `time`, or
+    /// `None` if either of them didn't compete in it.
+    pub fn score_gap(
+        &self,
+        a: &ParticipantId,
+        b: &ParticipantId,
+        time: MonthYear,
+        division: Division,
+    ) -> Option<i32> {
+        let score = |id| {
+            self.get(id)?
+                .contests
+                .iter()
+                .find(|c| c.contest_time == time && c.division == division)
+                .map(|c| c.score as i32)
+        };
+
+        Some(score(a)? - score(b)?)
+    }
+
+    /// Every distinct high school graduation year in the database, in
+    /// ascending order. Observers are left out.
+    pub fn graduation_years(&self) -> Vec<u16> {
+        self.participants
+            .iter()
+            .filter_map(|p| match p.id.graduation {
+                Graduation::HighSchool { year } => Some(year),
+                Graduation::Observer => None,
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Participants whose names look like mis-decoded UTF-8, such as "JosÃ©"
+    /// instead of "José". These usually need a [`Correction`] or a re-scrape.
+    ///
+    /// Names are flagged if they contain "â€", or "Ã" or "Â" followed by a
+    /// character that a UTF-8 continuation byte decodes to in Windows-1252.
+    pub fn suspect_encoding_names(&self) -> Vec<&ParticipantId> {
+        self.participants
+            .iter()
+            .filter(|p| looks_mis_decoded(&p.id.name))
+            .map(|p| &p.id)
+            .collect()
+    }
+
+    /// Edges between every pair of participants who took at least
+    /// `min_shared` contests together, weighted by the number of contests they
+    /// shared. Participants only count as having taken a contest together if
+    /// they were in the same division. Edges are sorted by decreasing weight,
+    /// and each pair only appears once.
+    ///
+    /// This looks at every pair of participants in each contest, which is
+    /// quadratic in contest size (on the order of a million pairs for a large
+    /// bronze contest), so don't call this on every query.
+    pub fn cocompetition_graph(
+        &self,
+        min_shared: usize,
+    ) -> Vec<(ParticipantId, ParticipantId, usize)> {
+        let mut contests = HashMap::<_, Vec<usize>>::new();
+        for (i, p) in self.participants.iter().enumerate() {
+            for c in &p.contests {
+                let participants = contests.entry((c.contest_time, c.division)).or_default();
+                // a participant may have multiple records in the same contest
+                if participants.last() != Some(&i) {
+                    participants.push(i);
+                }
+            }
+        }
+
+        let mut shared = HashMap::<_, usize>::new();
+        for participants in contests.values() {
+            for (j, &a) in participants.iter().enumerate() {
+                for &b in &participants[..j] {
+                    *shared.entry((b, a)).or_default() += 1;
+                }
+            }
+        }
+
+        let mut res = shared
+            .into_iter()
+            .filter(|&(_, count)| count >= min_shared.max(1))
+            .map(|((a, b), count)| {
+                let (a, b) = (&self.participants[a].id, &self.participants[b].id);
+                let (a, b) = if a <= b { (a, b) } else { (b, a) };
+                (a.clone(), b.clone(), count)
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by(|(a1, b1, c1), (a2, b2, c2)| {
+            c2.cmp(c1).then_with(|| (a1, b1).cmp(&(a2, b2)))
+        });
+
+        res
+    }
+
+    /// Participants who scored poorly early on but excelled later, using the
+    /// default [`RedemptionThresholds`]. See [`Self::redemption_stories_with`].
+    pub fn redemption_stories(&self) -> Vec<&Participant> {
+        self.redemption_stories_with(RedemptionThresholds::default())
+    }
+
+    /// Participants whose first `early_contests` contests were all at or below
+    /// `low_percentile`, and who afterwards either placed at or above
+    /// `high_percentile` in some contest or reached platinum. Records without
+    /// a percentile never count as scoring poorly or excelling.
+    pub fn redemption_stories_with(&self, thresholds: RedemptionThresholds) -> Vec<&Participant> {
+        self.participants
+            .iter()
+            .filter(|p| {
+                if p.contests.len() <= thresholds.early_contests {
+                    return false;
+                }
+                // merged participants' records aren't necessarily in order
+                let mut contests = p.contests.iter().collect::<Vec<_>>();
+                contests.sort_by_key(|c| (c.contest_time, c.division));
+                let (early, later) = contests.split_at(thresholds.early_contests);
+
+                early
+                    .iter()
+                    .all(|c| c.percentile.is_some_and(|x| x <= thresholds.low_percentile))
+                    && later.iter().any(|c| {
+                        c.division == Division::Platinum
+                            || c.percentile
+                                .is_some_and(|x| x >= thresholds.high_percentile)
+                    })
+            })
+            .collect()
+    }
+
+    /// Every (participant, contest, division) with more than one record, which
+    /// should never happen and indicates a bug in merging participants, such
+    /// as in [`Self::apply_corrections`]. Each is listed once, sorted by
+    /// participant and then contest.
+    pub fn find_duplicate_contest_records(&self) -> Vec<(&ParticipantId, MonthYear, Division)> {
+        let mut res = vec![];
+
+        for p in &self.participants {
+            let mut seen = HashSet::new();
+            let mut duplicates = BTreeSet::new();
+            for c in &p.contests {
+                if !seen.insert((c.contest_time, c.division)) {
+                    duplicates.insert((c.contest_time, c.division));
+                }
+            }
+
+            res.extend(
+                duplicates
+                    .into_iter()
+                    .map(|(time, division)| (&p.id, time, division)),
+            );
+        }
+
+        res.sort_unstable();
+        res
+    }
+
+    /// The `limit` contests in `division` where first and second place were
+    /// closest, along with the gap between their scores. Sorted by the gap in
+    /// increasing order, and then by time. Contests with fewer than two
+    /// participants are left out.
+    ///
+    /// Ties for first count as a gap of 0. Since recent results pages only
+    /// list those who promoted, this is usually between perfect scores.
+    pub fn closest_finishes(&self, division: Division, limit: usize) -> Vec<(MonthYear, u16)> {
+        let mut res = self
+            .by_contest()
+            .into_iter()
+            .filter(|&((_, d), _)| d == division)
+            .filter_map(|((time, _), mut records)| {
+                records.sort_unstable_by_key(|&(_, score)| std::cmp::Reverse(score));
+                match *records {
+                    [(_, first), (_, second), ..] => Some((time, first - second)),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_unstable_by_key(|&(time, gap)| (gap, time));
+        res.truncate(limit);
+        res
+    }
+
+    /// The records in `new` that aren't in this db, such as the results of a
+    /// contest released since. Contest records are matched by contest and
+    /// division, and camp records by year, so rescored contests don't show
+    /// up. Records removed in `new` aren't reported.
+    pub fn diff(&self, new: &UsacoDb) -> DbDiff {
+        let old = self
+            .participants
+            .iter()
+            .map(|p| (&p.id, p))
+            .collect::<HashMap<_, _>>();
+
+        let participants = new
+            .participants
+            .iter()
+            .filter_map(|p| {
+                let Some(old) = old.get(&p.id) else {
+                    return Some(p.clone());
+                };
+                let old_contests = old
+                    .contests
+                    .iter()
+                    .map(|c| (c.contest_time, c.division))
+                    .collect::<HashSet<_>>();
+                let old_camps = old
+                    .camps
+                    .iter()
+                    .map(|c| c.camp_year)
+                    .collect::<HashSet<_>>();
+
+                let added = Participant {
+                    id: p.id.clone(),
+                    contests: p
+                        .contests
+                        .iter()
+                        .filter(|c| !old_contests.contains(&(c.contest_time, c.division)))
+                        .cloned()
+                        .collect(),
+                    camps: p
+                        .camps
+                        .iter()
+                        .filter(|c| !old_camps.contains(&c.camp_year))
+                        .cloned()
+                        .collect(),
+                };
+                (!added.contests.is_empty() || !added.camps.is_empty()).then_some(added)
+            })
+            .collect();
+
+        DbDiff { participants }
+    }
+
+    /// The score distribution of a contest as `(bucket_start, count)` pairs,
+    /// where bucket `start` counts the scores from `start` to
+    /// `start + bucket_size - 1`. Buckets go from 0 up to the one with the
+    /// highest score, including empty ones in between. A `bucket_size` of 0 is
+    /// treated as 1. `None` if there are no records of the contest.
+    pub fn score_histogram(
+        &self,
+        time: MonthYear,
+        division: Division,
+        bucket_size: u16,
+    ) -> Option<Vec<(u16, usize)>> {
+        let bucket_size = bucket_size.max(1);
+        let records = self.by_contest().remove(&(time, division))?;
+
+        let max_bucket = records.iter().map(|&(_, s)| s / bucket_size).max()?;
+        let mut counts = vec![0; max_bucket as usize + 1];
+        for (_, score) in records {
+            counts[(score / bucket_size) as usize] += 1;
+        }
+
+        Some(
+            counts
+                .into_iter()
+                .enumerate()
+                .map(|(i, count)| (i as u16 * bucket_size, count))
+                .collect(),
+        )
+    }
+}
+
+impl Default for UsacoDb {
+    fn default() -> Self {
+        Self::new(
+            vec![],
+            IntlHistory {
+                ioi: vec![],
+                egoi: vec![],
+            },
+        )
+    }
+}
+
+/// How much less likely two participants are to be the same person for each
+/// part of their ids, besides their names, that differ.
+const MERGE_MISMATCH_PENALTY: f64 = 0.8;
+
+/// Gets rid of the preferred names that are in parentheses in IOI and EGOI
+/// results. Each group of parentheses is removed on its own, so that the
+/// names between them are kept.
+fn strip_preferred_names(intl_history: &mut IntlHistory) {
+    let re = Regex::new(r#"\([^()]*\) "#).unwrap();
+
+    for comp in [&mut intl_history.ioi, &mut intl_history.egoi] {
+        for participant in comp {
+            participant.name = re.replace_all(&participant.name, "").to_string();
+        }
+    }
+}
+
+impl From<UsacoData> for UsacoDb {
+    /// [`UsacoDb::from_data`] with the default [`GraduationMergeConfig`].
+    fn from(value: UsacoData) -> Self {
+        Self::from_data(value, GraduationMergeConfig::default())
+    }
+}
+
+impl UsacoDb {
+    /// Builds a db from scraped data, merging participants whose graduation
+    /// year was likely corrected according to `merge_config` (see
+    /// [`Self::merge_graduation_corrections`]).
+    pub fn from_data(mut value: UsacoData, merge_config: GraduationMergeConfig) -> Self {
+        let mut participants = HashMap::new();
+
+        for contest in value.contests {
+            let ranks = placements(&contest);
+            let contest_size = contest.participants.len() as u32;
+            let mut scores = contest
+                .participants
+                .iter()
+                .map(|p| p.score)
+                .collect::<Vec<_>>();
+            scores.sort_unstable();
+
+            for (p, rank) in contest.participants.into_iter().zip(ranks) {
+                let id = ParticipantId::from(p.clone());
+                let percentile =
+                    scores.partition_point(|&s| s <= p.score) as f32 / scores.len() as f32 * 100.;
+                let solved = p
+                    .submission_results
+                    .iter()
+                    .map(|r| {
+                        matches!(r, Some(r) if !r.is_empty() && r.iter().all(|&t| t == TestcaseResult::Correct))
+                    })
+                    .collect();
+
+                participants
+                    .entry(id.clone())
+                    .or_insert_with(|| Participant {
+                        id,
+                        contests: vec![],
+                        camps: vec![],
+                    })
+                    .contests
+                    .push(ParticipantContestRecord {
+                        contest_time: contest.time,
+                        division: contest.division,
+                        score: p.score,
+                        percentile: Some(percentile),
+                        rank: Some((rank, contest_size)),
+                        solved,
+                    });
+            }
+        }
+
+        for camp in value.camps {
+            for p in camp.participants {
+                let id = ParticipantId::from(p.clone());
+
+                participants
+                    .entry(id.clone())
+                    .or_insert_with(|| Participant {
+                        id,
+                        contests: vec![],
+                        camps: vec![],
+                    })
+                    .camps
+                    .push(ParticipantCampRecord {
+                        camp_year: camp.year,
+                        school: p.school,
+                        state: p.state,
+                        is_egoi: p.is_egoi,
+                    });
+            }
+        }
+
+        strip_preferred_names(&mut value.intl_history);
+
+        let mut db = Self::new(participants.into_values().collect(), value.intl_history);
+        db.merge_graduation_corrections(merge_config);
+        db
+    }
+}
+
+/// Various statistics about the bot to be preserved across runs.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct AppStats {
+    /// Maps users to the number of times they have queried.
+    #[serde(default)]
+    pub users_queried: HashMap<UserId, usize>,
+    /// Amount of /search requests this bot has responded to.
+    #[serde(default)]
+    pub query_count: u32,
+    /// The number of participants in the database after each update, oldest
+    /// first. Only the latest [`MAX_PARTICIPANT_COUNTS`] are kept.
+    #[serde(default)]
+    pub participant_counts: Vec<(DateTime<Utc>, usize)>,
+    /// Maps searched names, normalized to lowercase without duplicate
+    /// whitespace, to the number of times they were searched. Only the
+    /// [`MAX_QUERY_COUNTS`] most searched names are kept.
+    #[serde(default)]
+    pub query_counts: HashMap<String, u32>,
+}
+
+/// How many entries of [`AppStats::participant_counts`] are kept.
+pub const MAX_PARTICIPANT_COUNTS: usize = 100;
+
+/// How many entries of [`AppStats::query_counts`] are kept. Most names are
+/// only searched a handful of times, so keeping every one would grow the stats
+/// forever for the sake of names that never show up in /trending.
+pub const MAX_QUERY_COUNTS: usize = 1000;
+
+/// The `limit` most searched names in `counts`, most searched first and then
+/// alphabetically.
+fn top_query_counts(
+    counts: impl Iterator<Item = (String, u32)>,
+    limit: usize,
+) -> Vec<(String, u32)> {
+    let mut res = counts.collect::<Vec<_>>();
+    res.sort_unstable_by(|(n1, c1), (n2, c2)| c2.cmp(c1).then_with(|| n1.cmp(n2)));
+    res.truncate(limit);
+
+    res
+}
+
+impl AppStats {
+    /// Adds the stats of `other` into these, for combining the stats of bot
+    /// instances that ran separately. Query counts are summed, including those
+    /// of users who queried both instances and of names searched on both,
+    /// keeping the [`MAX_QUERY_COUNTS`] most searched names. Participant counts
+    /// are interleaved by time, keeping the latest [`MAX_PARTICIPANT_COUNTS`].
+    pub fn merge(&mut self, other: AppStats) {
+        self.query_count += other.query_count;
+        for (user, count) in other.users_queried {
+            *self.users_queried.entry(user).or_default() += count;
+        }
+        for (name, count) in other.query_counts {
+            *self.query_counts.entry(name).or_default() += count;
+        }
+        if self.query_counts.len() > MAX_QUERY_COUNTS {
+            self.query_counts = top_query_counts(self.query_counts.drain(), MAX_QUERY_COUNTS)
+                .into_iter()
+                .collect();
+        }
+
+        self.participant_counts.extend(other.participant_counts);
+        self.participant_counts.sort_by_key(|&(time, _)| time);
+        let excess = self
+            .participant_counts
+            .len()
+            .saturating_sub(MAX_PARTICIPANT_COUNTS);
+        self.participant_counts.drain(..excess);
+    }
+}
+
+/// The live version of [`AppStats`], which can be updated concurrently without
+/// locking all of it. Only [`LiveStats::snapshot`]s are saved.
+#[derive(Default)]
+pub struct LiveStats {
+    users_queried: DashMap<UserId, usize>,
+    query_count: AtomicU32,
+    participant_counts: std::sync::Mutex<Vec<(DateTime<Utc>, usize)>>,
+    query_counts: DashMap<String, u32>,
+}
+
+impl LiveStats {
+    /// Records a /search request from `user`.
+    pub fn record_query(&self, user: UserId) {
+        self.query_count.fetch_add(1, Ordering::Relaxed);
+        *self.users_queried.entry(user).or_default() += 1;
+    }
+
+    /// Records a /search for `name`, which is normalized like in
+    /// [`UsacoDb::query_name`]. Blank names are ignored.
+    ///
+    /// Once twice [`MAX_QUERY_COUNTS`] names have been searched, all but the
+    /// most searched are forgotten, so that the pruning only happens every so
+    /// often.
+    pub fn record_name_query(&self, name: &str) {
+        let name = normalize_name(name);
+        if name.is_empty() {
+            return;
+        }
+        *self.query_counts.entry(name).or_default() += 1;
+
+        if self.query_counts.len() > 2 * MAX_QUERY_COUNTS {
+            let kept = self
+                .top_queried_names(MAX_QUERY_COUNTS)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<HashSet<_>>();
+            self.query_counts.retain(|name, _| kept.contains(name));
+        }
+    }
+
+    /// The `limit` most searched (normalized) names and how many times each
+    /// was searched, most searched first and then alphabetically.
+    pub fn top_queried_names(&self, limit: usize) -> Vec<(String, u32)> {
+        top_query_counts(
+            self.query_counts
+                .iter()
+                .map(|e| (e.key().clone(), *e.value())),
+            limit,
+        )
+    }
+
+    /// Amount of /search requests this bot has responded to.
+    pub fn query_count(&self) -> u32 {
+        self.query_count.load(Ordering::Relaxed)
+    }
+
+    /// Number of distinct users who have queried.
+    pub fn users_queried(&self) -> usize {
+        self.users_queried.len()
+    }
+
+    /// Records that the database had `count` participants at `time`, after an
+    /// update.
+    pub fn record_participant_count(&self, time: DateTime<Utc>, count: usize) {
+        let mut counts = self.participant_counts();
+        counts.push((time, count));
+
+        let excess = counts.len().saturating_sub(MAX_PARTICIPANT_COUNTS);
+        counts.drain(..excess);
+    }
+
+    /// How many participants were added between `since` and the latest
+    /// update. This compares against the latest count recorded at or before
+    /// `since`. `None` if fewer than two counts were recorded, or if every
+    /// count is newer than `since`, since the growth over the whole period
+    /// isn't known then.
+    pub fn participant_growth_since(&self, since: DateTime<Utc>) -> Option<i64> {
+        let counts = self.participant_counts();
+        if counts.len() < 2 {
+            return None;
+        }
+        let &(_, latest) = counts.last()?;
+        let &(_, before) = counts.iter().rev().find(|&&(time, _)| time <= since)?;
+
+        Some(latest as i64 - before as i64)
+    }
+
+    fn participant_counts(&self) -> std::sync::MutexGuard<'_, Vec<(DateTime<Utc>, usize)>> {
+        self.participant_counts
+            .lock()
+            .expect("participant counts lock shouldn't be poisoned")
+    }
+
+    /// The current stats, in their serializable form. Queries recorded while
+    /// this is running may or may not be included.
+    pub fn snapshot(&self) -> AppStats {
+        AppStats {
+            users_queried: self
+                .users_queried
+                .iter()
+                .map(|e| (*e.key(), *e.value()))
+                .collect(),
+            query_count: self.query_count(),
+            participant_counts: self.participant_counts().clone(),
+            query_counts: self
+                .top_queried_names(MAX_QUERY_COUNTS)
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+impl From<AppStats> for LiveStats {
+    fn from(value: AppStats) -> Self {
+        Self {
+            users_queried: value.users_queried.into_iter().collect(),
+            query_count: AtomicU32::new(value.query_count),
+            participant_counts: value.participant_counts.into(),
+            query_counts: value.query_counts.into_iter().collect(),
+        }
+    }
+}
+
+/// A token bucket rate limiter for each user. Every user starts with
+/// `capacity` tokens and spends one per request, and tokens refill at a rate of
+/// `capacity` per `period`, up to `capacity`. Kept in memory only, so limits
+/// reset on restart.
+pub struct RateLimiter {
+    capacity: u32,
+    period: Duration,
+    /// The tokens each user had left at the time of their last request.
+    buckets: DashMap<UserId, (f64, Instant)>,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        Self {
+            capacity,
+            period,
+            buckets: DashMap::new(),
+        }
+    }
+
+    /// Spends one of `user`'s tokens on a request made at `now`. If they have
+    /// none left, nothing is spent, and the time until they get another token
+    /// is returned as the error.
+    pub fn check(&self, user: UserId, now: Instant) -> Result<(), Duration> {
+        let capacity = self.capacity as f64;
+        let per_token = self.period.as_secs_f64() / capacity;
+
+        let mut bucket = self.buckets.entry(user).or_insert((capacity, now));
+        let (tokens, last) = &mut *bucket;
+        *tokens = (*tokens + now.saturating_duration_since(*last).as_secs_f64() / per_token)
+            .min(capacity);
+        *last = now;
+
+        if *tokens >= 1. {
+            *tokens -= 1.;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1. - *tokens) * per_token))
+        }
+    }
+}
+
+/// The names each user wants to be told about new records for, managed with
+/// the watch and unwatch commands. Names are stored normalized, and match
+/// participants the same way [`UsacoDb::query_name`] does.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchList {
+    watched: HashMap<UserId, BTreeSet<String>>,
+}
+
+/// How many names each user can watch at once.
+pub const MAX_WATCHED_NAMES: usize = 25;
+
+impl WatchList {
+    /// Adds `name` to `user`'s watch list. Returns whether it was newly added,
+    /// which is false if they already watched it or are watching
+    /// [`MAX_WATCHED_NAMES`] names already.
+    pub fn watch(&mut self, user: UserId, name: &str) -> bool {
+        let names = self.watched.entry(user).or_default();
+        if names.len() >= MAX_WATCHED_NAMES {
+            return false;
+        }
+
+        names.insert(normalize_name(name))
+    }
+
+    /// Removes `name` from `user`'s watch list. Returns whether they were
+    /// watching it.
+    pub fn unwatch(&mut self, user: UserId, name: &str) -> bool {
+        let Entry::Occupied(mut names) = self.watched.entry(user) else {
+            return false;
+        };
+
+        let removed = names.get_mut().remove(&normalize_name(name));
+        if names.get().is_empty() {
+            names.remove();
+        }
+
+        removed
+    }
+
+    /// The (normalized) names `user` is watching, in alphabetical order.
+    pub fn names(&self, user: UserId) -> Vec<&str> {
+        self.watched
+            .get(&user)
+            .into_iter()
+            .flatten()
+            .map(|n| n.as_str())
+            .collect()
+    }
+
+    /// The participants in `diff` each user is watching, for notifying them
+    /// after an update. Users with nothing new are left out, and the rest are
+    /// in order of id.
+    pub fn notifications<'a>(&self, diff: &'a DbDiff) -> Vec<(UserId, Vec<&'a Participant>)> {
+        let mut res = self
+            .watched
+            .iter()
+            .filter_map(|(&user, names)| {
+                let participants = diff
+                    .participants
+                    .iter()
+                    .filter(|p| names.contains(&p.id.name.to_lowercase()))
+                    .collect::<Vec<_>>();
+
+                (!participants.is_empty()).then_some((user, participants))
+            })
+            .collect::<Vec<_>>();
+        res.sort_unstable_by_key(|&(user, _)| user);
+
+        res
+    }
+}
+
+/// The data persisted by this bot.
+pub struct StoreData {
+    pub db: UsacoDb,
+    pub stats: AppStats,
+    /// Manually maintained fixes to apply to `db`. These are never saved by
+    /// the bot.
+    pub corrections: Vec<Correction>,
+    pub aliases: Aliases,
+    pub watch_list: WatchList,
+}
+
+/// Replaces the contents of the file at `path` with `contents`, such that the
+/// file has either its old or its new contents even if we crash partway
+/// through. The new contents are written to a temporary file next to `path`,
+/// flushed to disk, and then renamed over `path`, which is atomic as long as
+/// both are on the same filesystem.
+async fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> anyhow::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    tokio::fs::write(&tmp_path, contents).await?;
+    // otherwise the rename could reach the disk before the data does
+    tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(&tmp_path)
+        .await?
+        .sync_all()
+        .await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+
+    Ok(())
+}
+
+/// A very simple database that saves and loads from the filesystem.
+pub struct FileStore {
+    path: PathBuf,
+    compress: bool,
+}
+
+impl FileStore {
+    /// Creates a new file store that saves and loads its data from the given
+    /// `path`. `path` should point to a folder.
+    pub fn new_path(path: PathBuf) -> Self {
+        Self {
+            path,
+            compress: false,
+        }
+    }
+
+    /// Whether to gzip the db, which is by far the largest file, saving it as
+    /// `usaco-db.json.gz` instead of `usaco-db.json`. Either way, the other
+    /// file is loaded if the expected one is missing, and removed after the
+    /// next save, so this can be switched in either direction. Off by default.
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// The paths the db is saved to with and without compression, preferred
+    /// one first.
+    fn db_paths(&self) -> [PathBuf; 2] {
+        let plain = self.path.join("usaco-db.json");
+        let compressed = self.path.join("usaco-db.json.gz");
+
+        if self.compress {
+            [compressed, plain]
+        } else {
+            [plain, compressed]
+        }
+    }
+
+    /// Attempts to load data from the path. Default values will be returned if
+    /// data fails to load, so for example a missing `corrections.json` means
+    /// no corrections are applied.
+    pub async fn load(&self) -> StoreData {
+        async fn load<T: DeserializeOwned + Default>(path: impl AsRef<Path>) -> T {
+            let path = path.as_ref();
+            let bytes = match tokio::fs::read(path).await {
+                Ok(bytes) => bytes,
+                // files are missing on the first run, and optional ones such as
+                // corrections.json may never exist
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Default::default(),
+                Err(e) => {
+                    error!("failed to load data from path {path:?} {e:?}");
+                    return Default::default();
+                }
+            };
+
+            (|| {
+                let data = if path.extension().is_some_and(|e| e == "gz") {
+                    let mut data = String::new();
+                    GzDecoder::new(bytes.as_slice()).read_to_string(&mut data)?;
+                    data
+                } else {
+                    String::from_utf8(bytes)?
+                };
+
+                Ok(serde_json::from_str::<T>(&data)?)
+            })()
+            .unwrap_or_else(|e: anyhow::Error| {
+                error!("failed to load data from path {path:?} {e:?}");
+                Default::default()
+            })
+        }
+
+        let [db_path, fallback] = self.db_paths();
+        // for when compression was just switched on or off
+        let db_path = match tokio::fs::try_exists(&db_path).await {
+            Ok(false) => fallback,
+            _ => db_path,
+        };
+
+        let (db, stats, corrections, aliases, watch_list) = tokio::join!(
+            load(db_path),
+            load(self.path.join("stats.json")),
+            load(self.path.join("corrections.json")),
+            load(self.path.join("aliases.json")),
+            load(self.path.join("watch-list.json"))
+        );
+
+        StoreData {
+            db,
+            stats,
+            corrections,
+            aliases,
+            watch_list,
+        }
+    }
+
+    /// Saves `db`. We require a mutable reference to prevent racing
+    /// the file system.
+    pub async fn save_db(&mut self, db: &UsacoDb) -> anyhow::Result<()> {
+        let [path, stale] = self.db_paths();
+
+        if self.compress {
+            let mut encoder = GzEncoder::new(vec![], Compression::default());
+            serde_json::to_writer(&mut encoder, &db)?;
+            write_atomic(&path, encoder.finish()?).await?;
+        } else {
+            write_atomic(&path, serde_json::to_string(&db)?).await?;
+        }
+
+        // so that it isn't loaded instead if compression is switched again
+        if let Err(e) = tokio::fs::remove_file(&stale).await {
+            if e.kind() != io::ErrorKind::NotFound {
+                warn!("failed to remove stale db at path {stale:?} {e:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves `stats`. We require a mutable reference to prevent racing
+    /// the file system.
+    pub async fn save_stats(&mut self, stats: &AppStats) -> anyhow::Result<()> {
+        write_atomic(
+            &self.path.join("stats.json"),
+            serde_json::to_string(&stats)?,
+        )
+        .await
+    }
+
+    /// Saves `watch_list`. We require a mutable reference to prevent racing
+    /// the file system.
+    pub async fn save_watch_list(&mut self, watch_list: &WatchList) -> anyhow::Result<()> {
+        write_atomic(
+            &self.path.join("watch-list.json"),
+            serde_json::to_string(&watch_list)?,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usaco_standings_scraper::{Camp, Contest, Month};
+
+    fn participant(
+        name: &str,
+        graduation: u16,
+        contests: &[(u16, Month, Division, u16)],
+    ) -> Participant {
+        Participant {
+            id: ParticipantId {
+                name: name.to_string(),
+                graduation: Graduation::HighSchool { year: graduation },
+                country: "USA".to_string(),
+            },
+            contests: contests
+                .iter()
+                .map(|&(year, month, division, score)| ParticipantContestRecord {
+                    contest_time: MonthYear { year, month },
+                    division,
+                    score,
+                    percentile: None,
+                    rank: None,
+                    solved: vec![],
+                })
+                .collect(),
+            camps: vec![],
+        }
+    }
+
+    fn camp(camp_year: u16) -> ParticipantCampRecord {
+        ParticipantCampRecord {
+            camp_year,
+            school: "Example High School".to_string(),
+            state: "CA".to_string(),
+            is_egoi: false,
+        }
+    }
+
+    fn intl(name: &str, year: u16, result: IntlMedal) -> IntlParticipant {
+        IntlParticipant {
+            year,
+            result,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_detect_promotions() {
+        let p = participant(
+            "A",
+            2025,
+            &[
+                (2022, Month::December, Division::Bronze, 500),
+                (2023, Month::January, Division::Silver, 800),
+                (2023, Month::February, Division::Silver, 700),
+                (2023, Month::December, Division::Silver, 1000),
+                // an extra record in a lower division isn't a promotion afterwards
+                (2024, Month::January, Division::Bronze, 900),
+                (2024, Month::January, Division::Gold, 300),
+            ],
+        );
+
+        assert_eq!(
+            detect_promotions(&p.contests),
+            [
+                MonthYear {
+                    year: 2023,
+                    month: Month::January
+                },
+                MonthYear {
+                    year: 2024,
+                    month: Month::January
+                },
+            ]
+        );
+        assert!(detect_promotions(&p.contests[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_detect_promotions_same_month() {
+        let p = participant(
+            "A",
+            2025,
+            &[
+                (2023, Month::December, Division::Silver, 1000),
+                (2024, Month::January, Division::Gold, 1000),
+                (2024, Month::January, Division::Platinum, 400),
+            ],
+        );
+
+        assert_eq!(
+            detect_promotions(&p.contests),
+            [MonthYear {
+                year: 2024,
+                month: Month::January
+            }]
+        );
+    }
+
+    #[test]
+    fn test_all_contests_chronological() {
+        let res = NameQueryResult {
+            participants: vec![
+                participant(
+                    "Jane Doe",
+                    2023,
+                    &[
+                        (2022, Month::January, Division::Gold, 300),
+                        (2021, Month::December, Division::Silver, 700),
+                    ],
+                ),
+                participant(
+                    "Jane Doe",
+                    2025,
+                    &[(2021, Month::December, Division::Bronze, 500)],
+                ),
+            ],
+            ioi: vec![],
+            egoi: vec![],
+        };
+
+        let contests = res
+            .all_contests_chronological()
+            .into_iter()
+            .map(|(id, c)| (id.graduation, c.score))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contests,
+            [
+                (Graduation::HighSchool { year: 2025 }, 500),
+                (Graduation::HighSchool { year: 2023 }, 700),
+                (Graduation::HighSchool { year: 2023 }, 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filtered() {
+        let mut older = participant(
+            "Jane Doe",
+            2023,
+            &[
+                (2021, Month::December, Division::Silver, 700),
+                (2022, Month::January, Division::Gold, 300),
+            ],
+        );
+        older.camps.push(camp(2023));
+        let res = NameQueryResult {
+            participants: vec![
+                older,
+                participant(
+                    "Jane Doe",
+                    2025,
+                    &[(2021, Month::December, Division::Bronze, 500)],
+                ),
+            ],
+            ioi: vec![
+                intl("Jane Doe", 2022, IntlMedal::Gold),
+                intl("Jane Doe", 2023, IntlMedal::Silver),
+            ],
+            egoi: vec![],
+        };
+        let scores = |res: &NameQueryResult| {
+            res.participants
+                .iter()
+                .map(|p| p.contests.iter().map(|c| c.score).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        let unfiltered = res.clone().filtered(None, None);
+        assert_eq!(scores(&unfiltered), scores(&res));
+        assert_eq!(unfiltered.ioi.len(), 2);
+
+        // the younger Jane Doe has no gold records, so she's dropped
+        let gold = res.clone().filtered(Some(Division::Gold), None);
+        assert_eq!(scores(&gold), [vec![300]]);
+        assert_eq!(gold.participants[0].camps.len(), 1);
+        assert_eq!(gold.ioi.len(), 2);
+
+        let recent = res.clone().filtered(None, Some(2022..=2022));
+        assert_eq!(scores(&recent), [vec![300]]);
+        assert!(recent.participants[0].camps.is_empty());
+        assert_eq!(recent.ioi.len(), 1);
+        assert_eq!(recent.ioi[0].year, 2022);
+
+        // older Jane Doe is kept for her camp, despite having no contests left
+        let later = res.filtered(Some(Division::Bronze), Some(2023..=2024));
+        assert_eq!(scores(&later), [Vec::<u16>::new()]);
+        assert_eq!(later.participants[0].camps.len(), 1);
+        assert_eq!(later.ioi.len(), 1);
+    }
+
+    #[test]
+    fn test_query_name_intl() {
+        let db = UsacoDb::new(
+            vec![participant("Ben Qi", 2019, &[])],
+            IntlHistory {
+                ioi: vec![
+                    intl("Benjamin  Qi", 2018, IntlMedal::Gold),
+                    intl("Benjamin Q Qi", 2017, IntlMedal::Silver),
+                    intl("Jane Qi", 2017, IntlMedal::Silver),
+                    intl("Qi", 2017, IntlMedal::Silver),
+                ],
+                egoi: vec![],
+            },
+        );
+
+        let res = db.query_name("ben qi");
+        assert_eq!(res.participants.len(), 1);
+        assert_eq!(
+            res.ioi.iter().map(|p| p.year).collect::<Vec<_>>(),
+            [2017, 2018]
+        );
+
+        // contest records still need the exact name
+        let res = db.query_name("benjamin qi");
+        assert!(res.participants.is_empty());
+        assert_eq!(res.ioi.len(), 2);
+    }
+
+    #[test]
+    fn test_strip_preferred_names() {
+        let mut history = IntlHistory {
+            ioi: vec![
+                intl("Benjamin (Ben) Qi", 2018, IntlMedal::Gold),
+                intl("Jane (JJ) Mary (M) Doe", 2018, IntlMedal::Gold),
+            ],
+            egoi: vec![],
+        };
+        strip_preferred_names(&mut history);
+
+        assert_eq!(
+            history
+                .ioi
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Benjamin Qi", "Jane Mary Doe"]
+        );
+    }
+
+    #[test]
+    fn test_intl_name_matches() {
+        assert!(intl_name_matches("ben qi", "benjamin qi"));
+        assert!(intl_name_matches("alexander wei", "alex wei"));
+        assert!(intl_name_matches("jane doe", "jane mary doe"));
+
+        // a letter apart, or too short to be a nickname
+        assert!(!intl_name_matches("eric chen", "erica chen"));
+        assert!(!intl_name_matches("jo chen", "john chen"));
+        assert!(!intl_name_matches("ben qi", "benjamin chen"));
+    }
+
+    #[test]
+    fn test_usaco_for_intl() {
+        let db = db(vec![
+            participant("Ben Qi", 2019, &[]),
+            participant("Benjamin Qi", 2019, &[]),
+            participant("Jane Qi", 2019, &[]),
+        ]);
+        let names = |intl_name| {
+            db.usaco_for_intl(intl_name)
+                .into_iter()
+                .map(|p| p.id.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        // the exact match first, then the nickname
+        assert_eq!(names("Benjamin  QI"), ["Benjamin Qi", "Ben Qi"]);
+        assert_eq!(names("Jane Mary Qi"), ["Jane Qi"]);
+        assert!(names("John Smith").is_empty());
+    }
+
+    #[test]
+    fn test_intl_team() {
+        let db = UsacoDb::new(
+            vec![],
+            IntlHistory {
+                ioi: vec![
+                    intl("Jane Doe", 2017, IntlMedal::Gold),
+                    intl("John Smith", 2017, IntlMedal::VisaIssue),
+                    intl("Jane Doe", 2018, IntlMedal::Silver),
+                ],
+                egoi: vec![intl("Jane Roe", 2017, IntlMedal::Bronze)],
+            },
+        );
+        let team = |comp, year| {
+            db.intl_team(comp, year)
+                .into_iter()
+                .map(|r| (r.name, r.result))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            team(IntlCompetition::Ioi, 2017),
+            [
+                ("Jane Doe".to_string(), IntlMedal::Gold),
+                ("John Smith".to_string(), IntlMedal::VisaIssue),
+            ]
+        );
+        assert_eq!(
+            team(IntlCompetition::Egoi, 2017),
+            [("Jane Roe".to_string(), IntlMedal::Bronze)]
+        );
+        assert!(team(IntlCompetition::Egoi, 2018).is_empty());
+    }
+
+    #[test]
+    fn test_summary_card() {
+        let mut p = participant(
+            "Benjamin Qi",
+            2019,
+            &[
+                (2015, Month::December, Division::Gold, 1000),
+                (2016, Month::January, Division::Platinum, 933),
+            ],
+        );
+        p.camps.push(camp(2016));
+        let res = NameQueryResult {
+            participants: vec![p],
+            ioi: vec![
+                intl("Benjamin Qi", 2017, IntlMedal::Silver),
+                intl("Benjamin Qi", 2018, IntlMedal::Gold),
+            ],
+            egoi: vec![],
+        };
+
+        assert_eq!(res.name_summary().best_score, Some(933));
+
+        let card = res.summary_card(false);
+        assert_eq!(
+            card,
+            "\
++------------------------------+
+| Benjamin Qi                  |
++------------------------------+
+| People:                    1 |
+| Best division:      platinum |
+| Contests:                  2 |
+| Camps:                     1 |
+| IOI:                1G 1S 0B |
+| EGOI:                      - |
++------------------------------+"
+        );
+
+        let hidden = res.summary_card(true);
+        assert!(!hidden.contains("Benjamin Qi"));
+        assert!(hidden.contains("[name hidden]"));
+        assert_eq!(card.lines().count(), hidden.lines().count());
+        assert!(card.lines().all(|l| l.chars().count() == 32));
+    }
+
+    #[test]
+    fn test_comparison_table() {
+        let a = NameQueryResult {
+            participants: vec![participant(
+                "Jane Doe",
+                2025,
+                &[
+                    (2023, Month::January, Division::Gold, 800),
+                    (2023, Month::Open, Division::Platinum, 500),
+                ],
+            )],
+            ioi: vec![intl("Jane Doe", 2024, IntlMedal::Gold)],
+            egoi: vec![],
+        };
+        let b = NameQueryResult {
+            participants: vec![participant(
+                "John Smith",
+                2025,
+                &[(2023, Month::Open, Division::Silver, 1000)],
+            )],
+            ioi: vec![],
+            egoi: vec![],
+        };
+
+        assert_eq!(
+            comparison_table(&a, &b, false),
+            "               Jane Doe         John Smith
+Contests       2                1
+Best division  platinum         silver
+Best score     500              1000
+Camps          0                0
+IOI            1G 0S 0B         -
+EGOI           -                -"
+        );
+
+        let hidden = comparison_table(&a, &b, true);
+        assert!(!hidden.contains("Jane Doe") && !hidden.contains("John Smith"));
+        assert!(hidden.starts_with("               Person 1         Person 2\n"));
+    }
+
+    #[test]
+    fn test_contests_by_division() {
+        let p = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2022, Month::January, Division::Silver, 800),
+                (2021, Month::December, Division::Bronze, 1000),
+                (2022, Month::Open, Division::Gold, 500),
+                (2022, Month::February, Division::Gold, 400),
+                (2021, Month::December, Division::Silver, 300),
+            ],
+        );
+
+        let groups = p.contests_by_division();
+        let summarized = groups
+            .iter()
+            .map(|(&d, records)| (d, records.iter().map(|c| c.score).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summarized,
+            [
+                (Division::Bronze, vec![1000]),
+                (Division::Silver, vec![300, 800]),
+                (Division::Gold, vec![400, 500]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_peak_division_records() {
+        let p = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2022, Month::Open, Division::Gold, 500),
+                (2021, Month::December, Division::Silver, 1000),
+                (2022, Month::February, Division::Gold, 400),
+            ],
+        );
+        let scores = p
+            .peak_division_records()
+            .iter()
+            .map(|c| (c.division, c.score))
+            .collect::<Vec<_>>();
+        assert_eq!(scores, [(Division::Gold, 400), (Division::Gold, 500)]);
+
+        assert!(participant("John Doe", 2025, &[])
+            .peak_division_records()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_score_trajectory() {
+        let p = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2023, Month::January, Division::Gold, 600),
+                (2021, Month::December, Division::Bronze, 1000),
+                (2022, Month::January, Division::Silver, 500),
+                (2022, Month::February, Division::Silver, 700),
+                (2022, Month::December, Division::Gold, 400),
+            ],
+        );
+
+        assert_eq!(
+            p.score_trajectory(),
+            [(2022, Division::Silver, 700), (2023, Division::Gold, 600)]
+        );
+    }
+
+    #[test]
+    fn test_consistency() {
+        let p = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2021, Month::December, Division::Gold, 200),
+                (2022, Month::January, Division::Gold, 400),
+                (2022, Month::February, Division::Gold, 400),
+                (2022, Month::Open, Division::Gold, 400),
+                (2022, Month::December, Division::Gold, 500),
+                (2023, Month::January, Division::Gold, 500),
+                (2023, Month::February, Division::Gold, 700),
+                (2023, Month::Open, Division::Gold, 900),
+                (2021, Month::November, Division::Silver, 1000),
+            ],
+        );
+
+        // mean 500, variance 40000
+        assert_eq!(p.consistency(Division::Gold), Some(200.));
+        assert_eq!(p.consistency(Division::Silver), None);
+        assert_eq!(p.consistency(Division::Platinum), None);
+    }
+
+    #[test]
+    fn test_biggest_jump() {
+        let p = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2022, Month::February, Division::Silver, 300),
+                (2021, Month::December, Division::Silver, 500),
+                (2022, Month::January, Division::Silver, 600),
+                (2022, Month::Open, Division::Silver, 800),
+            ],
+        );
+        assert_eq!(
+            p.biggest_jump(),
+            Some((
+                MonthYear {
+                    year: 2022,
+                    month: Month::February
+                },
+                MonthYear {
+                    year: 2022,
+                    month: Month::Open
+                },
+                500
+            ))
+        );
+
+        let p = participant(
+            "John Doe",
+            2025,
+            &[
+                (2021, Month::December, Division::Silver, 500),
+                (2022, Month::January, Division::Silver, 400),
+            ],
+        );
+        assert_eq!(p.biggest_jump(), None);
+        assert_eq!(participant("John Doe", 2025, &[]).biggest_jump(), None);
+    }
+
+    fn db(participants: Vec<Participant>) -> UsacoDb {
+        UsacoDb::new(participants, UsacoDb::default().intl_history)
+    }
+
+    #[test]
+    fn test_get() {
+        let db = db(vec![
+            participant(
+                "Jane Doe",
+                2023,
+                &[(2022, Month::Open, Division::Gold, 300)],
+            ),
+            participant(
+                "Jane Doe",
+                2024,
+                &[(2022, Month::Open, Division::Silver, 500)],
+            ),
+        ]);
+
+        let mut id = db.participants[1].id.clone();
+        assert_eq!(db.get(&id).map(|p| p.contests[0].score), Some(500));
+
+        id.graduation = Graduation::HighSchool { year: 2025 };
+        assert!(db.get(&id).is_none());
+    }
+
+    #[test]
+    fn test_name_index() {
+        // what `query_name` did before it had an index
+        fn linear_scan(db: &UsacoDb, name: &str) -> Vec<ParticipantId> {
+            let name = normalize_name(name);
+            let mut res = db
+                .participants
+                .iter()
+                .filter(|p| p.id.name.to_lowercase() == name)
+                .map(|p| p.id.clone())
+                .collect::<Vec<_>>();
+            res.sort_unstable();
+            res
+        }
+        fn check(db: &UsacoDb, names: &[&str]) {
+            for name in names {
+                let indexed = db
+                    .query_name(name)
+                    .participants
+                    .into_iter()
+                    .map(|p| p.id)
+                    .collect::<Vec<_>>();
+                assert_eq!(indexed, linear_scan(db, name), "{name}");
+            }
+        }
+
+        let names = [
+            "Jane Doe",
+            "jane  DOE",
+            "Jane Roe",
+            "John Smith",
+            "Jane",
+            "Jose",
+            "José",
+            "",
+        ];
+        let mut db = db(vec![
+            participant(
+                "Jane Doe",
+                2023,
+                &[(2022, Month::Open, Division::Gold, 300)],
+            ),
+            participant(
+                "JANE DOE",
+                2024,
+                &[(2022, Month::Open, Division::Gold, 500)],
+            ),
+            participant(
+                "Jane Roe",
+                2024,
+                &[(2022, Month::Open, Division::Gold, 700)],
+            ),
+            participant("José", 2024, &[]),
+            // names on results pages aren't normalized, so this is never found
+            participant("Jane  Doe", 2025, &[]),
+        ]);
+        check(&db, &names);
+        assert_eq!(db.query_name("jane doe").participants.len(), 2);
+
+        db.apply_aliases(&Aliases::from([(
+            "Jane Roe".to_string(),
+            "John Smith".to_string(),
+        )]));
+        check(&db, &names);
+        assert_eq!(db.query_name("john smith").participants.len(), 1);
+        assert!(db.query_name("jane roe").participants.is_empty());
+
+        // the index isn't saved, so it has to be rebuilt when loading
+        let serialized = serde_json::to_string(&db).unwrap();
+        assert!(!serialized.contains("name_index"));
+        let loaded = serde_json::from_str::<UsacoDb>(&serialized).unwrap();
+        check(&loaded, &names);
+        assert_eq!(loaded.query_name("jane doe").participants.len(), 2);
+    }
+
+    #[test]
+    fn test_records_in_range() {
+        let db = db(vec![
+            participant(
+                "Jane Doe",
+                2023,
+                &[
+                    (2020, Month::Open, Division::Silver, 100),
+                    (2020, Month::December, Division::Gold, 200),
+                    (2022, Month::January, Division::Gold, 300),
+                ],
+            ),
+            participant(
+                "John Doe",
+                2024,
+                &[
+                    (2021, Month::December, Division::Bronze, 400),
+                    (2022, Month::Open, Division::Silver, 500),
+                    (2022, Month::December, Division::Silver, 600),
+                ],
+            ),
+        ]);
+
+        // the 2021-22 season
+        let records = db.records_in_range(
+            MonthYear {
+                year: 2021,
+                month: Month::December,
+            },
+            MonthYear {
+                year: 2022,
+                month: Month::Open,
+            },
+        );
+        let records = records
+            .iter()
+            .map(|(id, c)| (id.name.as_str(), c.score))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records,
+            [("John Doe", 400), ("Jane Doe", 300), ("John Doe", 500)]
+        );
+    }
+
+    #[test]
+    fn test_close_rivalries() {
+        // gold scores in jan, feb, and open of 2022
+        let gold = |scores: [u16; 3]| {
+            [Month::January, Month::February, Month::Open]
+                .into_iter()
+                .zip(scores)
+                .map(|(month, score)| (2022, month, Division::Gold, score))
+                .collect::<Vec<_>>()
+        };
+
+        let db = db(vec![
+            participant("Alice", 2024, &gold([700, 800, 900])),
+            participant("Bob", 2024, &gold([720, 790, 400])),
+            participant("Carol", 2024, &gold([100, 810, 880])),
+            // close scores, but in a different division
+            participant(
+                "Dan",
+                2024,
+                &[(2022, Month::January, Division::Silver, 700)],
+            ),
+        ]);
+
+        let names = |rivalries: Vec<(ParticipantId, ParticipantId, usize)>| {
+            rivalries
+                .into_iter()
+                .map(|(a, b, count)| (a.name, b.name, count))
+                .collect::<Vec<_>>()
+        };
+        let s = |s: &str| s.to_string();
+
+        assert_eq!(
+            names(db.close_rivalries(2, 20)),
+            [(s("Alice"), s("Bob"), 2), (s("Alice"), s("Carol"), 2),]
+        );
+        assert_eq!(
+            names(db.close_rivalries(1, 20)),
+            [
+                (s("Alice"), s("Bob"), 2),
+                (s("Alice"), s("Carol"), 2),
+                (s("Bob"), s("Carol"), 1),
+            ]
+        );
+        assert!(db.close_rivalries(3, 20).is_empty());
+    }
+
+    #[test]
+    fn test_corrections_and_aliases() {
+        let mut db = db(vec![
+            participant(
+                "Jane Doe",
+                2024,
+                &[(2021, Month::December, Division::Bronze, 800)],
+            ),
+            participant(
+                "Jane Doe",
+                2025,
+                &[(2022, Month::January, Division::Silver, 700)],
+            ),
+            participant(
+                "jane  DOE",
+                2024,
+                &[(2022, Month::February, Division::Silver, 900)],
+            ),
+            participant(
+                "Janie Doe",
+                2024,
+                &[(2022, Month::Open, Division::Gold, 600)],
+            ),
+        ]);
+        db.intl_history
+            .ioi
+            .push(intl("Janie Doe", 2024, IntlMedal::Gold));
+
+        db.apply_corrections(&[Correction {
+            from: participant("Jane Doe", 2025, &[]).id,
+            to: participant("Jane Doe", 2024, &[]).id,
+        }]);
+        assert_eq!(db.people_count(), 3);
+
+        db.apply_aliases(&Aliases::from([
+            ("JANIE doe".to_string(), "Jane Doe".to_string()),
+            ("Jane Doe".to_string(), "Jane Doe".to_string()),
+        ]));
+        assert_eq!(db.people_count(), 1);
+
+        let res = db.query_name("jane doe");
+        assert_eq!(res.participants.len(), 1);
+        assert_eq!(
+            res.participants[0]
+                .contests
+                .iter()
+                .map(|c| c.score)
+                .collect::<Vec<_>>(),
+            [800, 700, 900, 600]
+        );
+        assert_eq!(res.ioi.len(), 1);
+    }
+
+    #[test]
+    fn test_avg_contests_to_promote() {
+        let db = db(vec![
+            // promoted after 1 bronze contest
+            participant(
+                "A",
+                2024,
+                &[
+                    (2021, Month::December, Division::Bronze, 1000),
+                    (2022, Month::January, Division::Silver, 500),
+                ],
+            ),
+            // promoted after 3 bronze contests
+            participant(
+                "B",
+                2024,
+                &[
+                    (2021, Month::December, Division::Bronze, 300),
+                    (2022, Month::January, Division::Bronze, 500),
+                    (2022, Month::February, Division::Bronze, 900),
+                    (2022, Month::Open, Division::Silver, 200),
+                ],
+            ),
+            // never promoted
+            participant(
+                "C",
+                2024,
+                &[
+                    (2021, Month::December, Division::Bronze, 300),
+                    (2022, Month::January, Division::Bronze, 400),
+                ],
+            ),
+            // started in silver
+            participant("D", 2024, &[(2021, Month::December, Division::Silver, 300)]),
+        ]);
+
+        assert_eq!(
+            db.participants[1].contests_until_promotion(Division::Bronze),
+            Some(3)
+        );
+        assert_eq!(
+            db.participants[2].contests_until_promotion(Division::Bronze),
+            None
+        );
+        assert_eq!(
+            db.participants[3].contests_until_promotion(Division::Bronze),
+            None
+        );
+
+        assert_eq!(db.avg_contests_to_promote(Division::Bronze), Some(2.));
+        assert_eq!(db.avg_contests_to_promote(Division::Silver), None);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let mut younger = participant(
+            "Alex Chen",
+            2023,
+            &[
+                (2021, Month::Open, Division::Platinum, 900),
+                (2020, Month::December, Division::Gold, 1000),
+            ],
+        );
+        younger.camps.push(camp(2021));
+        let older = participant(
+            "Alex Chen",
+            2019,
+            &[(2016, Month::January, Division::Bronze, 500)],
+        );
+
+        let res = NameQueryResult {
+            participants: vec![older, younger],
+            ioi: vec![intl("Alex Chen", 2021, IntlMedal::Gold)],
+            egoi: vec![],
+        };
+
+        let profiles = res.profiles();
+        assert_eq!(profiles.len(), 2);
+
+        let older = &profiles[0];
+        assert_eq!(older.id.graduation, Graduation::HighSchool { year: 2019 });
+        assert_eq!(older.contests.len(), 1);
+        assert_eq!(older.contests[0].grade, Some(9));
+        assert!(older.camps.is_empty());
+        // graduated before IOI 2021
+        assert!(older.ioi.is_empty());
+        assert_eq!(older.summary.best_division, Some(Division::Bronze));
+
+        let younger = &profiles[1];
+        assert_eq!(
+            younger
+                .contests
+                .iter()
+                .map(|c| (c.record.score, c.grade))
+                .collect::<Vec<_>>(),
+            [(1000, Some(10)), (900, Some(10))]
+        );
+        assert_eq!(younger.camps[0].grade, Some(10));
+        assert_eq!(younger.camps[0].record.school, "Example High School");
+        assert_eq!(younger.ioi.len(), 1);
+        assert_eq!(younger.summary.ioi.gold, 1);
+        assert_eq!(younger.summary.people, 1);
+    }
+
+    #[test]
+    fn test_contest_percentiles() {
+        let contestant = |name: &str, score| ContestParticipant {
+            country: "USA".to_string(),
+            graduation: Graduation::HighSchool { year: 2025 },
+            name: name.to_string(),
+            score,
+            submission_results: vec![],
+            submission_times: vec![],
+            problem_scores: vec![],
+        };
+        let data = UsacoData {
+            contests: vec![Contest {
+                time: MonthYear {
+                    year: 2024,
+                    month: Month::Open,
+                },
+                division: Division::Gold,
+                participants: vec![
+                    contestant("A", 1000),
+                    contestant("B", 500),
+                    contestant("C", 500),
+                    contestant("D", 100),
+                ],
+                problems: vec![],
+                division_mismatch: false,
+            }],
+            camps: vec![],
+            intl_history: IntlHistory {
+                ioi: vec![],
+                egoi: vec![],
+            },
+        };
+
+        let db = UsacoDb::from(data);
+        let percentile = |name| db.query_name(name).participants[0].contests[0].percentile;
+        assert_eq!(percentile("A"), Some(100.));
+        assert_eq!(percentile("B"), Some(75.));
+        assert_eq!(percentile("C"), Some(75.));
+        assert_eq!(percentile("D"), Some(25.));
+
+        let rank = |name| db.query_name(name).participants[0].contests[0].rank;
+        assert_eq!(rank("A"), Some((1, 4)));
+        assert_eq!(rank("C"), Some((2, 4)));
+        assert_eq!(rank("D"), Some((4, 4)));
+    }
+
+    #[test]
+    fn test_camp_roster() {
+        let finalist = |name: &str, is_egoi| CampParticipant {
+            graduation_year: 2025,
+            name: name.to_string(),
+            school: "Example High School".to_string(),
+            state: "CA".to_string(),
+            is_egoi,
+        };
+        let data = UsacoData {
+            contests: vec![],
+            camps: vec![
+                Camp {
+                    year: 2024,
+                    participants: vec![finalist("John Smith", false), finalist("Jane Doe", true)],
+                },
+                Camp {
+                    year: 2023,
+                    participants: vec![finalist("Jane Doe", false)],
+                },
+            ],
+            intl_history: IntlHistory {
+                ioi: vec![],
+                egoi: vec![],
+            },
+        };
+
+        let db = UsacoDb::from(data);
+        let roster = |year| {
+            db.camp_roster(year)
+                .into_iter()
+                .map(|(id, c)| (id.name.as_str(), c.is_egoi))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(roster(2024), [("Jane Doe", true), ("John Smith", false)]);
+        assert_eq!(roster(2023), [("Jane Doe", false)]);
+        assert!(roster(2022).is_empty());
+    }
+
+    #[test]
+    fn test_hardest_problems() {
+        use TestcaseResult::{Correct, WrongAnswer};
+
+        let contestant = |name: &str, submission_results| ContestParticipant {
+            country: "USA".to_string(),
+            graduation: Graduation::HighSchool { year: 2025 },
+            name: name.to_string(),
+            score: 0,
+            submission_results,
+            submission_times: vec![],
+            problem_scores: vec![],
+        };
+        let contest = |month, participants| Contest {
+            time: MonthYear { year: 2024, month },
+            division: Division::Gold,
+            participants,
+            problems: vec![],
+            division_mismatch: false,
+        };
+        let data = UsacoData {
+            contests: vec![
+                contest(
+                    Month::January,
+                    vec![
+                        contestant("A", vec![Some(vec![Correct, Correct]), Some(vec![Correct])]),
+                        contestant("B", vec![Some(vec![Correct, WrongAnswer]), None]),
+                    ],
+                ),
+                contest(
+                    Month::Open,
+                    vec![
+                        contestant("A", vec![Some(vec![Correct]), Some(vec![Correct])]),
+                        contestant("B", vec![Some(vec![Correct]), Some(vec![WrongAnswer])]),
+                        contestant("C", vec![Some(vec![Correct]), None]),
+                        contestant("D", vec![None, Some(vec![Correct])]),
+                    ],
+                ),
+            ],
+            camps: vec![],
+            intl_history: IntlHistory {
+                ioi: vec![],
+                egoi: vec![],
+            },
+        };
+
+        let db = UsacoDb::from(data);
+        let month = |month| MonthYear { year: 2024, month };
+        assert_eq!(
+            db.hardest_problems(Division::Gold, 3),
+            [
+                (month(Month::January), 0, 0.5),
+                (month(Month::January), 1, 0.5),
+                (month(Month::Open), 1, 0.5),
+            ]
+        );
+        assert!(db.hardest_problems(Division::Silver, 3).is_empty());
+    }
+
+    #[test]
+    fn test_most_decorated() {
+        let plat = |months: &[Month]| {
+            months
+                .iter()
+                .map(|&month| (2022, month, Division::Platinum, 500))
+                .collect::<Vec<_>>()
+        };
+
+        let mut medalist = participant("Medalist", 2023, &plat(&[Month::January, Month::Open]));
+        medalist.camps.push(camp(2022));
+        let grinder = participant(
+            "Grinder",
+            2023,
+            &plat(&[Month::January, Month::February, Month::Open]),
+        );
+        let bronze_only = participant(
+            "Someone",
+            2023,
+            &[(2022, Month::Open, Division::Bronze, 500)],
+        );
+
+        let mut db = db(vec![grinder, bronze_only, medalist]);
+        db.intl_history
+            .ioi
+            .push(intl("medalist", 2022, IntlMedal::Gold));
+
+        let ranking = db.most_decorated(10);
+        assert_eq!(
+            ranking
+                .iter()
+                .map(|(id, score)| (id.name.as_str(), score.total))
+                .collect::<Vec<_>>(),
+            // 2 + 5 + (5 + 20) and 3
+            [("Medalist", 32.), ("Grinder", 3.)]
+        );
+        assert_eq!(ranking[0].1.ioi.gold, 1);
+
+        // weighting platinum contests heavily flips the order
+        let ranking = db.most_decorated_with(
+            1,
+            DecorationWeights {
+                platinum: 100.,
+                ..Default::default()
+            },
+        );
+        assert_eq!(ranking.len(), 1);
+        assert_eq!(ranking[0].0.name, "Grinder");
+    }
+
+    #[test]
+    fn test_high_performer_counts() {
+        let jan = MonthYear {
+            year: 2022,
+            month: Month::January,
+        };
+        let feb = MonthYear {
+            year: 2022,
+            month: Month::February,
+        };
+        let open = MonthYear {
+            year: 2022,
+            month: Month::Open,
+        };
+
+        let db = db(vec![
+            participant(
+                "A",
+                2024,
+                &[
+                    (2022, Month::January, Division::Gold, 950),
+                    (2022, Month::February, Division::Gold, 1000),
+                    (2022, Month::Open, Division::Gold, 100),
+                ],
+            ),
+            participant(
+                "B",
+                2024,
+                &[
+                    (2022, Month::January, Division::Gold, 900),
+                    (2022, Month::February, Division::Gold, 899),
+                    (2022, Month::Open, Division::Gold, 200),
+                ],
+            ),
+            participant("C", 2024, &[(2022, Month::January, Division::Gold, 1000)]),
+            participant(
+                "D",
+                2024,
+                &[(2022, Month::February, Division::Silver, 1000)],
+            ),
+        ]);
+
+        assert_eq!(
+            db.high_performer_counts(Division::Gold, 900),
+            [(jan, 3), (feb, 1), (open, 0)]
+        );
+        assert_eq!(db.high_performer_counts(Division::Silver, 900), [(feb, 1)]);
+        assert!(db.high_performer_counts(Division::Platinum, 900).is_empty());
+    }
+
+    #[test]
+    fn test_perfect_attendance() {
+        let db = db(vec![
+            participant(
+                "Regular",
+                2024,
+                &[
+                    (2022, Month::January, Division::Gold, 500),
+                    (2022, Month::February, Division::Gold, 600),
+                    (2022, Month::Open, Division::Gold, 700),
+                ],
+            ),
+            // missed February
+            participant(
+                "Gap",
+                2024,
+                &[
+                    (2022, Month::January, Division::Gold, 500),
+                    (2022, Month::Open, Division::Gold, 700),
+                ],
+            ),
+            // didn't start until February, then promoted
+            participant(
+                "Promoted",
+                2024,
+                &[
+                    (2022, Month::February, Division::Gold, 900),
+                    (2022, Month::Open, Division::Gold, 1000),
+                    (2022, Month::December, Division::Platinum, 500),
+                ],
+            ),
+            participant("Once", 2024, &[(2022, Month::Open, Division::Gold, 700)]),
+        ]);
+
+        let names = |division| {
+            db.perfect_attendance(division)
+                .into_iter()
+                .map(|p| p.id.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(Division::Gold), ["Regular", "Promoted"]);
+        assert!(names(Division::Platinum).is_empty());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let open = MonthYear {
+            year: 2022,
+            month: Month::Open,
+        };
+        let db = db(vec![
+            participant("A", 2024, &[(2022, Month::Open, Division::Gold, 300)]),
+            participant("B", 2024, &[(2022, Month::Open, Division::Gold, 700)]),
+            participant("C", 2024, &[(2022, Month::Open, Division::Gold, 700)]),
+            participant("D", 2024, &[(2022, Month::Open, Division::Gold, 1000)]),
+        ]);
+
+        assert_eq!(db.percentile(open, Division::Gold, 700), Some(75.));
+        assert_eq!(db.percentile(open, Division::Gold, 0), Some(0.));
+        assert_eq!(db.percentile(open, Division::Gold, 1000), Some(100.));
+        assert_eq!(db.percentile(open, Division::Silver, 700), None);
+    }
+
+    #[test]
+    fn test_contest_participants() {
+        let db = db(vec![
+            participant("A", 2024, &[(2022, Month::Open, Division::Gold, 300)]),
+            participant(
+                "B",
+                2024,
+                &[
+                    (2022, Month::Open, Division::Gold, 700),
+                    (2022, Month::February, Division::Gold, 100),
+                ],
+            ),
+            participant("C", 2024, &[(2022, Month::Open, Division::Silver, 1000)]),
+        ]);
+
+        let leaderboard = db.contest_participants(
+            MonthYear {
+                year: 2022,
+                month: Month::Open,
+            },
+            Division::Gold,
+        );
+        assert_eq!(
+            leaderboard
+                .iter()
+                .map(|(id, score)| (id.name.as_str(), *score))
+                .collect::<Vec<_>>(),
+            [("B", 700), ("A", 300)]
+        );
+    }
+
+    #[test]
+    fn test_top_scores() {
+        let db = db(vec![
+            participant(
+                "A",
+                2024,
+                &[
+                    (2021, Month::December, Division::Gold, 1000),
+                    (2022, Month::February, Division::Gold, 1000),
+                    (2023, Month::January, Division::Gold, 900),
+                ],
+            ),
+            participant("B", 2024, &[(2021, Month::December, Division::Gold, 950)]),
+            participant("C", 2024, &[(2022, Month::Open, Division::Gold, 1000)]),
+            participant("D", 2024, &[(2023, Month::Open, Division::Silver, 1000)]),
+        ]);
+        let top = |season, limit| {
+            db.top_scores(Division::Gold, season, limit)
+                .into_iter()
+                .map(|(id, score, time)| (id.name, score, time.year, time.month))
+                .collect::<Vec<_>>()
+        };
+
+        // ties are broken by the earliest contest, and A is only listed once
+        assert_eq!(
+            top(None, 10),
+            [
+                ("A".to_string(), 1000, 2021, Month::December),
+                ("C".to_string(), 1000, 2022, Month::Open),
+                ("B".to_string(), 950, 2021, Month::December),
+            ]
+        );
+        assert_eq!(top(None, 1).len(), 1);
+        // only the 2022-23 season, which January 2023 is in
+        assert_eq!(
+            top(Some(2023), 10),
+            [("A".to_string(), 900, 2023, Month::January)]
+        );
+        assert!(db.top_scores(Division::Platinum, None, 10).is_empty());
+    }
+
+    #[test]
+    fn test_grade_score_correlation() {
+        let mut observer = participant(
+            "Observer",
+            2024,
+            &[(2022, Month::Open, Division::Gold, 1000)],
+        );
+        observer.id.graduation = Graduation::Observer;
+        let gold = db(vec![
+            // a 9th grader, 10th grader, and 11th grader, in increasing score
+            participant("A", 2025, &[(2022, Month::Open, Division::Gold, 300)]),
+            participant("B", 2024, &[(2022, Month::Open, Division::Gold, 500)]),
+            participant("C", 2023, &[(2022, Month::Open, Division::Gold, 900)]),
+            observer,
+            participant("D", 2023, &[(2022, Month::Open, Division::Silver, 100)]),
+        ]);
+
+        let r = gold.grade_score_correlation(Division::Gold).unwrap();
+        assert!(r > 0.9 && r <= 1., "{r}");
+        // only one silver record
+        assert_eq!(gold.grade_score_correlation(Division::Silver), None);
+        assert_eq!(gold.grade_score_correlation(Division::Platinum), None);
+
+        // an 11th grader scoring lower than a 9th grader
+        let inverted = db(vec![
+            participant("A", 2025, &[(2022, Month::Open, Division::Gold, 900)]),
+            participant("B", 2023, &[(2022, Month::Open, Division::Gold, 300)]),
+        ]);
+        assert!(inverted.grade_score_correlation(Division::Gold).unwrap() < 0.);
+    }
+
+    #[test]
+    fn test_school_class_ranking() {
+        let mut a = participant(
+            "A",
+            2024,
+            &[
+                (2022, Month::January, Division::Gold, 1000),
+                (2023, Month::January, Division::Platinum, 600),
+            ],
+        );
+        a.camps.push(camp(2023));
+        let mut b = participant(
+            "B",
+            2024,
+            &[(2023, Month::January, Division::Platinum, 800)],
+        );
+        b.camps.push(ParticipantCampRecord {
+            school: "example  high school".to_string(),
+            ..camp(2023)
+        });
+        // same school, different class
+        let mut c = participant(
+            "C",
+            2025,
+            &[(2023, Month::January, Division::Platinum, 1000)],
+        );
+        c.camps.push(camp(2023));
+        // same class, didn't camp
+        let d = participant(
+            "D",
+            2024,
+            &[(2023, Month::January, Division::Platinum, 1000)],
+        );
+
+        let db = db(vec![a, b, c, d]);
+        assert_eq!(
+            db.school_class_ranking("Example High School", 2024)
+                .iter()
+                .map(|(id, score)| (id.name.as_str(), *score))
+                .collect::<Vec<_>>(),
+            [("B", 800), ("A", 600)]
+        );
+    }
+
+    #[test]
+    fn test_query_school() {
+        let at = |name, year, school: &str| {
+            let mut p = participant(name, 2025, &[]);
+            p.camps.push(ParticipantCampRecord {
+                school: school.to_string(),
+                ..camp(year)
+            });
+            p
+        };
+        let mut moved = at("D", 2022, "Other High School");
+        moved.camps.push(ParticipantCampRecord {
+            school: "Lynbrook H.S.".to_string(),
+            ..camp(2024)
+        });
+        let db = db(vec![
+            at("A", 2023, "Lynbrook High School"),
+            at("B", 2022, "The Lynbrook HS"),
+            at("C", 2023, "Lynbrook Middle School"),
+            moved,
+            at("E", 2023, "Saratoga High School"),
+            participant("F", 2025, &[]),
+        ]);
+
+        let names = |query| {
+            db.query_school(query)
+                .into_iter()
+                .map(|p| p.id.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("lynbrook high school"), ["B", "A", "D"]);
+        assert_eq!(names("Lynbrook"), ["B", "A", "C", "D"]);
+        assert_eq!(names("saratoga hs"), ["E"]);
+        assert_eq!(names("high lynbrook"), Vec::<&str>::new());
+        assert_eq!(names(" . "), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_query_country() {
+        let mut a = participant("A", 2025, &[(2024, Month::Open, Division::Gold, 900)]);
+        a.id.country = "CAN".to_string();
+        let mut b = participant(
+            "B",
+            2025,
+            &[
+                (2023, Month::Open, Division::Gold, 1000),
+                (2024, Month::Open, Division::Platinum, 300),
+            ],
+        );
+        b.id.country = "CAN".to_string();
+        // from an old db, before countries were normalized
+        let mut c = participant("C", 2025, &[(2024, Month::Open, Division::Gold, 900)]);
+        c.id.country = "Canada".to_string();
+        let mut d = participant("D", 2025, &[]);
+        d.id.country = "CAN".to_string();
+        let db = db(vec![
+            d,
+            c,
+            participant("E", 2025, &[(2024, Month::Open, Division::Gold, 1000)]),
+            a,
+            b,
+        ]);
+
+        let names = |country| {
+            db.query_country(country)
+                .into_iter()
+                .map(|p| p.id.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("canada"), ["B", "A", "C", "D"]);
+        assert_eq!(names("CAN"), names("canada"));
+        assert_eq!(names("usa"), ["E"]);
+        assert_eq!(names("JPN"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_query_graduation() {
+        let mut camper = participant("A", 2025, &[(2024, Month::Open, Division::Platinum, 800)]);
+        camper.camps.push(camp(2024));
+        // a 2035 graduate wouldn't be in school yet in 2023
+        let typo = participant("B", 2035, &[(2023, Month::Open, Division::Bronze, 500)]);
+        let mut observer = participant("C", 2025, &[]);
+        observer.id.graduation = Graduation::Observer;
+        let db = UsacoDb::new(
+            vec![
+                typo.clone(),
+                participant("D", 2025, &[(2024, Month::Open, Division::Gold, 1000)]),
+                camper,
+                participant("E", 2026, &[(2024, Month::Open, Division::Gold, 1000)]),
+                observer.clone(),
+                participant("F", 2035, &[]),
+            ],
+            IntlHistory {
+                ioi: vec![
+                    intl("A", 2024, IntlMedal::Gold),
+                    intl("A", 2026, IntlMedal::Silver),
+                ],
+                egoi: vec![],
+            },
+        );
+
+        let names = |year| {
+            db.query_graduation(year)
+                .into_iter()
+                .map(|p| p.id.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(2025), ["A", "D"]);
+        assert_eq!(names(2035), ["B", "F"]);
+
+        assert!(typo.has_implausible_graduation());
+        assert!(!db.query_graduation(2025)[0].has_implausible_graduation());
+        assert!(!observer.has_implausible_graduation());
+
+        // the 2026 IOI was after the 2025 graduate finished high school
+        let summary = db.participant_summary(db.query_graduation(2025)[0]);
+        assert_eq!(
+            (summary.best_division, summary.best_score, summary.camps),
+            (Some(Division::Platinum), Some(800), 1)
+        );
+        assert_eq!((summary.ioi.appearances, summary.ioi.gold), (1, 1));
+    }
+
+    #[test]
+    fn test_merge_graduation_corrections() {
+        let open = |year, score| (year, Month::Open, Division::Gold, score);
+        let mut corrected = participant("jane  doe", 2026, &[open(2024, 800)]);
+        corrected.camps.push(camp(2024));
+        let mut observer = participant("Jane Doe", 2025, &[open(2021, 100)]);
+        observer.id.graduation = Graduation::Observer;
+        let mut abroad = participant("Jane Doe", 2024, &[open(2020, 100)]);
+        abroad.id.country = "CAN".to_string();
+        let mut db = db(vec![
+            participant(
+                "Jane Doe",
+                2025,
+                &[open(2021, 400), open(2022, 500), open(2023, 700)],
+            ),
+            corrected,
+            // two years off
+            participant("Jane Doe", 2027, &[open(2022, 300)]),
+            // a different person, since both took the same contest
+            participant("Jane Doe", 2024, &[open(2023, 200)]),
+            observer,
+            abroad,
+        ]);
+
+        let id = |name: &str, year| ParticipantId {
+            name: name.to_string(),
+            graduation: Graduation::HighSchool { year },
+            country: "USA".to_string(),
+        };
+        assert_eq!(
+            db.merge_graduation_corrections(GraduationMergeConfig::default()),
+            [(id("jane  doe", 2026), id("Jane Doe", 2025))]
+        );
+        assert_eq!(db.participants.len(), 5);
+        let merged = &db.query_name("Jane Doe").participants;
+        let merged = merged
+            .iter()
+            .find(|p| p.id == id("Jane Doe", 2025))
+            .unwrap();
+        assert_eq!((merged.contests.len(), merged.camps.len()), (4, 1));
+
+        // nothing left to merge, even with a larger threshold, since 2027 shares
+        // a contest with 2025, and is three years away from 2024
+        let config = GraduationMergeConfig {
+            max_year_difference: 2,
+        };
+        assert!(db.merge_graduation_corrections(config).is_empty());
+    }
+
+    #[test]
+    fn test_chained_corrections() {
+        let id = |year| participant("Jane Doe", year, &[]).id;
+        let correction = |from, to| Correction {
+            from: id(from),
+            to: id(to),
+        };
+        let mut db = db(vec![
+            participant(
+                "Jane Doe",
+                2023,
+                &[(2020, Month::Open, Division::Bronze, 300)],
+            ),
+            participant(
+                "Jane Doe",
+                2024,
+                &[(2021, Month::Open, Division::Silver, 500)],
+            ),
+            participant(
+                "Jane Doe",
+                2030,
+                &[(2022, Month::Open, Division::Gold, 700)],
+            ),
+            participant(
+                "Jane Doe",
+                2031,
+                &[(2023, Month::Open, Division::Gold, 900)],
+            ),
+        ]);
+
+        db.apply_corrections(&[
+            correction(2023, 2024),
+            correction(2024, 2025),
+            // a cycle, which is left alone
+            correction(2030, 2031),
+            correction(2031, 2030),
+        ]);
+
+        let mut people = db
+            .query_name("jane doe")
+            .participants
+            .iter()
+            .map(|p| (p.id.graduation, p.contests.len()))
+            .collect::<Vec<_>>();
+        people.sort();
+        assert_eq!(
+            people,
+            [
+                (Graduation::HighSchool { year: 2025 }, 2),
+                (Graduation::HighSchool { year: 2030 }, 1),
+                (Graduation::HighSchool { year: 2031 }, 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_store_missing_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        tokio::fs::write(dir.join("aliases.json"), r#"{"Janie Doe": "Jane Doe"}"#)
+            .await
+            .unwrap();
+
+        let data = FileStore::new_path(dir.to_path_buf()).load().await;
+        assert!(data.corrections.is_empty());
+        assert_eq!(data.aliases["Janie Doe"], "Jane Doe");
+    }
+
+    #[test]
+    fn test_campers_without_contests() {
+        let mut both = participant(
+            "Jane Doe",
+            2023,
+            &[(2022, Month::Open, Division::Gold, 300)],
+        );
+        both.camps.push(camp(2022));
+        let mut later = participant("John Doe", 2024, &[]);
+        later.camps.push(camp(2024));
+        let mut earlier = participant("John Smith", 2024, &[]);
+        earlier.camps.extend([camp(2024), camp(2023)]);
+        let db = db(vec![
+            both,
+            later,
+            participant("Jim Doe", 2024, &[]),
+            earlier,
+        ]);
+
+        let names = db
+            .campers_without_contests()
+            .into_iter()
+            .map(|p| p.id.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["John Smith", "John Doe"]);
+    }
+
+    #[test]
+    fn test_camp_division_mismatches() {
+        let mut gold = participant(
+            "Jane Doe",
+            2023,
+            &[
+                (2021, Month::Open, Division::Silver, 700),
+                (2022, Month::Open, Division::Gold, 900),
+            ],
+        );
+        gold.camps.push(camp(2022));
+        let mut platinum = participant(
+            "John Doe",
+            2023,
+            &[
+                (2022, Month::January, Division::Gold, 1000),
+                (2022, Month::Open, Division::Platinum, 800),
+            ],
+        );
+        platinum.camps.push(camp(2022));
+        let mut no_contests = participant("John Smith", 2024, &[]);
+        no_contests.camps.push(camp(2023));
+        let db = db(vec![
+            gold,
+            platinum,
+            no_contests,
+            participant("Jim Doe", 2024, &[(2023, Month::Open, Division::Gold, 500)]),
+        ]);
+
+        let names = db
+            .camp_division_mismatches()
+            .into_iter()
+            .map(|p| p.id.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Jane Doe"]);
+    }
+
+    #[test]
+    fn test_school_division_history() {
+        let mut jane = participant(
+            "Jane Doe",
+            2023,
+            &[
+                (2021, Month::December, Division::Silver, 1000),
+                (2022, Month::January, Division::Gold, 800),
+                (2022, Month::December, Division::Platinum, 500),
+            ],
+        );
+        jane.camps.push(camp(2023));
+        let mut john = participant(
+            "John Doe",
+            2024,
+            &[
+                (2021, Month::December, Division::Bronze, 1000),
+                (2023, Month::January, Division::Gold, 700),
+            ],
+        );
+        john.camps.push(camp(2024));
+        let mut other = participant(
+            "John Smith",
+            2024,
+            &[(2023, Month::Open, Division::Gold, 700)],
+        );
+        other.camps.push(ParticipantCampRecord {
+            school: "Other High School".to_string(),
+            ..camp(2024)
+        });
+        let db = db(vec![jane, john, other]);
+
+        assert_eq!(
+            db.school_division_history("example  high school"),
+            [(2022, [1, 0, 1, 0]), (2023, [0, 0, 1, 1])]
+        );
+    }
+
+    #[test]
+    fn test_live_stats() {
+        let stats = LiveStats::from(
+            serde_json::from_str::<AppStats>(r#"{"users_queried":{"1":2},"query_count":2}"#)
+                .unwrap(),
+        );
+        stats.record_query(UserId::new(1));
+        stats.record_query(UserId::new(3));
+        for name in ["Jane Doe", "john  doe", "JANE DOE", "  ", "Bob"] {
+            stats.record_name_query(name);
+        }
+
+        assert_eq!(stats.query_count(), 4);
+        assert_eq!(stats.users_queried(), 2);
+        assert_eq!(
+            stats.top_queried_names(2),
+            [("jane doe".to_string(), 2), ("bob".to_string(), 1)]
+        );
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.query_count, 4);
+        assert_eq!(
+            snapshot.users_queried,
+            HashMap::from([(UserId::new(1), 3), (UserId::new(3), 1)])
+        );
+        assert_eq!(snapshot.query_counts.len(), 3);
+        assert_eq!(
+            LiveStats::from(snapshot).top_queried_names(1),
+            [("jane doe".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_query_counts_capped() {
+        let stats = LiveStats::default();
+        for _ in 0..2 {
+            stats.record_name_query("Jane Doe");
+        }
+        for i in 0..2 * MAX_QUERY_COUNTS {
+            stats.record_name_query(&format!("name {i}"));
+        }
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.query_counts.len(), MAX_QUERY_COUNTS);
+        assert_eq!(snapshot.query_counts.get("jane doe"), Some(&2));
+        assert_eq!(stats.top_queried_names(1), [("jane doe".to_string(), 2)]);
+
+        let mut merged = AppStats::default();
+        merged.merge(snapshot.clone());
+        merged.merge(AppStats {
+            query_counts: HashMap::from([("bob".to_string(), 3)]),
+            ..Default::default()
+        });
+        assert_eq!(merged.query_counts.len(), MAX_QUERY_COUNTS);
+        assert_eq!(merged.query_counts.get("bob"), Some(&3));
+    }
+
+    #[test]
+    fn test_merge_stats() {
+        let now = Utc::now();
+        let mut stats = AppStats {
+            users_queried: HashMap::from([(UserId::new(1), 2), (UserId::new(2), 1)]),
+            query_count: 3,
+            participant_counts: vec![(now - chrono::Duration::days(2), 1000), (now, 1200)],
+            query_counts: HashMap::from([("jane doe".to_string(), 2)]),
+        };
+        stats.merge(AppStats {
+            users_queried: HashMap::from([(UserId::new(2), 4), (UserId::new(3), 1)]),
+            query_count: 5,
+            participant_counts: vec![(now - chrono::Duration::days(1), 1100)],
+            query_counts: HashMap::from([("jane doe".to_string(), 1), ("bob".to_string(), 3)]),
+        });
+
+        assert_eq!(stats.query_count, 8);
+        assert_eq!(
+            stats.query_counts,
+            HashMap::from([("jane doe".to_string(), 3), ("bob".to_string(), 3)])
+        );
+        assert_eq!(
+            stats.users_queried,
+            HashMap::from([
+                (UserId::new(1), 2),
+                (UserId::new(2), 5),
+                (UserId::new(3), 1)
+            ])
+        );
+        assert_eq!(
+            stats
+                .participant_counts
+                .iter()
+                .map(|&(_, count)| count)
+                .collect::<Vec<_>>(),
+            [1000, 1100, 1200]
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let (a, b) = (UserId::new(1), UserId::new(2));
+        let start = Instant::now();
+
+        assert_eq!(limiter.check(a, start), Ok(()));
+        assert_eq!(limiter.check(a, start), Ok(()));
+        assert_eq!(limiter.check(a, start), Err(Duration::from_secs(30)));
+        // users have separate buckets
+        assert_eq!(limiter.check(b, start), Ok(()));
+
+        // a token refills every 30 seconds
+        let later = start + Duration::from_secs(40);
+        assert_eq!(limiter.check(a, later), Ok(()));
+        assert!(limiter.check(a, later).is_err());
+
+        // buckets don't fill past capacity
+        let much_later = later + Duration::from_secs(3600);
+        assert_eq!(limiter.check(a, much_later), Ok(()));
+        assert_eq!(limiter.check(a, much_later), Ok(()));
+        assert!(limiter.check(a, much_later).is_err());
+    }
+
+    #[test]
+    fn test_participant_growth() {
+        let stats = LiveStats::default();
+        let now = Utc::now();
+        let days_ago = |days| now - chrono::Duration::days(days);
+        assert_eq!(stats.participant_growth_since(days_ago(7)), None);
+
+        stats.record_participant_count(days_ago(10), 1000);
+        assert_eq!(stats.participant_growth_since(days_ago(7)), None);
+        stats.record_participant_count(days_ago(8), 1100);
+        stats.record_participant_count(days_ago(1), 1242);
+        assert_eq!(stats.participant_growth_since(days_ago(7)), Some(142));
+        // every count is newer, so the growth since then isn't known
+        assert_eq!(stats.participant_growth_since(days_ago(30)), None);
+
+        for i in 0..MAX_PARTICIPANT_COUNTS {
+            stats.record_participant_count(now, 2000 + i);
+        }
+        let counts = stats.snapshot().participant_counts;
+        assert_eq!(counts.len(), MAX_PARTICIPANT_COUNTS);
+        assert_eq!(counts[0], (now, 2000));
+    }
+
+    #[test]
+    fn test_suggest_merges() {
+        let mut observer = participant(
+            "Jane Doe",
+            2024,
+            &[(2021, Month::Open, Division::Gold, 500)],
+        );
+        observer.id.graduation = Graduation::Observer;
+        let mut abroad = participant(
+            "Jane Doe",
+            2025,
+            &[(2022, Month::Open, Division::Gold, 500)],
+        );
+        abroad.id.country = "CAN".to_string();
+        let db = db(vec![
+            participant(
+                "John Doe",
+                2024,
+                &[(2022, Month::Open, Division::Gold, 300)],
+            ),
+            participant(
+                "Jon Doe",
+                2024,
+                &[(2022, Month::January, Division::Gold, 400)],
+            ),
+            participant(
+                "Jane Doe",
+                2024,
+                &[(2022, Month::December, Division::Gold, 600)],
+            ),
+            observer,
+            abroad,
+            // competed at the same time as "John Doe", so can't be the same person
+            participant(
+                "John Doe",
+                2025,
+                &[(2022, Month::Open, Division::Silver, 300)],
+            ),
+        ]);
+
+        let summarize = |config| {
+            db.suggest_merges(config)
+                .into_iter()
+                .map(|s| (s.first.name, s.second.name, (s.confidence * 100.).round()))
+                .collect::<Vec<_>>()
+        };
+        let pair = |first: &str, second: &str, confidence| {
+            (first.to_string(), second.to_string(), confidence)
+        };
+
+        // a misspelled name is likely the same person, and each differing graduation
+        // year or country makes it less likely
+        assert_eq!(
+            summarize(MergeConfig::default()),
+            [
+                pair("John Doe", "Jon Doe", 97.),
+                pair("Jane Doe", "Jane Doe", 80.),
+                pair("John Doe", "Jon Doe", 77.),
+                pair("Jane Doe", "Jane Doe", 64.),
+                pair("Jane Doe", "Jane Doe", 64.),
+            ]
+        );
+        assert_eq!(
+            summarize(MergeConfig {
+                min_name_similarity: 1.,
+                require_same_country: true,
+                ..Default::default()
+            }),
+            [pair("Jane Doe", "Jane Doe", 80.)]
+        );
+        assert_eq!(
+            summarize(MergeConfig {
+                require_same_graduation: true,
+                ..Default::default()
+            }),
+            [pair("John Doe", "Jon Doe", 97.)]
+        );
+    }
+
+    #[test]
+    fn test_suspicious_contest_gaps() {
+        let regular = |name: &str, division, skipped: &[Month]| {
+            let contests = [
+                (2021, Month::December),
+                (2022, Month::January),
+                (2022, Month::February),
+                (2022, Month::Open),
+            ]
+            .into_iter()
+            .filter(|(_, month)| !skipped.contains(month))
+            .map(|(year, month)| (year, month, division, 500))
+            .collect::<Vec<_>>();
+            participant(name, 2024, &contests)
+        };
+
+        // the january gold page is missing, but one person skipping february is fine
+        let mut participants = (0..6)
+            .map(|i| regular(&format!("Gold {i}"), Division::Gold, &[Month::January]))
+            .collect::<Vec<_>>();
+        participants.extend((0..6).map(|i| regular(&format!("Silver {i}"), Division::Silver, &[])));
+        participants.push(regular("Skipper", Division::Silver, &[Month::February]));
+        let db = db(participants);
+
+        assert_eq!(
+            db.suspicious_contest_gaps(),
+            [(
+                MonthYear {
+                    year: 2022,
+                    month: Month::January
+                },
+                Division::Gold
+            )]
+        );
+        assert!(db.suspicious_contest_gaps_with(7, 0.5).is_empty());
+        assert_eq!(db.suspicious_contest_gaps_with(5, 0.1).len(), 2);
+    }
+
+    #[test]
+    fn test_season_competitiveness() {
+        let mut participants = vec![];
+        // the 2022 season: more participants, but lower scores
+        for (i, score) in [(0, 400), (1, 500), (2, 600)] {
+            participants.push(participant(
+                &format!("Jane {i}"),
+                2023,
+                &[
+                    (2021, Month::December, Division::Gold, score),
+                    (2022, Month::January, Division::Gold, score),
+                ],
+            ));
+        }
+        // the 2023 season
+        for (i, score) in [(0, 900), (1, 1000)] {
+            participants.push(participant(
+                &format!("John {i}"),
+                2024,
+                &[(2023, Month::Open, Division::Gold, score)],
+            ));
+        }
+        let db = db(participants);
+
+        let weights = CompetitivenessWeights {
+            participants: 10.,
+            top_score: 1.,
+            top_n: 2,
+        };
+        assert_eq!(
+            db.season_competitiveness_with(weights),
+            [(2023, 20. + 950.), (2022, 30. + 550.)]
+        );
+
+        let weights = CompetitivenessWeights {
+            participants: 1000.,
+            ..weights
+        };
+        assert_eq!(
+            db.season_competitiveness_with(weights),
+            [(2022, 3000. + 550.), (2023, 2000. + 950.)]
+        );
+    }
+
+    #[test]
+    fn test_promotion_data_cutoff() {
+        let mut db = db(vec![participant(
+            "Jane Doe",
+            2023,
+            &[
+                (2019, Month::December, Division::Bronze, 1000),
+                (2020, Month::January, Division::Silver, 1000),
+                (2020, Month::February, Division::Gold, 500),
+                (2021, Month::Open, Division::Platinum, 500),
+            ],
+        )]);
+        assert_eq!(
+            db.promotion_data_cutoff(),
+            Some(MonthYear {
+                year: 2020,
+                month: Month::January
+            })
+        );
+
+        db.participants[0]
+            .contests
+            .retain(|c| c.division >= Division::Gold);
+        db.reindex();
+        assert_eq!(db.promotion_data_cutoff(), None);
+    }
+
+    #[test]
+    fn test_fastest_climbers() {
+        use Division::*;
+
+        let climber = |name: &str, divisions: &[Division]| {
+            let contests = divisions
+                .iter()
+                .zip([
+                    (2019, Month::December),
+                    (2020, Month::January),
+                    (2020, Month::February),
+                    (2020, Month::Open),
+                    (2020, Month::December),
+                    (2021, Month::January),
+                    (2021, Month::February),
+                    (2021, Month::Open),
+                    (2021, Month::December),
+                ])
+                .map(|(&division, (year, month))| (year, month, division, 500))
+                .collect::<Vec<_>>();
+            participant(name, 2023, &contests)
+        };
+
+        let db = db(vec![
+            climber(
+                "Slow",
+                &[
+                    Bronze, Bronze, Silver, Silver, Silver, Gold, Gold, Gold, Platinum,
+                ],
+            ),
+            climber("Fast", &[Bronze, Bronze, Silver, Gold, Platinum]),
+            // started in silver, so never climbed from bronze
+            climber("Skipper", &[Silver, Platinum]),
+            climber("Stuck", &[Bronze, Silver, Gold]),
+        ]);
+
+        let climbers = db
+            .fastest_climbers(5)
+            .into_iter()
+            .map(|(id, count)| (id.name.as_str(), count))
+            .collect::<Vec<_>>();
+        assert_eq!(climbers, [("Fast", 4), ("Slow", 8)]);
+        assert_eq!(db.fastest_climbers(1).len(), 1);
+    }
+
+    #[test]
+    fn test_skip_empty_vecs() {
+        let p = participant(
+            "Jane Doe",
+            2025,
+            &[(2022, Month::Open, Division::Gold, 500)],
+        );
+
+        let json = serde_json::to_value(&p).unwrap();
+        assert!(json.get("camps").is_none());
+        assert!(json["contests"][0].get("solved").is_none());
+
+        let p = serde_json::from_value::<Participant>(json).unwrap();
+        assert!(p.camps.is_empty());
+        assert_eq!(p.contests.len(), 1);
+        assert!(p.contests[0].solved.is_empty());
+    }
+
+    #[test]
+    fn test_score_gap() {
+        let db = db(vec![
+            participant(
+                "Jane Doe",
+                2023,
+                &[(2022, Month::Open, Division::Gold, 300)],
+            ),
+            participant(
+                "John Doe",
+                2023,
+                &[
+                    (2022, Month::Open, Division::Gold, 500),
+                    (2022, Month::February, Division::Gold, 200),
+                ],
+            ),
+        ]);
+        let (jane, john) = (&db.participants[0].id, &db.participants[1].id);
+        let time = |month| MonthYear { year: 2022, month };
+
+        assert_eq!(
+            db.score_gap(jane, john, time(Month::Open), Division::Gold),
+            Some(-200)
+        );
+        assert_eq!(
+            db.score_gap(john, jane, time(Month::Open), Division::Gold),
+            Some(200)
+        );
+        assert_eq!(
+            db.score_gap(jane, john, time(Month::February), Division::Gold),
+            None
+        );
+        assert_eq!(
+            db.score_gap(jane, john, time(Month::Open), Division::Silver),
+            None
+        );
+    }
+
+    #[test]
+    fn test_graduation_years() {
+        let mut observer = participant("Observer", 2000, &[]);
+        observer.id.graduation = Graduation::Observer;
+
+        let db = db(vec![
+            participant("A", 2025, &[]),
+            participant("B", 2023, &[]),
+            observer,
+            participant("C", 2025, &[]),
+            participant("D", 2024, &[]),
+        ]);
+
+        assert_eq!(db.graduation_years(), vec![2023, 2024, 2025]);
+    }
+
+    #[test]
+    fn test_suspect_encoding_names() {
+        let db = db(vec![
+            participant("JosÃ© Garcia", 2025, &[]),
+            participant("José Garcia", 2025, &[]),
+            participant("Zoë Brontë", 2024, &[]),
+            participant("Ã‰lodie Martin", 2024, &[]),
+            participant("Anne â€œAnnieâ€ Lee", 2023, &[]),
+            participant("JOÃO SILVA", 2023, &[]),
+        ]);
+
+        let names = db
+            .suspect_encoding_names()
+            .into_iter()
+            .map(|id| id.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["JosÃ© Garcia", "Ã‰lodie Martin", "Anne â€œAnnieâ€ Lee"]
+        );
+    }
+
+    #[test]
+    fn test_cocompetition_graph() {
+        let db = db(vec![
+            participant(
+                "A",
+                2025,
+                &[
+                    (2023, Month::December, Division::Bronze, 500),
+                    (2024, Month::January, Division::Silver, 500),
+                    (2024, Month::February, Division::Silver, 500),
+                ],
+            ),
+            participant(
+                "B",
+                2025,
+                &[
+                    (2023, Month::December, Division::Bronze, 400),
+                    (2024, Month::January, Division::Silver, 400),
+                    (2024, Month::February, Division::Silver, 400),
+                ],
+            ),
+            participant(
+                "C",
+                2025,
+                &[
+                    (2023, Month::December, Division::Bronze, 300),
+                    // same contest as A and B, but a different division
+                    (2024, Month::January, Division::Bronze, 300),
+                ],
+            ),
+        ]);
+
+        let edges = db
+            .cocompetition_graph(1)
+            .into_iter()
+            .map(|(a, b, count)| (a.name, b.name, count))
+            .collect::<Vec<_>>();
+        let edge = |a: &str, b: &str, count| (a.to_string(), b.to_string(), count);
+        assert_eq!(
+            edges,
+            [edge("A", "B", 3), edge("A", "C", 1), edge("B", "C", 1)]
+        );
+
+        assert_eq!(db.cocompetition_graph(2).len(), 1);
+        assert!(db.cocompetition_graph(4).is_empty());
+    }
+
+    #[test]
+    fn test_redemption_stories() {
+        let with_percentiles = |mut p: Participant, percentiles: &[f32]| {
+            for (c, &x) in p.contests.iter_mut().zip(percentiles) {
+                c.percentile = Some(x);
+            }
+            p
+        };
+
+        let db = db(vec![
+            with_percentiles(
+                participant(
+                    "Redeemed",
+                    2025,
+                    &[
+                        (2022, Month::December, Division::Bronze, 100),
+                        (2023, Month::January, Division::Bronze, 150),
+                        (2023, Month::February, Division::Bronze, 1000),
+                    ],
+                ),
+                &[10., 20., 95.],
+            ),
+            with_percentiles(
+                participant(
+                    "Always Strong",
+                    2025,
+                    &[
+                        (2022, Month::December, Division::Bronze, 1000),
+                        (2023, Month::January, Division::Silver, 1000),
+                        (2023, Month::February, Division::Gold, 1000),
+                    ],
+                ),
+                &[99., 99., 99.],
+            ),
+            with_percentiles(
+                participant(
+                    "Platinum",
+                    2025,
+                    &[
+                        (2022, Month::December, Division::Gold, 100),
+                        (2023, Month::January, Division::Gold, 100),
+                        (2023, Month::February, Division::Platinum, 100),
+                    ],
+                ),
+                &[5., 5., 5.],
+            ),
+            with_percentiles(
+                participant(
+                    "Never Improved",
+                    2025,
+                    &[
+                        (2022, Month::December, Division::Bronze, 100),
+                        (2023, Month::January, Division::Bronze, 100),
+                        (2023, Month::February, Division::Bronze, 300),
+                    ],
+                ),
+                &[10., 10., 50.],
+            ),
+            // the same as "Redeemed", but with its records out of order, like
+            // after merging
+            with_percentiles(
+                participant(
+                    "Merged",
+                    2025,
+                    &[
+                        (2023, Month::February, Division::Bronze, 1000),
+                        (2022, Month::December, Division::Bronze, 100),
+                        (2023, Month::January, Division::Bronze, 150),
+                    ],
+                ),
+                &[95., 10., 20.],
+            ),
+        ]);
+
+        let names = |res: Vec<&Participant>| {
+            res.into_iter()
+                .map(|p| p.id.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(db.redemption_stories()),
+            ["Redeemed", "Platinum", "Merged"]
+        );
+        assert_eq!(
+            names(db.redemption_stories_with(RedemptionThresholds {
+                high_percentile: 50.,
+                ..Default::default()
+            })),
+            ["Redeemed", "Platinum", "Never Improved", "Merged"]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_contest_records() {
+        let db = db(vec![
+            participant(
+                "Duplicated",
+                2025,
+                &[
+                    (2023, Month::December, Division::Silver, 500),
+                    (2023, Month::December, Division::Silver, 500),
+                    (2023, Month::December, Division::Silver, 600),
+                    // same contest, different division is fine
+                    (2024, Month::January, Division::Silver, 500),
+                    (2024, Month::January, Division::Gold, 500),
+                ],
+            ),
+            participant(
+                "Clean",
+                2025,
+                &[
+                    (2023, Month::December, Division::Silver, 500),
+                    (2024, Month::January, Division::Gold, 500),
+                ],
+            ),
+        ]);
+
+        let res = db.find_duplicate_contest_records();
+        assert_eq!(
+            res,
+            [(
+                &db.participants[0].id,
+                MonthYear {
+                    year: 2023,
+                    month: Month::December
+                },
+                Division::Silver
+            )]
+        );
+    }
+
+    #[test]
+    fn test_closest_finishes() {
+        let db = db(vec![
+            participant(
+                "A",
+                2025,
+                &[
+                    (2023, Month::December, Division::Gold, 1000),
+                    (2024, Month::January, Division::Gold, 900),
+                    (2024, Month::February, Division::Gold, 800),
+                    (2024, Month::Open, Division::Gold, 700),
+                ],
+            ),
+            participant(
+                "B",
+                2025,
+                &[
+                    (2023, Month::December, Division::Gold, 1000),
+                    (2024, Month::January, Division::Gold, 500),
+                    (2024, Month::February, Division::Gold, 750),
+                    // a different division
+                    (2024, Month::Open, Division::Platinum, 700),
+                ],
+            ),
+            participant(
+                "C",
+                2025,
+                &[
+                    (2023, Month::December, Division::Gold, 200),
+                    (2024, Month::January, Division::Gold, 850),
+                ],
+            ),
+        ]);
+
+        let time = |year, month| MonthYear { year, month };
+        assert_eq!(
+            db.closest_finishes(Division::Gold, 10),
+            [
+                (time(2023, Month::December), 0),
+                (time(2024, Month::January), 50),
+                (time(2024, Month::February), 50),
+            ]
+        );
+        assert_eq!(db.closest_finishes(Division::Gold, 1).len(), 1);
+        assert!(db.closest_finishes(Division::Platinum, 10).is_empty());
+    }
+
+    #[test]
+    fn test_percentile_by_division() {
+        let climber = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2023, Month::December, Division::Gold, 900),
+                (2024, Month::January, Division::Gold, 800),
+                (2024, Month::February, Division::Platinum, 400),
+            ],
+        );
+        let db = db(vec![
+            climber.clone(),
+            participant(
+                "A",
+                2025,
+                &[
+                    (2023, Month::December, Division::Gold, 500),
+                    (2024, Month::January, Division::Gold, 850),
+                    (2024, Month::February, Division::Platinum, 900),
+                ],
+            ),
+            participant(
+                "B",
+                2025,
+                &[
+                    (2023, Month::December, Division::Gold, 300),
+                    (2024, Month::January, Division::Gold, 300),
+                    (2024, Month::February, Division::Platinum, 600),
+                ],
+            ),
+            participant(
+                "C",
+                2025,
+                &[
+                    (2023, Month::December, Division::Gold, 100),
+                    (2024, Month::January, Division::Gold, 100),
+                    (2024, Month::February, Division::Platinum, 300),
+                ],
+            ),
+        ]);
+
+        // computed from the other records, since the helper leaves them out
+        assert_eq!(
+            climber.percentile_by_division(&db),
+            HashMap::from([(Division::Gold, 87.5), (Division::Platinum, 50.)])
+        );
+
+        // stored percentiles are used as is
+        let mut stored = climber.clone();
+        stored.contests[2].percentile = Some(40.);
+        assert_eq!(stored.percentile_by_division(&db)[&Division::Platinum], 40.);
+
+        // contests missing from the db are skipped
+        assert!(climber
+            .percentile_by_division(&UsacoDb::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = db(vec![
+            participant(
+                "Jane Doe",
+                2025,
+                &[(2023, Month::December, Division::Gold, 500)],
+            ),
+            participant(
+                "John Doe",
+                2025,
+                &[(2023, Month::December, Division::Silver, 700)],
+            ),
+        ]);
+        let mut camper = participant(
+            "Jane Doe",
+            2025,
+            &[
+                (2023, Month::December, Division::Gold, 500),
+                (2024, Month::January, Division::Gold, 900),
+            ],
+        );
+        camper.camps.push(camp(2024));
+        let new = db(vec![
+            camper,
+            participant(
+                "John Doe",
+                2025,
+                &[(2023, Month::December, Division::Silver, 800)],
+            ),
+            participant(
+                "Newcomer",
+                2026,
+                &[(2024, Month::January, Division::Bronze, 300)],
+            ),
+        ]);
+
+        let diff = old.diff(&new);
+        let added = diff
+            .participants
+            .iter()
+            .map(|p| {
+                (
+                    p.id.name.as_str(),
+                    p.contests
+                        .iter()
+                        .map(|c| (c.contest_time.month, c.score))
+                        .collect::<Vec<_>>(),
+                    p.camps.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // the rescored december contest isn't new
+        assert_eq!(
+            added,
+            [
+                ("Jane Doe", vec![(Month::January, 900)], 1),
+                ("Newcomer", vec![(Month::January, 300)], 0),
+            ]
+        );
+
+        assert!(new.diff(&new).participants.is_empty());
+    }
+
+    #[test]
+    fn test_watch_list() {
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+        let mut watch_list = WatchList::default();
+
+        assert!(watch_list.watch(alice, "Jane  DOE"));
+        assert!(!watch_list.watch(alice, "jane doe"));
+        assert!(watch_list.watch(alice, "John Doe"));
+        assert!(watch_list.watch(bob, "John Doe"));
+        assert_eq!(watch_list.names(alice), ["jane doe", "john doe"]);
+
+        let diff = DbDiff {
+            participants: vec![
+                participant(
+                    "Jane Doe",
+                    2025,
+                    &[(2024, Month::January, Division::Gold, 900)],
+                ),
+                participant("Someone Else", 2025, &[]),
+            ],
+        };
+        let notifications = watch_list
+            .notifications(&diff)
+            .into_iter()
+            .map(|(user, participants)| {
+                (
+                    user,
+                    participants
+                        .iter()
+                        .map(|p| p.id.name.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(notifications, [(alice, vec!["Jane Doe"])]);
+
+        assert!(watch_list.unwatch(bob, "john doe"));
+        assert!(!watch_list.unwatch(bob, "john doe"));
+        assert!(watch_list.names(bob).is_empty());
+
+        for i in 0..MAX_WATCHED_NAMES {
+            watch_list.watch(bob, &format!("name {i}"));
+        }
+        assert!(!watch_list.watch(bob, "one too many"));
+        assert_eq!(watch_list.names(bob).len(), MAX_WATCHED_NAMES);
+    }
+
+    #[test]
+    fn test_score_histogram() {
+        let time = MonthYear {
+            year: 2024,
+            month: Month::Open,
+        };
+        let db = db([0, 50, 99, 100, 350, 399, 333]
+            .into_iter()
+            .enumerate()
+            .map(|(i, score)| {
+                participant(
+                    &format!("Participant {i}"),
+                    2025,
+                    &[(2024, Month::Open, Division::Gold, score)],
+                )
+            })
+            .collect());
+
+        assert_eq!(
+            db.score_histogram(time, Division::Gold, 100),
+            Some(vec![(0, 3), (100, 1), (200, 0), (300, 3)])
+        );
+        assert_eq!(
+            db.score_histogram(time, Division::Gold, 250),
+            Some(vec![(0, 4), (250, 3)])
+        );
+        assert_eq!(db.score_histogram(time, Division::Silver, 100), None);
+    }
+
+    #[test]
+    fn test_query_name_fuzzy() {
+        let db = db(vec![
+            participant("Benjamin Qi", 2021, &[]),
+            participant("Jane Doe", 2025, &[]),
+            participant("Jane Doe", 2026, &[]),
+            participant("John Smith", 2025, &[]),
+        ]);
+        let names = |query, max_results| {
+            db.query_name_fuzzy(query, max_results)
+                .into_iter()
+                .map(|(score, id)| (score, id.name, id.graduation))
+                .collect::<Vec<_>>()
+        };
+
+        let grad = |year| Graduation::HighSchool { year };
+        assert_eq!(
+            names("jane  DOE", 5),
+            [
+                (100, "Jane Doe".to_string(), grad(2025)),
+                (100, "Jane Doe".to_string(), grad(2026)),
+            ]
+        );
+        // typos still match, but less closely
+        let typo = names("Jnae Doe", 5);
+        assert_eq!(typo.len(), 2);
+        assert!(typo
+            .iter()
+            .all(|&(score, ..)| (MIN_FUZZY_SCORE..100).contains(&score)));
+        assert_eq!(names("Jane Doe", 1).len(), 1);
+
+        // nicknames the plain similarity would miss
+        assert_eq!(
+            names("Ben Qi", 5),
+            [(MIN_FUZZY_SCORE + 15, "Benjamin Qi".to_string(), grad(2021))]
+        );
+
+        assert!(names("Xavier Zhu", 5).is_empty());
+    }
+
+    #[test]
+    fn test_autocomplete_names() {
+        let db = db(vec![
+            participant("Jane Doe", 2025, &[]),
+            participant("Jane Doe", 2026, &[]),
+            participant("JANE DOE", 2026, &[]),
+            participant("Janet Smith", 2025, &[]),
+            participant("Mary Jane", 2025, &[]),
+            participant("John Doe", 2025, &[]),
+        ]);
+
+        assert_eq!(
+            db.autocomplete_names(" jan", 25),
+            ["Jane Doe", "Janet Smith", "Mary Jane"]
+        );
+        assert_eq!(db.autocomplete_names("doe", 25), ["Jane Doe", "John Doe"]);
+        assert_eq!(db.autocomplete_names("jan", 1), ["Jane Doe"]);
+        assert!(db.autocomplete_names("  ", 25).is_empty());
+
+        // the first names alphabetically, regardless of the order in the db
+        let db = UsacoDb::new(
+            ["John Smith", "Jane Doe", "Jim Doe"]
+                .map(|name| participant(name, 2025, &[]))
+                .into(),
+            UsacoDb::default().intl_history,
+        );
+        assert_eq!(db.autocomplete_names("j", 2), ["Jane Doe", "Jim Doe"]);
+        assert!(db.autocomplete_names("xyz", 25).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_save() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        // a leftover from a save that was interrupted
+        tokio::fs::write(dir.join("stats.json.tmp"), "{")
+            .await
+            .unwrap();
+
+        let mut store = FileStore::new_path(dir.to_path_buf());
+        let db = db(vec![participant("Jane Doe", 2025, &[])]);
+        store.save_db(&db).await.unwrap();
+        store.save_db(&db).await.unwrap();
+        store.save_stats(&AppStats::default()).await.unwrap();
+
+        let data = store.load().await;
+        assert_eq!(
+            data.db
+                .participants
+                .iter()
+                .map(|p| &p.id)
+                .collect::<Vec<_>>(),
+            db.participants.iter().map(|p| &p.id).collect::<Vec<_>>()
+        );
+        assert!(!dir.join("usaco-db.json.tmp").exists());
+        assert!(!dir.join("stats.json.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_compression() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let names = |db: &UsacoDb| {
+            db.participants
+                .iter()
+                .map(|p| p.id.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut plain = FileStore::new_path(dir.to_path_buf());
+        plain
+            .save_db(&db(vec![participant("Jane Doe", 2025, &[])]))
+            .await
+            .unwrap();
+
+        // an uncompressed db is still loaded after switching
+        let mut compressed = FileStore::new_path(dir.to_path_buf()).with_compression(true);
+        assert_eq!(names(&compressed.load().await.db), ["Jane Doe"]);
+
+        compressed
+            .save_db(&db(vec![participant("John Doe", 2025, &[])]))
+            .await
+            .unwrap();
+        assert!(dir.join("usaco-db.json.gz").exists());
+        assert!(!dir.join("usaco-db.json").exists());
+        assert_eq!(names(&compressed.load().await.db), ["John Doe"]);
+        // and switching back
+        assert_eq!(names(&plain.load().await.db), ["John Doe"]);
     }
 }
@@ -0,0 +1,9 @@
+//! Storage and queries behind the USACO standings bot. The Discord frontend
+//! lives in the `usaco-standings-bot` binary.
+//!
+//! With the `image` feature, [`card_image`] renders profiles as PNG images for
+//! the `cardimg` command.
+
+#[cfg(feature = "image")]
+pub mod card_image;
+pub mod database;
@@ -1,27 +1,55 @@
-mod database;
-
 use anyhow::Context as _;
 use chrono::{Datelike, Utc};
-use database::{AppStats, FileStore, NameQueryResult, UsacoDb};
 use poise::{
     builtins::HelpConfiguration, serenity_prelude as serenity, serenity_prelude::CreateAttachment,
     CreateReply, FrameworkError,
 };
-use reqwest::{Client, StatusCode, Url};
+use reqwest::Client;
 use serenity::{
-    ActivityData, Color, CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter,
-    CurrentApplicationInfo, GatewayIntents,
+    ActivityData, Color, ComponentInteractionCollector, CreateActionRow, CreateAllowedMentions,
+    CreateButton, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CurrentApplicationInfo, GatewayIntents,
 };
 use std::{
     env,
-    future::Future,
-    pin::Pin,
-    sync::Arc,
+    str::FromStr,
+    sync::{Arc, OnceLock},
     time::{Duration, Instant},
 };
 use tokio::sync::{oneshot, Mutex};
 use tracing::{error, info, warn};
-use usaco_standings_scraper::{Division, Graduation, IntlMedal, Month};
+use usaco_standings_bot::database::{
+    comparison_table, detect_promotions, Aliases, Correction, FileStore, LiveStats,
+    NameQueryResult, Participant, RateLimiter, UsacoDb, WatchList, MAX_WATCHED_NAMES,
+};
+use usaco_standings_scraper::{
+    normalize_country, Division, Graduation, HttpClient, IntlCompetition, IntlMedal, Month,
+    MonthYear, ParseNameError, ParseOptions,
+};
+
+/// Formats `n` as an ordinal, such as "1st" or "12th".
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{n}{suffix}")
+}
+
+/// The smallest contest to show "top X%" for. In smaller contests (such as
+/// recent ones where only a handful of people promoted), every rank is a big
+/// percentage, which says more about the contest than the participant.
+const MIN_TOP_PERCENT_SIZE: u32 = 10;
+
+/// The percentage of participants ranked at or above `rank` out of `size`,
+/// rounded up so that even first place in a big contest is the top 1%.
+fn top_percent(rank: u32, size: u32) -> u32 {
+    (rank * 100).div_ceil(size)
+}
 
 /// Format a [`NameQueryResult`] as a string to display to users. If
 /// `hide_name`, all names will be hidden.
@@ -33,26 +61,6 @@ fn format_name_query_result(
     search_name: &str,
     hide_name: bool,
 ) -> String {
-    fn fmt_month(month: Month) -> &'static str {
-        match month {
-            Month::November => "nov",
-            Month::December => "dec",
-            Month::January => "jan",
-            Month::February => "feb",
-            Month::March => "mar",
-            Month::Open => "open",
-        }
-    }
-
-    fn fmt_division(division: Division) -> &'static str {
-        match division {
-            Division::Bronze => "bronze",
-            Division::Silver => "silver",
-            Division::Gold => "gold",
-            Division::Platinum => "platinum",
-        }
-    }
-
     let mut out = String::new();
 
     macro_rules! outln {
@@ -89,42 +97,48 @@ fn format_name_query_result(
             }
         );
 
-        for c in &p.contests {
-            let season = c.contest_time.year
-                + if matches!(c.contest_time.month, Month::November | Month::December) {
-                    1
-                } else {
-                    0
-                };
-            let grade = match p.id.graduation {
-                Graduation::HighSchool { year } => Some(12 - (year as i32 - season as i32)),
-                Graduation::Observer => None,
-            };
+        let promotions = detect_promotions(&p.contests);
+        for (i, c) in p.contests.iter().enumerate() {
+            let grade = p.id.graduation.grade_at(c.contest_time.season());
+
+            // a participant can have records in multiple divisions of the same contest, in
+            // which case the last (highest division) one is the promotion
+            let promoted = promotions.contains(&c.contest_time)
+                && p.contests
+                    .get(i + 1)
+                    .is_none_or(|next| next.contest_time != c.contest_time);
 
             outln!(
-                "Scored {score} on {month} {year} {division} {grade}",
+                "Scored {score} on {month} {year} {division} {grade}{rank}{promoted}",
                 score = c.score,
-                month = fmt_month(c.contest_time.month),
+                month = c.contest_time.month,
                 year = c.contest_time.year,
-                division = fmt_division(c.division),
+                division = c.division,
                 grade = match grade {
                     Some(grade) => format!("in grade {grade}"),
                     None => "as an observer".to_string(),
-                }
+                },
+                rank = match c.rank {
+                    Some((rank, size)) if size >= MIN_TOP_PERCENT_SIZE => format!(
+                        ", ranked {} of {size} (top {}%)",
+                        ordinal(rank),
+                        top_percent(rank, size)
+                    ),
+                    Some((rank, size)) => format!(", ranked {} of {size}", ordinal(rank)),
+                    None => String::new(),
+                },
+                promoted = if promoted { " (promoted)" } else { "" }
             );
         }
 
         for c in &p.camps {
-            let graduation = match p.id.graduation {
-                Graduation::HighSchool { year } => year,
-                Graduation::Observer => {
+            match p.id.graduation.grade_at(c.camp_year) {
+                Some(grade) => outln!("Camped in {} in grade {grade}", c.camp_year),
+                None => {
                     warn!("camp record from an observer {:?}", p.id);
-                    9999
+                    outln!("Camped in {}", c.camp_year);
                 }
-            };
-            let grade = 12 - (graduation as i32 - c.camp_year as i32);
-
-            outln!("Camped in {} in grade {grade}", c.camp_year);
+            }
         }
         outln!();
     }
@@ -155,16 +169,259 @@ fn format_name_query_result(
     out.trim().to_string()
 }
 
+/// Format the new records of watched participants as a DM to send after an
+/// update.
+fn format_watch_notification(participants: &[&Participant]) -> String {
+    let mut out = "New USACO records were found for names you're watching:\n".to_string();
+
+    for p in participants {
+        out.push_str(&format!(
+            "\n{} from {} {}:\n",
+            p.id.name,
+            p.id.country,
+            match p.id.graduation {
+                Graduation::HighSchool { year } => format!("with graduation year {year}"),
+                Graduation::Observer => "as an observer".to_string(),
+            }
+        ));
+
+        for c in &p.contests {
+            out.push_str(&format!(
+                "- Scored {} on {} {} {}\n",
+                c.score, c.contest_time.month, c.contest_time.year, c.division
+            ));
+        }
+        for c in &p.camps {
+            out.push_str(&format!("- Camped in {}\n", c.camp_year));
+        }
+    }
+
+    out.push_str("\nUse /unwatch to stop getting these messages.");
+    out
+}
+
 struct AppData {
     db: &'static Mutex<UsacoDb>,
-    stats: &'static Mutex<AppStats>,
+    stats: &'static LiveStats,
+    watch_list: &'static Mutex<WatchList>,
+    /// Fixes that are applied to the db whenever it's replaced
+    corrections: Vec<Correction>,
+    aliases: Aliases,
     /// Start of this bot process, used to calculate uptime
     start: Instant,
     application_info: CurrentApplicationInfo,
+    /// Limits each user to [`SEARCHES_PER_MINUTE`] searches
+    search_limiter: RateLimiter,
 }
 
+/// How many searches each user can make per minute, other than the owners
+const SEARCHES_PER_MINUTE: u32 = 10;
+
 type Context<'a> = poise::Context<'a, AppData, anyhow::Error>;
 
+/// Whether the author of `ctx` owns the bot, either directly or through its
+/// team.
+fn is_owner(ctx: Context<'_>) -> bool {
+    let info = &ctx.data().application_info;
+    let author = ctx.author().id;
+
+    info.owner.as_ref().is_some_and(|u| u.id == author)
+        || info
+            .team
+            .as_ref()
+            .is_some_and(|t| t.members.iter().any(|m| m.user.id == author))
+}
+
+/// Splits `text` into chunks of at most `limit` bytes, breaking at line
+/// boundaries. Lines that are too long by themselves are broken up anywhere
+/// other than in the middle of a character. Chunks always have at least one
+/// character, so characters wider than `limit` get chunks of their own.
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current = String::new();
+
+    for mut line in text.lines() {
+        while line.len() > limit {
+            let mut split = limit;
+            while !line.is_char_boundary(split) {
+                split -= 1;
+            }
+            if split == 0 {
+                split = line.chars().next().map_or(0, char::len_utf8);
+            }
+
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(line[..split].to_string());
+            line = &line[split..];
+        }
+
+        if !current.is_empty() && current.len() + 1 + line.len() > limit {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Replies with `text` as plain messages, split up to stay under Discord's 2000
+/// character message limit. Text that would take more than a few messages is
+/// sent as an attachment instead, like `search` does for long results.
+///
+/// Commands with replies that may be long should go through this instead of
+/// [`Context::say`]. Like `say`, replies are ephemeral if and only if the
+/// command's own `ephemeral` attribute is set.
+async fn reply_chunked(ctx: Context<'_>, text: &str) -> anyhow::Result<()> {
+    send_chunked(ctx, text, false).await
+}
+
+/// [`reply_chunked`], with the replies only visible to the author if
+/// `ephemeral` is set (for slash commands). Otherwise, the command's own
+/// default is kept, since poise only falls back to it when a reply leaves
+/// `ephemeral` unset.
+async fn send_chunked(ctx: Context<'_>, text: &str, ephemeral: bool) -> anyhow::Result<()> {
+    /// Discord's limit on the length of a message
+    const MESSAGE_LIMIT: usize = 2000;
+    /// Maximum number of messages to send before falling back to an attachment
+    const MAX_MESSAGES: usize = 3;
+
+    let chunks = split_message(text, MESSAGE_LIMIT);
+    let reply = || {
+        let reply = CreateReply::default();
+        if ephemeral {
+            reply.ephemeral(true)
+        } else {
+            reply
+        }
+    };
+
+    if chunks.len() > MAX_MESSAGES {
+        ctx.send(reply().attachment(CreateAttachment::bytes(text, "result.txt")))
+            .await?;
+    } else {
+        for chunk in chunks {
+            ctx.send(reply().content(chunk)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits `text` into pages of at most `max_lines` lines each, breaking at
+/// blank lines (which separate records in [`format_name_query_result`]) so
+/// that records are only split up if they don't fit on a page by themselves.
+/// Blank lines at page breaks are dropped.
+///
+/// Pages only depend on the number of lines of each record, so the output of
+/// `format_name_query_result` is split the same way regardless of `hide_name`.
+fn split_pages(text: &str, max_lines: usize) -> Vec<String> {
+    let mut pages = vec![];
+    let mut current = Vec::<&str>::new();
+
+    for record in text.split("\n\n") {
+        let lines = record.lines().collect::<Vec<_>>();
+
+        if !current.is_empty() && current.len() + 1 + lines.len() > max_lines {
+            pages.push(std::mem::take(&mut current).join("\n"));
+        }
+        if !current.is_empty() {
+            current.push("");
+        }
+
+        for chunk in lines.chunks(max_lines.max(1)) {
+            if !current.is_empty() && current.len() + chunk.len() > max_lines {
+                pages.push(std::mem::take(&mut current).join("\n"));
+            }
+            current.extend(chunk);
+        }
+    }
+
+    if !current.is_empty() {
+        pages.push(current.join("\n"));
+    }
+
+    pages
+}
+
+/// Sends `pages` as a single message, with buttons to navigate between them.
+/// The buttons get disabled once nobody has pressed them for 5 minutes.
+async fn paginate(ctx: Context<'_>, pages: &[CreateEmbed], ephemeral: bool) -> anyhow::Result<()> {
+    anyhow::ensure!(!pages.is_empty(), "no pages to paginate");
+
+    // the button ids start with the ctx id, so we can tell which presses are for
+    // this message
+    let ctx_id = ctx.id();
+    let buttons = [("first", '⏮'), ("prev", '◀'), ("next", '▶'), ("last", '⏭')]
+        .map(|(name, emoji)| (format!("{ctx_id}{name}"), emoji));
+    let components = |disabled: bool| {
+        vec![CreateActionRow::Buttons(
+            buttons
+                .iter()
+                .map(|(id, emoji)| CreateButton::new(id).emoji(*emoji).disabled(disabled))
+                .collect(),
+        )]
+    };
+
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .embed(pages[0].clone())
+                .components(components(pages.len() == 1))
+                .ephemeral(ephemeral),
+        )
+        .await?;
+    if pages.len() == 1 {
+        return Ok(());
+    }
+
+    let mut current: usize = 0;
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(Duration::from_secs(5 * 60))
+        .await
+    {
+        current = match buttons
+            .iter()
+            .position(|(id, _)| *id == press.data.custom_id)
+        {
+            Some(0) => 0,
+            Some(1) => current.saturating_sub(1),
+            Some(2) => (current + 1).min(pages.len() - 1),
+            Some(3) => pages.len() - 1,
+            _ => continue,
+        };
+
+        press
+            .create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new().embed(pages[current].clone()),
+                ),
+            )
+            .await?;
+    }
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(pages[current].clone())
+                .components(components(true)),
+        )
+        .await?;
+
+    Ok(())
+}
+
 /// Shows this help menu
 #[poise::command(prefix_command, slash_command)]
 async fn help(
@@ -194,109 +451,1049 @@ async fn invite(ctx: Context<'_>) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Check the bot's latency
+/// Check the bot's latency
+#[poise::command(prefix_command, slash_command)]
+async fn ping(ctx: Context<'_>) -> anyhow::Result<()> {
+    let now = Instant::now();
+
+    let msg = ctx.say(":ping_pong:").await?;
+    msg.edit(
+        ctx,
+        CreateReply::default().content(format!(
+            ":ping_pong:\nroundtrip: {}ms\ngateway: {}ms",
+            now.elapsed().as_millis(),
+            ctx.ping().await.as_millis()
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Suggests participant names matching what's been typed so far, for slash
+/// command name arguments. Nothing is suggested when names are being hidden,
+/// since suggestions would give away other names.
+async fn autocomplete_name(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    /// Discord's limit on the number of autocomplete choices
+    const MAX_CHOICES: usize = 25;
+
+    let hide_name = match ctx {
+        Context::Application(ctx) => ctx.args.iter().any(|o| {
+            o.name == "hide_name" && matches!(o.value, serenity::ResolvedValue::Boolean(true))
+        }),
+        Context::Prefix(_) => false,
+    };
+    if hide_name || partial.contains("+hide") {
+        return vec![];
+    }
+
+    ctx.data()
+        .db
+        .lock()
+        .await
+        .autocomplete_names(partial, MAX_CHOICES)
+}
+
+/// The `options` whose names (as formatted by their `Display` impls) could be
+/// what's been typed so far, such as "dec" for "de" or "december".
+fn matching_choices<T: ToString>(options: &[T], partial: &str) -> Vec<String> {
+    let partial = partial.trim().to_lowercase();
+
+    options
+        .iter()
+        .map(|o| o.to_string())
+        .filter(|name| name.starts_with(&partial) || partial.starts_with(name.as_str()))
+        .collect()
+}
+
+/// Suggests contest months for slash command month arguments.
+async fn autocomplete_month(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    matching_choices(&Month::ALL, partial)
+}
+
+/// Suggests divisions for slash command division arguments.
+async fn autocomplete_division(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    matching_choices(&Division::ALL, partial)
+}
+
+/// A division to filter search results by. Prefix commands take it as a
+/// marker like "+gold" so that names starting with a division, such as
+/// "Silver Chen", are still searched as names. Slash commands take the
+/// division on its own, since it's a separate option there.
+#[derive(Debug, Copy, Clone)]
+struct DivisionFilter(Division);
+
+impl FromStr for DivisionFilter {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        s.strip_prefix('+').unwrap_or(s).parse().map(Self)
+    }
+}
+
+#[poise::async_trait]
+impl<'a> poise::PopArgument<'a> for DivisionFilter {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        _ctx: &serenity::Context,
+        _msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let (word, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let Some(division) = word.strip_prefix('+') else {
+            return Err((
+                "division filters start with a +".into(),
+                Some(word.to_string()),
+            ));
+        };
+
+        match division.parse() {
+            Ok(division) => Ok((rest.trim_start(), attachment_index, Self(division))),
+            Err(e) => Err((Box::new(e), Some(word.to_string()))),
+        }
+    }
+}
+
+/// Suggests competitions for slash command IOI/EGOI arguments.
+async fn autocomplete_competition(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    matching_choices(&IntlCompetition::ALL, partial)
+}
+
+/// Lookup USACO records for a given name
+///
+/// Use slash commands if you want names in result to be hidden, or for the \
+/// result to be only visible to you.
+///
+/// Results can be narrowed down to a division and range of years, which go \
+/// before the name. For example, "s;search +gold 2020 2022 john doe" only \
+/// shows John Doe's gold contests from 2020 to 2022, along with any camps \
+/// and IOI or EGOI results in those years.
+///
+/// Note that recent bronze and silver promotions may not be reported since \
+/// USACO stopped releasing them.
+///
+/// The bot will update its response if you edit your command, and the bot \
+/// will delete its response if you delete your message.
+#[poise::command(prefix_command, slash_command, track_edits)]
+async fn search(
+    ctx: Context<'_>,
+    #[flag]
+    #[description = "Hide name in response"]
+    mut hide_name: bool,
+    #[description = "Should result only be shown to you? (slash command only)"] private: Option<
+        bool,
+    >,
+    #[description = "Only show contests in this division"]
+    #[autocomplete = "autocomplete_division"]
+    division: Option<DivisionFilter>,
+    #[description = "Only show records from this year or later"] from_year: Option<u16>,
+    #[description = "Only show records from this year or earlier"] to_year: Option<u16>,
+    #[rest]
+    #[description = "Full name to look up (case-insensitive)"]
+    #[autocomplete = "autocomplete_name"]
+    mut name: String,
+) -> anyhow::Result<()> {
+    /// Maximum number of similar names to suggest when nothing is found
+    const MAX_SUGGESTIONS: usize = 3;
+    /// Number of lines per page of results too long for a single embed
+    const PAGE_LINES: usize = 40;
+
+    if !is_owner(ctx) {
+        if let Err(wait) = ctx
+            .data()
+            .search_limiter
+            .check(ctx.author().id, Instant::now())
+        {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!(
+                        "Slow down! You can search again in {}s.",
+                        wait.as_secs_f64().ceil()
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    }
+
+    let new_query = match ctx {
+        // avoid double counting caused by edit tracking
+        Context::Prefix(pref) => pref.msg.edited_timestamp.is_none(),
+        _ => true,
+    };
+    if new_query {
+        ctx.data().stats.record_query(ctx.author().id);
+    }
+
+    let private = private.unwrap_or_default();
+
+    // poise won't parse something like `s;search john doe +hide`, but we can just
+    // deal with this manually.
+    if name.contains("+hide") {
+        hide_name = true;
+        name = name.replace("+hide", "");
+    }
+
+    // we should be safe against any response hijacking, since we shouldn't be able
+    // to ping anyone in our embeds, but let's still do this just to be safe.
+    name = name.replace('`', "");
+
+    // whoever searched for a hidden name didn't want it shown, so it shouldn't
+    // show up in /trending either
+    if new_query && !hide_name {
+        ctx.data().stats.record_name_query(&name);
+    }
+
+    let (res, cutoff, suggestions) = {
+        let db = ctx.data().db.lock().await;
+        let res = db.query_name(&name);
+
+        // suggestions would give away other names, so they're left out when
+        // names are hidden
+        let suggestions = if res.participants.is_empty()
+            && res.ioi.is_empty()
+            && res.egoi.is_empty()
+            && !hide_name
+        {
+            // several records can share a name, so take extra to have enough
+            // distinct ones
+            let mut names = db
+                .query_name_fuzzy(&name, 5 * MAX_SUGGESTIONS)
+                .into_iter()
+                .map(|(_, id)| id.name)
+                .collect::<Vec<_>>();
+            names.dedup();
+            names.truncate(MAX_SUGGESTIONS);
+            names
+        } else {
+            vec![]
+        };
+
+        let years = (from_year.is_some() || to_year.is_some())
+            .then(|| from_year.unwrap_or(0)..=to_year.unwrap_or(u16::MAX));
+
+        (
+            res.filtered(division.map(|d| d.0), years),
+            db.promotion_data_cutoff(),
+            suggestions,
+        )
+    };
+    // people who competed after bronze and silver results stopped being released
+    // may be missing promotions
+    let missing_promotions = cutoff.filter(|&cutoff| {
+        res.participants
+            .iter()
+            .flat_map(|p| &p.contests)
+            .any(|c| c.contest_time > cutoff)
+    });
+    let res = format_name_query_result(&res, &name, hide_name);
+
+    // max length of embed description is 4096
+    if res.len() <= 4000 {
+        let mut embed = CreateEmbed::new()
+            .title("USACO Standings Search Result")
+            .color(Color::BLUE)
+            .description(format!("```{res}```",));
+
+        if name.to_lowercase().starts_with("name") {
+            embed = embed.footer(CreateEmbedFooter::new(
+                r#"hint: this command was recently refactored. perhaps you wanted to do s;search <name>, for example "s;search benjamin qi". alternatively, use /search"#,
+            ));
+        } else if !suggestions.is_empty() {
+            embed = embed.footer(CreateEmbedFooter::new(format!(
+                "did you mean {}?",
+                suggestions.join(", ")
+            )));
+        } else if let Some(cutoff) = missing_promotions {
+            embed = embed.footer(CreateEmbedFooter::new(format!(
+                "note: bronze and silver promotions after {} {} aren't reported since USACO \
+                stopped releasing them",
+                cutoff.month, cutoff.year
+            )));
+        }
+
+        ctx.send(CreateReply::default().embed(embed).ephemeral(private))
+            .await?;
+    } else {
+        let pages = split_pages(&res, PAGE_LINES);
+
+        if pages.iter().any(|p| p.len() > 4000) {
+            // only happens if a single record is too long for an embed, which
+            // would take a lot of very long lines
+            send_chunked(ctx, &res, private).await?;
+        } else {
+            let embeds = pages
+                .iter()
+                .enumerate()
+                .map(|(i, page)| {
+                    let mut footer = format!("Page {}/{}", i + 1, pages.len());
+                    if let Some(cutoff) = missing_promotions {
+                        footer += &format!(
+                            ". note: bronze and silver promotions after {} {} aren't reported \
+                            since USACO stopped releasing them",
+                            cutoff.month, cutoff.year
+                        );
+                    }
+
+                    CreateEmbed::new()
+                        .title("USACO Standings Search Result")
+                        .color(Color::BLUE)
+                        .description(format!("```{page}```"))
+                        .footer(CreateEmbedFooter::new(footer))
+                })
+                .collect::<Vec<_>>();
+
+            paginate(ctx, &embeds, private).await?;
+        }
+    }
+
+    // TODO: implement name hiding with prefix commands properly
+    // if hide_name {
+    //     if let Context::Prefix(pref) = ctx {
+    //         pref.msg.delete(ctx.http()).await.ok();
+    //     }
+    // }
+
+    Ok(())
+}
+
+/// Show a compact summary card of USACO records for a given name
+///
+/// Use slash commands if you want the name in the card to be hidden, or for \
+/// the card to be only visible to you.
+#[poise::command(prefix_command, slash_command, track_edits)]
+async fn card(
+    ctx: Context<'_>,
+    #[flag]
+    #[description = "Hide name in response"]
+    mut hide_name: bool,
+    #[description = "Should result only be shown to you? (slash command only)"] private: Option<
+        bool,
+    >,
+    #[rest]
+    #[description = "Full name to look up (case-insensitive)"]
+    #[autocomplete = "autocomplete_name"]
+    mut name: String,
+) -> anyhow::Result<()> {
+    let private = private.unwrap_or_default();
+
+    // same manual flag handling and sanitization as in `search`
+    if name.contains("+hide") {
+        hide_name = true;
+        name = name.replace("+hide", "");
+    }
+    name = name.replace('`', "");
+
+    let res = ctx.data().db.lock().await.query_name(&name);
+
+    let embed = CreateEmbed::new()
+        .title("USACO Standings Card")
+        .color(Color::BLUE)
+        .description(format!("```{}```", res.summary_card(hide_name)));
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(private))
+        .await?;
+
+    Ok(())
+}
+
+/// Compare the USACO records of two people side by side
+///
+/// For example, "s;compare Jane Doe vs John Smith". Use slash commands if \
+/// you want the names to be hidden, or for the comparison to be only \
+/// visible to you.
+#[poise::command(prefix_command, slash_command, track_edits)]
+async fn compare(
+    ctx: Context<'_>,
+    #[flag]
+    #[description = "Hide names in response"]
+    mut hide_name: bool,
+    #[description = "Should result only be shown to you? (slash command only)"] private: Option<
+        bool,
+    >,
+    #[rest]
+    #[description = "Two full names separated by \"vs\" (case-insensitive)"]
+    mut names: String,
+) -> anyhow::Result<()> {
+    let private = private.unwrap_or_default();
+
+    // same manual flag handling and sanitization as in `search`
+    if names.contains("+hide") {
+        hide_name = true;
+        names = names.replace("+hide", "");
+    }
+    names = names.replace('`', "");
+
+    // the separator is matched case-insensitively, and lowercasing doesn't
+    // change the byte offsets of ascii
+    let Some(split) = names.to_ascii_lowercase().find(" vs ") else {
+        reply_chunked(
+            ctx,
+            "Separate the two names with \"vs\". Use /help compare for usage.",
+        )
+        .await?;
+        return Ok(());
+    };
+    let queries = [names[..split].trim(), names[split + " vs ".len()..].trim()];
+
+    let [a, b] = {
+        let db = ctx.data().db.lock().await;
+        queries.map(|name| db.query_name(name))
+    };
+
+    // merging different people into one column would be misleading
+    let mut problems = vec![];
+    for (i, (query, res)) in queries.iter().zip([&a, &b]).enumerate() {
+        let name = if hide_name {
+            format!("Name {}", i + 1)
+        } else {
+            format!("\"{query}\"")
+        };
+
+        if res.participants.is_empty() && res.ioi.is_empty() && res.egoi.is_empty() {
+            problems.push(format!("No USACO records found for {name}."));
+        } else if res.participants.len() > 1 {
+            problems.push(format!(
+                "{name} matches {} different people. Use /search to tell them apart.",
+                res.participants.len()
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content(problems.join("\n"))
+                .ephemeral(private),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let embed = CreateEmbed::new()
+        .title("USACO Standings Comparison")
+        .color(Color::BLUE)
+        .description(format!("```{}```", comparison_table(&a, &b, hide_name)));
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(private))
+        .await?;
+
+    Ok(())
+}
+
+/// Show a summary card of USACO records for a given name as an image
+///
+/// If several people share the name, the card is of the first one, in the \
+/// same order as /search.
+#[cfg(feature = "image")]
+#[poise::command(prefix_command, slash_command)]
+async fn cardimg(
+    ctx: Context<'_>,
+    #[flag]
+    #[description = "Hide name in response"]
+    mut hide_name: bool,
+    #[description = "Should result only be shown to you? (slash command only)"] private: Option<
+        bool,
+    >,
+    #[rest]
+    #[description = "Full name to look up (case-insensitive)"]
+    #[autocomplete = "autocomplete_name"]
+    mut name: String,
+) -> anyhow::Result<()> {
+    let private = private.unwrap_or_default();
+
+    // same manual flag handling and sanitization as in `search`
+    if name.contains("+hide") {
+        hide_name = true;
+        name = name.replace("+hide", "");
+    }
+    name = name.replace('`', "");
+
+    let profiles = ctx.data().db.lock().await.query_name(&name).profiles();
+    let Some(profile) = profiles.first() else {
+        reply_chunked(ctx, "No USACO records found for that name.").await?;
+        return Ok(());
+    };
+
+    // rendering is cpu bound, so keep it off the async runtime
+    let png = tokio::task::block_in_place(|| {
+        usaco_standings_bot::card_image::render_profile_card(profile, hide_name)
+    });
+
+    ctx.send(
+        CreateReply::default()
+            .attachment(CreateAttachment::bytes(png, "card.png"))
+            .ephemeral(private),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Show the leaderboard of a contest
+///
+/// For example, "s;contest open 2024 platinum" shows the results of the 2024 \
+/// US Open platinum contest.
+///
+/// Note that recent leaderboards only contain participants who promoted, \
+/// since that's all USACO releases.
+#[poise::command(prefix_command, slash_command)]
+async fn contest(
+    ctx: Context<'_>,
+    #[description = "Month of the contest (nov, dec, jan, feb, mar, or open)"]
+    #[autocomplete = "autocomplete_month"]
+    month: String,
+    #[description = "Year the contest was held"] year: u16,
+    #[description = "Division (bronze, silver, gold, or platinum)"]
+    #[autocomplete = "autocomplete_division"]
+    division: String,
+) -> anyhow::Result<()> {
+    /// Number of participants listed per page
+    const PAGE_SIZE: usize = 25;
+
+    let (Ok(month), Ok(division)) = (month.parse::<Month>(), division.parse::<Division>()) else {
+        reply_chunked(
+            ctx,
+            "Unrecognized month or division. Use /help contest for usage.",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let lines = {
+        let db = ctx.data().db.lock().await;
+        let leaderboard = db.contest_participants(MonthYear { year, month }, division);
+
+        leaderboard
+            .iter()
+            .map(|&(id, score)| {
+                // participants with the same score share a rank
+                let rank = 1 + leaderboard.partition_point(|&(_, s)| s > score);
+                format!("{rank:>4}. {score:>4} {} ({})", id.name, id.country)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    if lines.is_empty() {
+        reply_chunked(ctx, "No results found for that contest.").await?;
+        return Ok(());
+    }
+
+    let page_count = lines.len().div_ceil(PAGE_SIZE);
+    let pages = lines
+        .chunks(PAGE_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            CreateEmbed::new()
+                .title(format!("USACO {month} {year} {division} Results"))
+                .color(Color::BLUE)
+                .description(format!("```{}```", chunk.join("\n")))
+                .footer(CreateEmbedFooter::new(format!(
+                    "Page {}/{page_count}, {} participants",
+                    i + 1,
+                    lines.len()
+                )))
+        })
+        .collect::<Vec<_>>();
+
+    paginate(ctx, &pages, false).await
+}
+
+/// Show the score distribution of a contest
+///
+/// For example, "s;histogram open 2024 platinum 100" shows how many \
+/// participants of the 2024 US Open platinum contest scored 0-99, 100-199, \
+/// and so on.
+///
+/// Note that recent contests only include participants who promoted, since \
+/// that's all USACO releases.
+#[poise::command(prefix_command, slash_command)]
+async fn histogram(
+    ctx: Context<'_>,
+    #[description = "Month of the contest (nov, dec, jan, feb, mar, or open)"]
+    #[autocomplete = "autocomplete_month"]
+    month: String,
+    #[description = "Year the contest was held"] year: u16,
+    #[description = "Division (bronze, silver, gold, or platinum)"]
+    #[autocomplete = "autocomplete_division"]
+    division: String,
+    #[description = "Width of each score range (defaults to 100)"] bucket_size: Option<u16>,
+) -> anyhow::Result<()> {
+    /// Length of the longest bar, in characters
+    const BAR_WIDTH: usize = 30;
+
+    let (Ok(month), Ok(division)) = (month.parse::<Month>(), division.parse::<Division>()) else {
+        reply_chunked(
+            ctx,
+            "Unrecognized month or division. Use /help histogram for usage.",
+        )
+        .await?;
+        return Ok(());
+    };
+    // tiny buckets would make for thousands of lines
+    let bucket_size = bucket_size.unwrap_or(100).max(25);
+
+    let histogram = ctx.data().db.lock().await.score_histogram(
+        MonthYear { year, month },
+        division,
+        bucket_size,
+    );
+    let Some(histogram) = histogram else {
+        reply_chunked(ctx, "No results found for that contest.").await?;
+        return Ok(());
+    };
+
+    let max_count = histogram.iter().map(|&(_, c)| c).max().unwrap_or(0).max(1);
+    let total = histogram.iter().map(|&(_, c)| c).sum::<usize>();
+    let chart = histogram
+        .iter()
+        .map(|&(start, count)| {
+            // rounded up so that nonempty buckets always get a bar
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(max_count));
+            format!(
+                "{start:>4}-{end:<4} {bar:<BAR_WIDTH$} {count}",
+                end = start.saturating_add(bucket_size - 1)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(format!(
+                    "USACO {month} {year} {division} Score Distribution"
+                ))
+                .color(Color::BLUE)
+                .description(format!("```{chart}```"))
+                .footer(CreateEmbedFooter::new(format!("{total} participants"))),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Show the highest scores ever in a division
+///
+/// For example, "s;leaderboard platinum" shows the best platinum scores of \
+/// all time, and "s;leaderboard platinum 2024" only those of the 2023-24 \
+/// season. Each person is listed once, with their best score.
+#[poise::command(prefix_command, slash_command)]
+async fn leaderboard(
+    ctx: Context<'_>,
+    #[description = "Division (bronze, silver, gold, or platinum)"]
+    #[autocomplete = "autocomplete_division"]
+    division: String,
+    #[description = "Only include this season, named after the year it ends"] season: Option<u16>,
+) -> anyhow::Result<()> {
+    /// Number of participants listed
+    const LIMIT: usize = 25;
+
+    let Ok(division) = division.parse::<Division>() else {
+        reply_chunked(
+            ctx,
+            "Unrecognized division. Use /help leaderboard for usage.",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let top = ctx
+        .data()
+        .db
+        .lock()
+        .await
+        .top_scores(division, season, LIMIT);
+    if top.is_empty() {
+        // most likely platinum before it existed, or a season not scraped yet
+        reply_chunked(ctx, "No records found for that division.").await?;
+        return Ok(());
+    }
+
+    let lines = top
+        .iter()
+        .map(|(id, score, MonthYear { year, month })| {
+            // participants with the same score share a rank
+            let rank = 1 + top.partition_point(|(_, s, _)| s > score);
+            format!(
+                "{rank:>2}. {score:>4} {} ({}), {month} {year}",
+                id.name, id.country
+            )
+        })
+        .collect::<Vec<_>>();
+    let title = match season {
+        Some(season) => format!(
+            "Top USACO {division} Scores of {}-{season}",
+            season.saturating_sub(1)
+        ),
+        None => format!("Top USACO {division} Scores"),
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(title)
+                .color(Color::BLUE)
+                .description(format!("```{}```", lines.join("\n"))),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Show the participants from a country
+///
+/// For example, "s;country canada" lists Canadian participants, best first, \
+/// along with how many peaked in each division. Countries can be given by \
+/// name or by code, like "CAN" or "united kingdom". Since so many \
+/// participants are from the USA, "s;country 100 usa" only lists the top 100, \
+/// though the counts still include everyone.
+#[poise::command(prefix_command, slash_command)]
+async fn country(
+    ctx: Context<'_>,
+    #[description = "Only list this many participants"]
+    #[min = 1]
+    limit: Option<usize>,
+    #[description = "Country name or code"]
+    #[rest]
+    country: String,
+) -> anyhow::Result<()> {
+    /// Number of participants listed per page
+    const PAGE_SIZE: usize = 25;
+
+    // prefix commands don't enforce the minimum
+    if limit == Some(0) {
+        reply_chunked(ctx, "The limit must be at least 1.").await?;
+        return Ok(());
+    }
+
+    let (counts, lines) = {
+        let db = ctx.data().db.lock().await;
+        let participants = db.query_country(&country);
+
+        // indexed by division, with participants without contests at the end
+        let mut counts = [0; Division::ALL.len() + 1];
+        for p in &participants {
+            match p.contests.iter().map(|c| c.division).max() {
+                Some(division) => counts[division as usize] += 1,
+                None => counts[Division::ALL.len()] += 1,
+            }
+        }
+
+        let lines = participants
+            .iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|p| {
+                let peak = p
+                    .contests
+                    .iter()
+                    .map(|c| (c.division, c.score))
+                    .max()
+                    .map_or("no contests".to_string(), |(division, score)| {
+                        format!("{division} {score}")
+                    });
+                let graduation = match p.id.graduation {
+                    Graduation::HighSchool { year } => format!("class of {year}"),
+                    Graduation::Observer => "observer".to_string(),
+                };
+
+                format!("{} ({graduation}): {peak}", p.id.name)
+            })
+            .collect::<Vec<_>>();
+
+        (counts, lines)
+    };
+
+    if lines.is_empty() {
+        reply_chunked(ctx, "No participants found from that country.").await?;
+        return Ok(());
+    }
+
+    let total = counts.iter().sum::<usize>();
+    let mut summary = Division::ALL
+        .iter()
+        .rev()
+        .map(|&division| format!("{division}: {}", counts[division as usize]))
+        .collect::<Vec<_>>();
+    if counts[Division::ALL.len()] > 0 {
+        summary.push(format!("no contests: {}", counts[Division::ALL.len()]));
+    }
+    let summary = format!("Peak divisions: {}", summary.join(", "));
+
+    let page_count = lines.len().div_ceil(PAGE_SIZE);
+    let pages = lines
+        .chunks(PAGE_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            CreateEmbed::new()
+                .title(format!(
+                    "USACO Participants from {}",
+                    normalize_country(&country)
+                ))
+                .color(Color::BLUE)
+                .description(format!("{summary}\n```{}```", chunk.join("\n")))
+                .footer(CreateEmbedFooter::new(format!(
+                    "Page {}/{page_count}, {} of {total} participants listed",
+                    i + 1,
+                    lines.len()
+                )))
+        })
+        .collect::<Vec<_>>();
+
+    paginate(ctx, &pages, false).await
+}
+
+/// Show the participants graduating in some year
+///
+/// For example, "s;class 2026" lists everyone graduating high school in \
+/// 2026, best first, along with their camps and IOI and EGOI appearances. \
+/// Participants whose graduation year doesn't fit their records, which is \
+/// usually a typo on a results page, are marked with a "?".
+#[poise::command(prefix_command, slash_command)]
+async fn class(
+    ctx: Context<'_>,
+    #[description = "Year of graduation from high school"] year: u16,
+) -> anyhow::Result<()> {
+    /// Number of participants listed per page
+    const PAGE_SIZE: usize = 25;
+
+    let lines = {
+        let db = ctx.data().db.lock().await;
+
+        db.query_graduation(year)
+            .into_iter()
+            .map(|p| {
+                let summary = db.participant_summary(p);
+
+                let mut highlights = vec![match summary.best_division.zip(summary.best_score) {
+                    Some((division, score)) => format!("{division} {score}"),
+                    None => "no contests".to_string(),
+                }];
+                if summary.camps > 0 {
+                    highlights.push(format!("camp x{}", summary.camps));
+                }
+                for (comp, m) in [("IOI", summary.ioi), ("EGOI", summary.egoi)] {
+                    if m.appearances > 0 {
+                        highlights.push(format!("{comp} {}G {}S {}B", m.gold, m.silver, m.bronze));
+                    }
+                }
+
+                let flag = if p.has_implausible_graduation() {
+                    "?"
+                } else {
+                    ""
+                };
+                format!(
+                    "{}{flag} ({}): {}",
+                    p.id.name,
+                    p.id.country,
+                    highlights.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    if lines.is_empty() {
+        reply_chunked(ctx, "No participants found graduating that year.").await?;
+        return Ok(());
+    }
+
+    let page_count = lines.len().div_ceil(PAGE_SIZE);
+    let pages = lines
+        .chunks(PAGE_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            CreateEmbed::new()
+                .title(format!("USACO Class of {year}"))
+                .color(Color::BLUE)
+                .description(format!("```{}```", chunk.join("\n")))
+                .footer(CreateEmbedFooter::new(format!(
+                    "Page {}/{page_count}, {} participants",
+                    i + 1,
+                    lines.len()
+                )))
+        })
+        .collect::<Vec<_>>();
+
+    paginate(ctx, &pages, false).await
+}
+
+/// Show the finalists of a USACO camp
+///
+/// For example, "s;camp 2024" lists the finalists of the 2024 camp, held at \
+/// the end of the 2023-24 season, along with their schools. EGOI finalists \
+/// are marked.
 #[poise::command(prefix_command, slash_command)]
-async fn ping(ctx: Context<'_>) -> anyhow::Result<()> {
-    let now = Instant::now();
+async fn camp(
+    ctx: Context<'_>,
+    #[description = "Year the camp was held"] year: u16,
+) -> anyhow::Result<()> {
+    /// Number of finalists listed per page
+    const PAGE_SIZE: usize = 25;
+
+    let lines = ctx
+        .data()
+        .db
+        .lock()
+        .await
+        .camp_roster(year)
+        .into_iter()
+        .map(|(id, c)| {
+            let mut line = id.name.clone();
+            // school and state aren't known for records from old db files
+            if !c.school.is_empty() {
+                line += &format!(", {} ({})", c.school, c.state);
+            }
+            if c.is_egoi {
+                line += " [EGOI]";
+            }
+            line
+        })
+        .collect::<Vec<_>>();
 
-    let msg = ctx.say(":ping_pong:").await?;
-    msg.edit(
-        ctx,
-        CreateReply::default().content(format!(
-            ":ping_pong:\nroundtrip: {}ms\ngateway: {}ms",
-            now.elapsed().as_millis(),
-            ctx.ping().await.as_millis()
-        )),
-    )
-    .await?;
+    if lines.is_empty() {
+        reply_chunked(ctx, "No finalists found for that year.").await?;
+        return Ok(());
+    }
 
-    Ok(())
+    let page_count = lines.len().div_ceil(PAGE_SIZE);
+    let pages = lines
+        .chunks(PAGE_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            CreateEmbed::new()
+                .title(format!("USACO {year} Camp Finalists"))
+                .color(Color::BLUE)
+                .description(format!("```{}```", chunk.join("\n")))
+                .footer(CreateEmbedFooter::new(format!(
+                    "Page {}/{page_count}, {} finalists",
+                    i + 1,
+                    lines.len()
+                )))
+        })
+        .collect::<Vec<_>>();
+
+    paginate(ctx, &pages, false).await
 }
 
-/// Lookup USACO records for a given name
+/// Show the camp finalists from a school
 ///
-/// Use slash commands if you want names in result to be hidden, or for the \
-/// result to be only visible to you.
-///
-/// Note that recent bronze and silver promotions may not be reported since \
-/// USACO stopped releasing them.
-///
-/// The bot will update its response if you edit your command, and the bot \
-/// will delete its response if you delete your message.
-#[poise::command(prefix_command, slash_command, track_edits)]
-async fn search(
+/// For example, "s;school lynbrook" lists finalists from schools with \
+/// "Lynbrook" in their name, along with the camps they attended. Case, \
+/// punctuation, and abbreviations like "HS" don't matter. Schools are only \
+/// known for camp finalists, so other participants aren't listed.
+#[poise::command(prefix_command, slash_command)]
+async fn school(
     ctx: Context<'_>,
-    #[flag]
-    #[description = "Hide name in response"]
-    mut hide_name: bool,
-    #[description = "Should result only be shown to you? (slash command only)"] private: Option<
-        bool,
-    >,
+    #[description = "Name of the school"]
     #[rest]
-    #[description = "Full name to look up (case-insensitive)"]
-    mut name: String,
+    school: String,
 ) -> anyhow::Result<()> {
-    {
-        let new_query = match ctx {
-            // avoid double counting caused by edit tracking
-            Context::Prefix(pref) => pref.msg.edited_timestamp.is_none(),
-            _ => true,
-        };
-
-        if new_query {
-            let mut stats = ctx.data().stats.lock().await;
-
-            stats.query_count += 1;
-            *stats.users_queried.entry(ctx.author().id).or_default() += 1;
-        }
-    }
-
-    let private = private.unwrap_or_default();
+    /// Number of finalists listed per page
+    const PAGE_SIZE: usize = 25;
+
+    let lines = ctx
+        .data()
+        .db
+        .lock()
+        .await
+        .query_school(&school)
+        .into_iter()
+        .map(|p| {
+            let mut camps = p.camps.iter().collect::<Vec<_>>();
+            camps.sort_unstable_by_key(|c| c.camp_year);
+            let camps = camps
+                .into_iter()
+                .map(|c| format!("{} ({}, {})", c.camp_year, c.school, c.state))
+                .collect::<Vec<_>>();
+
+            format!(
+                "{}, {}: {}",
+                p.id.name,
+                match p.id.graduation {
+                    Graduation::HighSchool { year } => format!("class of {year}"),
+                    Graduation::Observer => "observer".to_string(),
+                },
+                camps.join(", ")
+            )
+        })
+        .collect::<Vec<_>>();
 
-    // poise won't parse something like `s;search john doe +hide`, but we can just
-    // deal with this manually.
-    if name.contains("+hide") {
-        hide_name = true;
-        name = name.replace("+hide", "");
+    if lines.is_empty() {
+        reply_chunked(ctx, "No finalists found from that school.").await?;
+        return Ok(());
     }
 
-    // we should be safe against any response hijacking, since we shouldn't be able
-    // to ping anyone in our embeds, but let's still do this just to be safe.
-    name = name.replace('`', "");
-
-    let res = ctx.data().db.lock().await.query_name(&name);
-    let res = format_name_query_result(&res, &name, hide_name);
+    let page_count = lines.len().div_ceil(PAGE_SIZE);
+    let pages = lines
+        .chunks(PAGE_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            CreateEmbed::new()
+                .title("Camp Finalists by School")
+                .color(Color::BLUE)
+                .description(format!("```{}```", chunk.join("\n")))
+                .footer(CreateEmbedFooter::new(format!(
+                    "Page {}/{page_count}, {} finalists",
+                    i + 1,
+                    lines.len()
+                )))
+        })
+        .collect::<Vec<_>>();
 
-    // max length of embed description is 4096
-    if res.len() <= 4000 {
-        let mut embed = CreateEmbed::new()
-            .title("USACO Standings Search Result")
-            .color(Color::BLUE)
-            .description(format!("```{res}```",));
+    paginate(ctx, &pages, false).await
+}
 
-        if name.to_lowercase().starts_with("name") {
-            embed = embed.footer(CreateEmbedFooter::new(
-                r#"hint: this command was recently refactored. perhaps you wanted to do s;search <name>, for example "s;search benjamin qi". alternatively, use /search"#,
-            ));
-        }
+/// Show the US team at an IOI or EGOI
+///
+/// For example, "s;team ioi 2024" lists the US team at IOI 2024 along with \
+/// their medals.
+#[poise::command(prefix_command, slash_command)]
+async fn team(
+    ctx: Context<'_>,
+    #[description = "Competition (ioi or egoi)"]
+    #[autocomplete = "autocomplete_competition"]
+    competition: String,
+    #[description = "Year the competition was held"] year: u16,
+) -> anyhow::Result<()> {
+    let Ok(competition) = competition.parse::<IntlCompetition>() else {
+        reply_chunked(ctx, "Unrecognized competition. Use /help team for usage.").await?;
+        return Ok(());
+    };
 
-        ctx.send(CreateReply::default().embed(embed).ephemeral(private))
-            .await?;
-    } else {
-        ctx.send(
-            CreateReply::default()
-                .attachment(CreateAttachment::bytes(res, "result.txt"))
-                .ephemeral(private),
-        )
-        .await?;
+    let team = ctx.data().db.lock().await.intl_team(competition, year);
+    if team.is_empty() {
+        reply_chunked(ctx, "No team found for that year.").await?;
+        return Ok(());
     }
 
-    // TODO: implement name hiding with prefix commands properly
-    // if hide_name {
-    //     if let Context::Prefix(pref) = ctx {
-    //         pref.msg.delete(ctx.http()).await.ok();
-    //     }
-    // }
+    let lines = team
+        .iter()
+        .map(|r| {
+            let result = match r.result {
+                IntlMedal::VisaIssue => "qualified but did not attend",
+                IntlMedal::NoMedal => "no medal",
+                IntlMedal::Bronze => "bronze medal",
+                IntlMedal::Silver => "silver medal",
+                IntlMedal::Gold => "gold medal",
+            };
+            format!("{}: {result}", r.name)
+        })
+        .collect::<Vec<_>>();
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(format!("US Team at {competition} {year}"))
+                .color(Color::BLUE)
+                .description(format!("```{}```", lines.join("\n"))),
+        ),
+    )
+    .await?;
 
     Ok(())
 }
@@ -311,7 +1508,7 @@ async fn botinfo(ctx: Context<'_>) -> anyhow::Result<()> {
 
     let data = ctx.data();
     let db = data.db.lock().await;
-    let stats = data.stats.lock().await;
+    let stats = data.stats;
 
     let embed = CreateEmbed::new()
         .description(&data.application_info.description)
@@ -323,8 +1520,8 @@ async fn botinfo(ctx: Context<'_>) -> anyhow::Result<()> {
             readable::up::UptimeFull::from(data.start.elapsed()).to_string(),
             true,
         )
-        .field("Queries Made", stats.query_count.to_string(), true)
-        .field("Users Queried", stats.users_queried.len().to_string(), true)
+        .field("Queries Made", stats.query_count().to_string(), true)
+        .field("Users Queried", stats.users_queried().to_string(), true)
         .field("Server Count", ctx.cache().guild_count().to_string(), true)
         .field(
             "User Count",
@@ -341,9 +1538,17 @@ async fn botinfo(ctx: Context<'_>) -> anyhow::Result<()> {
                 .to_string(),
             true,
         )
+        .field(
+            "USACO Records",
+            // the growth is left out until counts go back at least a week
+            match stats.participant_growth_since(Utc::now() - chrono::Duration::weeks(1)) {
+                Some(growth) => format!("{} ({growth:+} since last week)", db.people_count()),
+                None => db.people_count().to_string(),
+            },
+            true,
+        )
         .fields(
             [
-                ("USACO Records", db.people_count()),
                 ("USACO Contest Records", db.contest_count()),
                 ("USACO Camp Records", db.camp_count()),
                 ("IOI Records", db.ioi_people_count()),
@@ -373,20 +1578,91 @@ async fn botinfo(ctx: Context<'_>) -> anyhow::Result<()> {
         );
 
     drop(db);
-    drop(stats);
     ctx.send(CreateReply::default().embed(embed)).await?;
 
     Ok(())
 }
 
+/// Show the most searched names
+///
+/// Names are shown as they were searched, lowercased. This is restricted to \
+/// owners, since what people look up is private.
+#[poise::command(prefix_command, owners_only, hide_in_help)]
+async fn trending(ctx: Context<'_>) -> anyhow::Result<()> {
+    /// Number of names listed
+    const LIMIT: usize = 25;
+
+    let top = ctx.data().stats.top_queried_names(LIMIT);
+    if top.is_empty() {
+        reply_chunked(ctx, "No names have been searched yet.").await?;
+        return Ok(());
+    }
+
+    let lines = top
+        .iter()
+        .enumerate()
+        .map(|(i, (name, count))| format!("{:>2}. {count:>5} {name}", i + 1))
+        .collect::<Vec<_>>();
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Most Searched Names")
+                .color(Color::BLUE)
+                .description(format!("```{}```", lines.join("\n"))),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// How often the IOI and EGOI results are refreshed on their own. This is
+/// only a single request, so it's cheap enough to run daily and pick up new
+/// results within a day of them being posted, without waiting for an update.
+const INTL_HISTORY_REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The client the scraper requests usaco.org through.
+fn usaco_client() -> impl HttpClient<Error = reqwest::Error> + Send + 'static {
+    let client = Client::new();
+
+    usaco_standings_scraper::client_fn(move |url| {
+        let client = client.clone();
+
+        async move {
+            let r = client.get(url).send().await?;
+
+            let status = r.status();
+            let text = r.text().await?;
+
+            Ok::<_, reqwest::Error>((status, text))
+        }
+    })
+}
+
+/// Re-scrapes only the IOI and EGOI results into `db`, leaving contest and
+/// camp results alone.
+async fn refresh_intl_history(db: &Mutex<UsacoDb>, aliases: &Aliases) -> anyhow::Result<()> {
+    let intl_history = usaco_standings_scraper::parse_history(usaco_client()).await?;
+
+    let mut db = db.lock().await;
+    db.replace_intl_history(intl_history);
+    db.apply_aliases(aliases);
+
+    Ok(())
+}
+
 /// Update the USACO standings database
+///
+/// With the `history` flag, only the IOI and EGOI results are updated. \
+/// These are also refreshed on their own once a day.
 #[poise::command(prefix_command, owners_only, hide_in_help)]
-async fn update(ctx: Context<'_>) -> anyhow::Result<()> {
+async fn update(ctx: Context<'_>, #[flag] history: bool) -> anyhow::Result<()> {
     /// Current progress of the parsing
     struct Progress {
         max_year: u16,
-        parsed: u32,
-        total: u32,
+        parsed: usize,
+        total: usize,
     }
 
     impl Progress {
@@ -410,7 +1686,7 @@ async fn update(ctx: Context<'_>) -> anyhow::Result<()> {
                             "{}/{} ({:.0}%)",
                             self.parsed,
                             self.total,
-                            self.parsed as f64 / self.total as f64 * 100.
+                            self.parsed as f64 / self.total.max(1) as f64 * 100.
                         ),
                         true,
                     ),
@@ -418,56 +1694,48 @@ async fn update(ctx: Context<'_>) -> anyhow::Result<()> {
         }
     }
 
-    struct HttpClient {
-        client: Client,
-        progress: Arc<Mutex<Progress>>,
-    }
-
-    impl usaco_standings_scraper::HttpClient for HttpClient {
-        type Error = reqwest::Error;
-        type Future =
-            Pin<Box<dyn Future<Output = Result<(StatusCode, String), Self::Error>> + Send>>;
-
-        fn get(&mut self, url: Url) -> Self::Future {
-            let client = self.client.clone();
-            let progress = self.progress.clone();
-
-            Box::pin(async move {
-                progress.lock().await.total += 1;
-
-                let r = client.get(url).send().await?;
-
-                let status = r.status();
-                let text = r.text().await?;
-
-                progress.lock().await.parsed += 1;
-
-                Ok((status, text))
-            })
-        }
-    }
-
     let now = Utc::now();
     let max_year = now.year() + if now.month() >= 10 { 1 } else { 0 };
     let max_year = max_year.try_into().expect("year shouldn't over/underflow");
 
-    let progress = Arc::new(Mutex::new(Progress {
+    // only ever locked briefly from synchronous code, so a std mutex is fine
+    let progress = Arc::new(std::sync::Mutex::new(Progress {
         max_year,
         parsed: 0,
         total: 0,
     }));
-    let client = HttpClient {
-        client: Client::new(),
-        progress: progress.clone(),
+    let client = usaco_client();
+    let get_message = |finished| {
+        progress
+            .lock()
+            .expect("progress lock shouldn't be poisoned")
+            .get_message(ctx, finished)
     };
 
-    let msg = ctx
-        .send(progress.lock().await.get_message(ctx, false))
-        .await?;
+    if history {
+        refresh_intl_history(ctx.data().db, &ctx.data().aliases).await?;
+        reply_chunked(ctx, "Successfully updated IOI and EGOI results!").await?;
+        return Ok(());
+    }
 
+    let msg = ctx.send(get_message(false)).await?;
+
+    let options = ParseOptions {
+        progress: Some({
+            let progress = progress.clone();
+            Arc::new(move |p| {
+                let mut progress = progress
+                    .lock()
+                    .expect("progress lock shouldn't be poisoned");
+                progress.parsed = p.completed;
+                progress.total = p.total;
+            })
+        }),
+        ..ParseOptions::new(max_year)
+    };
     let (tx, mut rx) = oneshot::channel();
     tokio::spawn(async move {
-        tx.send(usaco_standings_scraper::parse_all(max_year, client).await)
+        tx.send(usaco_standings_scraper::parse_all_with_options(options, client).await)
             .expect("channel should always receive");
     });
 
@@ -478,34 +1746,268 @@ async fn update(ctx: Context<'_>) -> anyhow::Result<()> {
             break res?;
         }
         interval.tick().await;
-        msg.edit(ctx, progress.lock().await.get_message(ctx, false))
-            .await?;
+        msg.edit(ctx, get_message(false)).await?;
+    };
+
+    msg.edit(ctx, get_message(true)).await?;
+
+    let mut db = UsacoDb::from(data);
+    db.apply_corrections(&ctx.data().corrections);
+    db.apply_aliases(&ctx.data().aliases);
+    ctx.data()
+        .stats
+        .record_participant_count(Utc::now(), db.people_count());
+    let diff = {
+        let mut old = ctx.data().db.lock().await;
+        let diff = old.diff(&db);
+        *old = db;
+        diff
+    };
+
+    let notifications = ctx.data().watch_list.lock().await.notifications(&diff);
+    let mut notified = 0;
+    for (user, participants) in notifications {
+        let res = async {
+            // DMs have the same length limit as other messages
+            for chunk in split_message(&format_watch_notification(&participants), 2000) {
+                user.direct_message(ctx, CreateMessage::new().content(chunk))
+                    .await?;
+            }
+
+            anyhow::Ok(())
+        }
+        .await;
+
+        match res {
+            Ok(()) => notified += 1,
+            Err(e) => warn!("failed to notify {user} of watched records: {e:?}"),
+        }
+    }
+
+    reply_chunked(
+        ctx,
+        &format!(
+            "Successfully finished parsing in {:.2} seconds! Found new records for {} \
+            participant(s), and notified {notified} watcher(s).",
+            (Utc::now() - now).num_milliseconds() as f64 / 1000.,
+            diff.participants.len()
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Get a DM when new USACO records are found for a name
+///
+/// The bot checks for new records of the names you're watching whenever its \
+/// data is updated. Run without a name to list the names you're watching.
+#[poise::command(prefix_command, slash_command, ephemeral)]
+async fn watch(
+    ctx: Context<'_>,
+    #[rest]
+    #[description = "Full name to watch (case-insensitive)"]
+    #[autocomplete = "autocomplete_name"]
+    name: Option<String>,
+) -> anyhow::Result<()> {
+    let user = ctx.author().id;
+    let mut watch_list = ctx.data().watch_list.lock().await;
+
+    let reply = match name.map(|n| n.replace('`', "")) {
+        None => {
+            let names = watch_list.names(user);
+            if names.is_empty() {
+                "You aren't watching any names. Use /watch <name> to start.".to_string()
+            } else {
+                format!("You're watching: {}", names.join(", "))
+            }
+        }
+        Some(name) if name.trim().is_empty() => "Please provide a name to watch.".to_string(),
+        Some(name) => {
+            if watch_list.watch(user, &name) {
+                format!("You'll be messaged when new records are found for {name}.")
+            } else if watch_list.names(user).len() >= MAX_WATCHED_NAMES {
+                format!(
+                    "You can watch at most {MAX_WATCHED_NAMES} names. Use /unwatch to remove some."
+                )
+            } else {
+                format!("You're already watching {name}.")
+            }
+        }
+    };
+    drop(watch_list);
+
+    reply_chunked(ctx, &reply).await?;
+
+    Ok(())
+}
+
+/// Stop getting DMs about new USACO records for a name
+#[poise::command(prefix_command, slash_command, ephemeral)]
+async fn unwatch(
+    ctx: Context<'_>,
+    #[rest]
+    #[description = "Name to stop watching (case-insensitive)"]
+    name: String,
+) -> anyhow::Result<()> {
+    let name = name.replace('`', "");
+    let removed = ctx
+        .data()
+        .watch_list
+        .lock()
+        .await
+        .unwatch(ctx.author().id, &name);
+
+    let reply = if removed {
+        format!("You'll no longer be messaged about {name}.")
+    } else {
+        format!("You weren't watching {name}.")
     };
+    reply_chunked(ctx, &reply).await?;
+
+    Ok(())
+}
 
-    msg.edit(ctx, progress.lock().await.get_message(ctx, true))
+/// Export the standings of a contest as JSON
+///
+/// The contest is scraped fresh from usaco.org rather than reconstructed from \
+/// the database, since the database doesn't keep submission results or \
+/// problem names.
+#[poise::command(prefix_command, owners_only, hide_in_help)]
+async fn contestdata(
+    ctx: Context<'_>,
+    #[description = "Month of the contest (nov, dec, jan, feb, mar, or open)"]
+    #[autocomplete = "autocomplete_month"]
+    month: String,
+    #[description = "Year the contest was held"] year: u16,
+    #[description = "Division (bronze, silver, gold, or platinum)"]
+    #[autocomplete = "autocomplete_division"]
+    division: String,
+) -> anyhow::Result<()> {
+    let (Ok(month), Ok(division)) = (month.parse::<Month>(), division.parse::<Division>()) else {
+        reply_chunked(
+            ctx,
+            "Unrecognized month or division. Use /help contestdata for usage.",
+        )
         .await?;
+        return Ok(());
+    };
+    let time = MonthYear { year, month };
 
-    *ctx.data().db.lock().await = data.into();
+    let url = usaco_standings_scraper::contest_results_url(time, division);
+    let r = Client::new().get(url.clone()).send().await?;
+    if !r.status().is_success() {
+        reply_chunked(ctx, &format!("Got status {} from {url}.", r.status())).await?;
+        return Ok(());
+    }
+    let contest = usaco_standings_scraper::parse_contest_page(time, division, &r.text().await?);
 
-    ctx.say(format!(
-        "Successfully finished parsing in {:.2} seconds!",
-        (Utc::now() - now).num_milliseconds() as f64 / 1000.
-    ))
+    ctx.send(CreateReply::default().attachment(CreateAttachment::bytes(
+        serde_json::to_string_pretty(&contest)?,
+        format!("{month}{}_{division}.json", year % 100),
+    )))
     .await?;
 
     Ok(())
 }
 
+/// The parts of [`AppData`] that are saved to the [`FileStore`].
+#[derive(Copy, Clone)]
+struct SavedData {
+    db: &'static Mutex<UsacoDb>,
+    stats: &'static LiveStats,
+    watch_list: &'static Mutex<WatchList>,
+}
+
+/// Saves `data` to `filestore`, logging any failures. Returns whether
+/// everything was saved.
+async fn save_data(filestore: &Mutex<FileStore>, data: SavedData) -> bool {
+    // always lock the filestore before the data, so that saves can't deadlock
+    // each other
+    let mut filestore = filestore.lock().await;
+    let mut saved = true;
+
+    // a bit unfortunate that the guards for `data` are held while waiting
+    // for the filesystem, but it probably doesn't really matter
+    if let Err(e) = filestore.save_db(&*data.db.lock().await).await {
+        warn!("failed to save db to database: {e:?}");
+        saved = false;
+    }
+    if let Err(e) = filestore.save_stats(&data.stats.snapshot()).await {
+        warn!("failed to save stats to database: {e:?}");
+        saved = false;
+    }
+    if let Err(e) = filestore
+        .save_watch_list(&*data.watch_list.lock().await)
+        .await
+    {
+        warn!("failed to save watch list to database: {e:?}");
+        saved = false;
+    }
+
+    saved
+}
+
+/// Waits for a request to shut down: ctrl-c, or on unix, SIGTERM as well
+/// (which is what deploys send).
+async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            res = tokio::signal::ctrl_c() => res?,
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
 
     let store_path = env::var("FILE_STORE_PATH").context("looking for filestore path")?;
-    let mut filestore = FileStore::new_path(store_path.parse()?);
-    let store_data = filestore.load().await;
+    // opt in, since older versions of the bot can't load a compressed db
+    let compress_db = env::var("COMPRESS_DB").is_ok_and(|v| v == "1" || v == "true");
+    let filestore = Arc::new(Mutex::new(
+        FileStore::new_path(store_path.parse()?).with_compression(compress_db),
+    ));
+    let mut store_data = filestore.lock().await.load().await;
+    // set once the bot is set up, for saving one last time on shutdown
+    let saved_data = Arc::new(OnceLock::new());
+    store_data.db.apply_corrections(&store_data.corrections);
+    store_data.db.apply_aliases(&store_data.aliases);
 
     let options = poise::FrameworkOptions {
-        commands: vec![help(), invite(), ping(), search(), botinfo(), update()],
+        commands: vec![
+            help(),
+            invite(),
+            ping(),
+            search(),
+            card(),
+            compare(),
+            #[cfg(feature = "image")]
+            cardimg(),
+            contest(),
+            histogram(),
+            leaderboard(),
+            country(),
+            class(),
+            camp(),
+            school(),
+            team(),
+            botinfo(),
+            watch(),
+            unwatch(),
+            update(),
+            trending(),
+            contestdata(),
+        ],
         prefix_options: poise::PrefixFrameworkOptions {
             prefix: Some("s;".into()),
             edit_tracker: Some(Arc::new(poise::EditTracker::for_timespan(
@@ -534,6 +2036,8 @@ async fn main() -> anyhow::Result<()> {
         ..Default::default()
     };
 
+    let autosave_filestore = filestore.clone();
+    let setup_saved_data = saved_data.clone();
     let framework = poise::Framework::builder()
         .setup(move |ctx, ready, framework| {
             Box::pin(async move {
@@ -545,28 +2049,43 @@ async fn main() -> anyhow::Result<()> {
 
                 let data = AppData {
                     db: Box::leak(Box::new(Mutex::new(store_data.db))),
-                    stats: Box::leak(Box::new(Mutex::new(store_data.stats))),
+                    stats: Box::leak(Box::new(LiveStats::from(store_data.stats))),
+                    watch_list: Box::leak(Box::new(Mutex::new(store_data.watch_list))),
+                    corrections: store_data.corrections,
+                    aliases: store_data.aliases,
                     start: Instant::now(),
                     application_info: ctx.http.get_current_application_info().await?,
+                    search_limiter: RateLimiter::new(SEARCHES_PER_MINUTE, Duration::from_secs(60)),
                 };
-                let db = data.db;
-                let stats = data.stats;
+                let to_save = SavedData {
+                    db: data.db,
+                    stats: data.stats,
+                    watch_list: data.watch_list,
+                };
+                let _ = setup_saved_data.set(to_save);
 
-                // save data every 5 minutes. for now, it's ok to lose the last 5 minutes of
-                // data in the case of a shutdown.
+                // save data every 5 minutes, as well as on shutdown. it's ok to lose the
+                // last 5 minutes of data if the bot crashes.
                 tokio::spawn(async move {
                     let mut interval = tokio::time::interval(Duration::from_secs(5 * 60));
 
                     loop {
                         interval.tick().await;
+                        save_data(&autosave_filestore, to_save).await;
+                    }
+                });
 
-                        // a bit unfortunate that the guards for `data` are held while waiting
-                        // for the filesystem, but it probably doesn't really matter
-                        if let Err(e) = filestore.save_db(&*db.lock().await).await {
-                            warn!("failed to save db to database: {e:?}");
-                        }
-                        if let Err(e) = filestore.save_stats(&*stats.lock().await).await {
-                            warn!("failed to save stats to database: {e:?}");
+                let (db, aliases) = (data.db, data.aliases.clone());
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(INTL_HISTORY_REFRESH_INTERVAL);
+                    // the first tick is immediate, and the saved results were just loaded
+                    interval.tick().await;
+
+                    loop {
+                        interval.tick().await;
+                        match refresh_intl_history(db, &aliases).await {
+                            Ok(()) => info!("refreshed IOI and EGOI results"),
+                            Err(e) => error!("failed to refresh IOI and EGOI results: {e:?}"),
                         }
                     }
                 });
@@ -584,7 +2103,118 @@ async fn main() -> anyhow::Result<()> {
         .framework(framework)
         .await?;
 
-    client.start().await?;
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        match shutdown_signal().await {
+            Ok(()) => {
+                info!("shutting down");
+                shard_manager.shutdown_all().await;
+            }
+            Err(e) => error!("failed to listen for shutdown signals: {e:?}"),
+        }
+    });
+
+    let res = client.start().await;
+
+    // the client has stopped, so nothing else will change the data
+    if let Some(&data) = saved_data.get() {
+        if save_data(&filestore, data).await {
+            info!("saved data before shutting down");
+        } else {
+            error!("failed to save data before shutting down");
+        }
+    }
+
+    res?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_division_filter() {
+        let parse = |s: &str| s.parse::<DivisionFilter>().ok().map(|d| d.0);
+        assert_eq!(parse("+gold"), Some(Division::Gold));
+        assert_eq!(parse("plat"), Some(Division::Platinum));
+        assert_eq!(parse("+chen"), None);
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let ordinals = [1, 2, 3, 4, 11, 12, 13, 21, 102, 111, 1203].map(ordinal);
+        assert_eq!(
+            ordinals,
+            [
+                "1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "111th",
+                "1203rd"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_top_percent() {
+        assert_eq!(top_percent(1, 2000), 1);
+        assert_eq!(top_percent(25, 100), 25);
+        assert_eq!(top_percent(26, 100), 26);
+        assert_eq!(top_percent(199, 200), 100);
+        assert_eq!(top_percent(3, 10), 30);
+    }
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("", 10), Vec::<String>::new());
+        assert_eq!(split_message("short", 10), ["short"]);
+        assert_eq!(split_message("aaa\nbbb\nccc", 7), ["aaa\nbbb", "ccc"]);
+        assert_eq!(
+            split_message("aaa\nbbbbbbbbbb\nc", 4),
+            ["aaa", "bbbb", "bbbb", "bb\nc"]
+        );
+        // never split in the middle of a character
+        assert_eq!(split_message("ééé", 3), ["é", "é", "é"]);
+        // even if that goes over the limit
+        assert_eq!(split_message("éé", 1), ["é", "é"]);
+        assert_eq!(split_message("ab\nc", 0), ["a", "b", "c"]);
+        assert!(split_message(&"line\n".repeat(1000), 2000)
+            .iter()
+            .all(|c| c.len() <= 2000));
+    }
+
+    #[test]
+    fn test_matching_choices() {
+        assert_eq!(
+            matching_choices(&Month::ALL, ""),
+            Month::ALL.map(|m| m.to_string())
+        );
+        assert_eq!(matching_choices(&Month::ALL, " De"), ["dec"]);
+        assert_eq!(matching_choices(&Month::ALL, "december"), ["dec"]);
+        assert_eq!(matching_choices(&Division::ALL, "PLAT"), ["platinum"]);
+        assert!(matching_choices(&Division::ALL, "diamond").is_empty());
+    }
+
+    #[test]
+    fn test_split_pages() {
+        assert_eq!(split_pages("", 3), Vec::<String>::new());
+        assert_eq!(split_pages("a\nb\n\nc", 4), ["a\nb\n\nc"]);
+        // records are kept together when they fit on a page
+        assert_eq!(split_pages("a\nb\n\nc\nd", 3), ["a\nb", "c\nd"]);
+        assert_eq!(
+            split_pages("a\n\nb\nc\nd\ne\nf\n\ng", 2),
+            ["a", "b\nc", "d\ne", "f", "g"]
+        );
+
+        // only line counts matter, as format_name_query_result relies on
+        let shape = |text: &str| {
+            split_pages(text, 3)
+                .iter()
+                .map(|p| p.lines().count())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            shape("Jane Doe\nx\n\nJane Doe\ny"),
+            shape("[name hidden]\nx\n\n[name hidden]\ny")
+        );
+    }
+}
@@ -1,40 +1,135 @@
 //! Scrapes all past USACO results and outputs the result to stdout as json.
+//!
+//! Pass `--format yaml` to output yaml instead, or `--format csv` to output
+//! one row per contest record, which requires the `csv` feature.
+//!
+//! Pass `--validate` to check the scraped data with
+//! [`usaco_standings_scraper::validate`] instead of outputting it, printing
+//! any issues to stderr. This exits with code 0 if there are no critical
+//! issues (warnings are fine), 1 if the scrape itself failed, and 2 if there
+//! are critical issues, so it can be used in CI to catch USACO changing its
+//! pages.
+//!
+//! Pass `--split-by-season --out-dir <dir>` to write one json file per season
+//! to `<dir>` instead of outputting to stdout, which requires the `split`
+//! feature. See [`usaco_standings_scraper::write_split_by_season`] for the
+//! layout.
 
+use anyhow::bail;
 use chrono::{Datelike, Utc};
-use reqwest::{Client, StatusCode, Url};
-use std::{future::Future, pin::Pin};
+use reqwest::Client;
+use std::path::{Path, PathBuf};
+use usaco_standings_scraper::{Severity, UsacoData};
 
-/// A simple implementation of [`usaco_standings_scraper::HttpClient`] by
-/// directly wrapping a [`Client`].
-struct HttpClient {
-    client: Client,
+/// The format to output the scraped data in.
+#[derive(Debug, Copy, Clone)]
+enum Format {
+    Json,
+    Yaml,
+    #[cfg(feature = "csv")]
+    Csv,
 }
 
-impl usaco_standings_scraper::HttpClient for HttpClient {
-    type Error = reqwest::Error;
-    type Future = Pin<Box<dyn Future<Output = Result<(StatusCode, String), Self::Error>> + Send>>;
+/// The command line arguments.
+#[derive(Debug, Clone)]
+struct Args {
+    format: Format,
+    validate: bool,
+    /// The directory to write per-season files to, if splitting by season.
+    split_dir: Option<PathBuf>,
+}
 
-    fn get(&mut self, url: Url) -> Self::Future {
-        let client = self.client.clone();
+impl Args {
+    /// Reads the command line arguments, defaulting to json output to stdout
+    /// without validation.
+    fn from_args() -> anyhow::Result<Self> {
+        let mut args = std::env::args().skip(1);
+        let mut format = Format::Json;
+        let mut validate = false;
+        let mut split_by_season = false;
+        let mut out_dir = None;
 
-        Box::pin(async move {
-            let r = client.get(url).send().await?;
+        while let Some(arg) = args.next() {
+            if arg == "--validate" {
+                validate = true;
+                continue;
+            }
+            if arg == "--split-by-season" {
+                if cfg!(not(feature = "split")) {
+                    bail!("splitting by season requires the `split` feature");
+                }
+                split_by_season = true;
+                continue;
+            }
+            if arg == "--out-dir" {
+                let Some(dir) = args.next() else {
+                    bail!("missing value for `--out-dir`");
+                };
+                out_dir = Some(dir.into());
+                continue;
+            }
+            if arg != "--format" {
+                bail!("unexpected argument `{arg}`");
+            }
 
-            let status = r.status();
-            Ok((status, r.text().await?))
+            format = match args.next().as_deref() {
+                Some("json") => Format::Json,
+                Some("yaml") => Format::Yaml,
+                #[cfg(feature = "csv")]
+                Some("csv") => Format::Csv,
+                #[cfg(not(feature = "csv"))]
+                Some("csv") => bail!("csv output requires the `csv` feature"),
+                Some(other) => bail!("unknown format `{other}`"),
+                None => bail!("missing value for `--format`"),
+            };
+        }
+
+        let split_dir = match (split_by_season, out_dir) {
+            (true, Some(dir)) => Some(dir),
+            (true, None) => bail!("`--split-by-season` requires `--out-dir`"),
+            (false, Some(_)) => bail!("`--out-dir` is only used with `--split-by-season`"),
+            (false, None) => None,
+        };
+
+        Ok(Self {
+            format,
+            validate,
+            split_dir,
         })
     }
 }
 
+#[cfg(feature = "split")]
+fn write_split(data: &UsacoData, dir: &Path) -> anyhow::Result<()> {
+    usaco_standings_scraper::write_split_by_season(data, dir)
+}
+
+#[cfg(not(feature = "split"))]
+fn write_split(_: &UsacoData, _: &Path) -> anyhow::Result<()> {
+    // already rejected by `Args::from_args`
+    bail!("splitting by season requires the `split` feature")
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
         .with_writer(std::io::stderr)
         .init();
 
-    let client = HttpClient {
-        client: Client::new(),
-    };
+    let args = Args::from_args()?;
+
+    // a simple client which directly wraps a reqwest `Client`
+    let client = Client::new();
+    let client = usaco_standings_scraper::client_fn(move |url| {
+        let client = client.clone();
+
+        async move {
+            let r = client.get(url).send().await?;
+
+            let status = r.status();
+            Ok::<_, reqwest::Error>((status, r.text().await?))
+        }
+    });
 
     let now = Utc::now();
     let max_year = now.year() + if now.month() >= 10 { 1 } else { 0 };
@@ -46,7 +141,29 @@ async fn main() -> anyhow::Result<()> {
         client,
     )
     .await?;
-    serde_json::to_writer(std::io::stdout(), &data)?;
+
+    if args.validate {
+        let issues = usaco_standings_scraper::validate(&data);
+        for issue in &issues {
+            eprintln!("{issue}");
+        }
+
+        if issues.iter().any(|i| i.severity == Severity::Critical) {
+            std::process::exit(2);
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = &args.split_dir {
+        return write_split(&data, dir);
+    }
+
+    match args.format {
+        Format::Json => serde_json::to_writer(std::io::stdout(), &data)?,
+        Format::Yaml => serde_yaml::to_writer(std::io::stdout(), &data)?,
+        #[cfg(feature = "csv")]
+        Format::Csv => usaco_standings_scraper::write_contests_csv(&data, std::io::stdout())?,
+    }
 
     Ok(())
 }
@@ -0,0 +1,63 @@
+//! Saving and loading the pages [`parse_all_with_options`] has already parsed,
+//! so that a failed scrape can be resumed. See
+//! [`ParseOptions::checkpoint_dir`].
+//!
+//! [`parse_all_with_options`]: crate::parse_all_with_options
+//! [`ParseOptions::checkpoint_dir`]: crate::ParseOptions::checkpoint_dir
+
+use crate::{Division, MonthYear};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
+use tracing::warn;
+
+/// The checkpoint file name of the `division` contest held at `time`, named
+/// like its results page. For example, `open24_platinum.json`.
+pub(crate) fn contest_file_name(time: MonthYear, division: Division) -> String {
+    format!(
+        "{}{}_{}.json",
+        time.month.url_name(),
+        time.year % 100,
+        division.url_name()
+    )
+}
+
+/// The checkpoint file name of the camp held in `camp_year`, named like its
+/// finalists page. For example, `finalists24.json`.
+pub(crate) fn camp_file_name(camp_year: u16) -> String {
+    format!("finalists{}.json", camp_year % 100)
+}
+
+/// The checkpointed result at `path`, which is `Some(None)` for pages that
+/// didn't exist. `None` if there's no usable checkpoint, in which case the
+/// page should be requested again.
+pub(crate) async fn load<T: DeserializeOwned>(path: &Path) -> Option<Option<T>> {
+    let data = tokio::fs::read_to_string(path).await.ok()?;
+
+    serde_json::from_str(&data)
+        .inspect_err(|e| warn!("ignoring malformed checkpoint {path:?}: {e:?}"))
+        .ok()
+}
+
+/// Creates the checkpoint directory `dir` if it doesn't exist yet, so that
+/// pages can be saved to it. Failing to do so isn't fatal, but nothing will be
+/// checkpointed.
+pub(crate) async fn create_dir(dir: &Path) {
+    if let Err(e) = tokio::fs::create_dir_all(dir).await {
+        warn!("failed to create checkpoint directory {dir:?}: {e:?}");
+    }
+}
+
+/// Checkpoints `value` to `path`. Failing to do so isn't fatal, since the page
+/// just gets requested again when resuming.
+pub(crate) async fn save<T: Serialize>(path: &Path, value: &Option<T>) {
+    let res = async {
+        tokio::fs::write(path, serde_json::to_string(value)?).await?;
+
+        anyhow::Ok(())
+    }
+    .await;
+
+    if let Err(e) = res {
+        warn!("failed to write checkpoint {path:?}: {e:?}");
+    }
+}
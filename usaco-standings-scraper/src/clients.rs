@@ -0,0 +1,425 @@
+//! Wrappers around [`HttpClient`]s that add behavior such as rate limiting,
+//! and [`client_fn`] for implementing [`HttpClient`] with a closure. Wrappers
+//! implement [`HttpClient`] themselves, so they can be freely composed with
+//! each other.
+
+use crate::HttpClient;
+use http::StatusCode;
+#[cfg(feature = "cache")]
+use std::path::{Path, PathBuf};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+#[cfg(feature = "cache")]
+use tracing::warn;
+use url::Url;
+
+/// The boxed future returned by the wrappers in this module.
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// An [`HttpClient`] which makes its requests by calling a closure. See
+/// [`client_fn`].
+#[derive(Debug, Clone)]
+pub struct FnClient<F> {
+    f: F,
+}
+
+/// Implements [`HttpClient`] with `f`, which is called with the URL of every
+/// request and returns a future resolving to its status and body. The future
+/// has to own everything it uses, so clients such as `reqwest::Client` should
+/// be cloned into it:
+///
+/// ```ignore
+/// let client = reqwest::Client::new();
+/// let client = usaco_standings_scraper::client_fn(move |url| {
+///     let client = client.clone();
+///
+///     async move {
+///         let r = client.get(url).send().await?;
+///         let status = r.status();
+///         Ok::<_, reqwest::Error>((status, r.text().await?))
+///     }
+/// });
+/// ```
+pub fn client_fn<F, Fut, E>(f: F) -> FnClient<F>
+where
+    F: FnMut(Url) -> Fut,
+    Fut: Future<Output = Result<(StatusCode, String), E>> + Send + 'static,
+{
+    FnClient { f }
+}
+
+impl<F, Fut, E> HttpClient for FnClient<F>
+where
+    F: FnMut(Url) -> Fut,
+    Fut: Future<Output = Result<(StatusCode, String), E>> + Send + 'static,
+{
+    type Error = E;
+    type Future = Fut;
+
+    fn get(&mut self, url: Url) -> Self::Future {
+        (self.f)(url)
+    }
+}
+
+/// Limits the rate of requests made through the wrapped client using a token
+/// bucket. The bucket holds up to `burst` tokens and refills at
+/// `requests_per_second`; every request takes a token, waiting for one to
+/// become available if the bucket is empty.
+///
+/// A request's slot is reserved when [`HttpClient::get`] is called, but the
+/// wrapped client's [`HttpClient::get`] is only called once that slot arrives,
+/// so this works even with clients that start requesting right away. Clones
+/// share the wrapped client.
+#[derive(Debug, Clone)]
+pub struct RateLimitedClient<C> {
+    inner: Arc<Mutex<C>>,
+    requests_per_second: f64,
+    burst: f64,
+    /// Tokens in the bucket as of `last`. Goes negative when requests queue up
+    /// waiting for tokens.
+    tokens: f64,
+    last: Instant,
+}
+
+impl<C> RateLimitedClient<C> {
+    /// Wraps `inner` so that at most `requests_per_second` requests are made
+    /// per second, with no bursting.
+    ///
+    /// # Panics
+    /// Panics if `requests_per_second` is not positive.
+    pub fn new(inner: C, requests_per_second: f64) -> Self {
+        assert!(
+            requests_per_second > 0.,
+            "requests_per_second should be positive"
+        );
+
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            requests_per_second,
+            burst: 1.,
+            tokens: 1.,
+            last: Instant::now(),
+        }
+    }
+
+    /// Allows up to `burst` requests to be made at once after the client has
+    /// been idle for a while. Values below 1 are treated as 1.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1) as f64;
+        self.tokens = self.burst;
+        self
+    }
+
+    /// Consumes the wrapper and returns the wrapped client, or `None` if it's
+    /// still in use by clones of this wrapper or by pending requests.
+    pub fn into_inner(self) -> Option<C> {
+        Arc::into_inner(self.inner).map(|inner| {
+            inner
+                .into_inner()
+                .expect("wrapped client lock shouldn't be poisoned")
+        })
+    }
+}
+
+impl<C: HttpClient + Send + 'static> HttpClient for RateLimitedClient<C>
+where
+    C::Error: 'static,
+{
+    type Error = C::Error;
+    type Future = BoxFuture<Result<(StatusCode, String), Self::Error>>;
+
+    fn get(&mut self, url: Url) -> Self::Future {
+        let now = Instant::now();
+        let refilled = now.duration_since(self.last).as_secs_f64() * self.requests_per_second;
+        self.last = now;
+        self.tokens = (self.tokens + refilled).min(self.burst) - 1.;
+
+        // a negative balance is the number of requests in line before us
+        let start = if self.tokens < 0. {
+            now + Duration::from_secs_f64(-self.tokens / self.requests_per_second)
+        } else {
+            now
+        };
+
+        let inner = self.inner.clone();
+
+        Box::pin(async move {
+            tokio::time::sleep_until(start).await;
+
+            // the lock is only held while starting the request
+            let fut = inner
+                .lock()
+                .expect("wrapped client lock shouldn't be poisoned")
+                .get(url);
+            fut.await
+        })
+    }
+}
+
+/// Caches the wrapped client's responses on disk, so repeated runs (such as
+/// while working on the parsers) request each page only once. Each response is
+/// stored in the cache directory under a hash of its URL, and served from
+/// there on later requests for the same URL. Only successful responses are
+/// cached, since errors such as a 404 for a contest that hasn't been posted
+/// yet can go away.
+///
+/// The wrapped client's [`HttpClient::get`] is only called once the cache has
+/// been checked, when the returned future is first polled, so cache hits never
+/// reach it. In particular, they don't count towards a wrapped
+/// [`RateLimitedClient`]'s rate limit. Clones share the wrapped client.
+#[cfg(feature = "cache")]
+#[derive(Debug, Clone)]
+pub struct CachingClient<C> {
+    inner: Arc<Mutex<C>>,
+    dir: PathBuf,
+    refresh: bool,
+}
+
+#[cfg(feature = "cache")]
+impl<C> CachingClient<C> {
+    /// Wraps `inner` so that its responses are cached in `dir`, which is
+    /// created if it doesn't exist.
+    pub fn new(inner: C, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            dir: dir.into(),
+            refresh: false,
+        }
+    }
+
+    /// If `refresh` is set, every page is requested again through the wrapped
+    /// client, with the fresh responses replacing the cached ones.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Consumes the wrapper and returns the wrapped client, or `None` if it's
+    /// still in use by clones of this wrapper or by pending requests.
+    pub fn into_inner(self) -> Option<C> {
+        Arc::into_inner(self.inner).map(|inner| {
+            inner
+                .into_inner()
+                .expect("wrapped client lock shouldn't be poisoned")
+        })
+    }
+
+    /// The file `url`'s response is cached in. The hash is FNV-1a rather than
+    /// std's hasher, since that isn't guaranteed to stay the same across Rust
+    /// releases.
+    fn cache_path(&self, url: &Url) -> PathBuf {
+        let hash = url.as_str().bytes().fold(0xcbf29ce484222325u64, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+
+        self.dir.join(format!("{hash:016x}"))
+    }
+}
+
+/// The cached response at `path`, stored as the status code on the first line
+/// followed by the body. `None` if there's no usable cached response.
+#[cfg(feature = "cache")]
+async fn load_cached(path: &Path) -> Option<(StatusCode, String)> {
+    let data = tokio::fs::read_to_string(path).await.ok()?;
+
+    let res = data.split_once('\n').and_then(|(status, body)| {
+        let status = StatusCode::from_u16(status.parse().ok()?).ok()?;
+        Some((status, body.to_string()))
+    });
+    if res.is_none() {
+        warn!("ignoring malformed cached response {path:?}");
+    }
+
+    res
+}
+
+#[cfg(feature = "cache")]
+impl<C: HttpClient + Send + 'static> HttpClient for CachingClient<C>
+where
+    C::Error: Send + 'static,
+{
+    type Error = C::Error;
+    type Future = BoxFuture<Result<(StatusCode, String), Self::Error>>;
+
+    fn get(&mut self, url: Url) -> Self::Future {
+        let path = self.cache_path(&url);
+        let inner = self.inner.clone();
+        let dir = self.dir.clone();
+        let refresh = self.refresh;
+
+        Box::pin(async move {
+            if !refresh {
+                if let Some(res) = load_cached(&path).await {
+                    return Ok(res);
+                }
+            }
+
+            // the lock is only held while starting the request
+            let fut = inner
+                .lock()
+                .expect("wrapped client lock shouldn't be poisoned")
+                .get(url);
+            let (status, body) = fut.await?;
+
+            if status.is_success() {
+                // failing to cache isn't fatal, the page is just requested
+                // again next time
+                let res = async {
+                    tokio::fs::create_dir_all(&dir).await?;
+                    tokio::fs::write(&path, format!("{}\n{body}", status.as_u16())).await
+                }
+                .await;
+                if let Err(e) = res {
+                    warn!("failed to cache response in {path:?}: {e:?}");
+                }
+            }
+
+            Ok((status, body))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        convert::Infallible,
+        sync::{Arc, Mutex},
+    };
+
+    /// Records the time at which each request is made, which is as soon as
+    /// [`HttpClient::get`] is called rather than when the future is polled.
+    #[derive(Clone, Default)]
+    struct RecordingClient {
+        times: Arc<Mutex<Vec<Instant>>>,
+    }
+
+    impl HttpClient for RecordingClient {
+        type Error = Infallible;
+        type Future = BoxFuture<Result<(StatusCode, String), Infallible>>;
+
+        fn get(&mut self, _url: Url) -> Self::Future {
+            self.times.lock().unwrap().push(Instant::now());
+
+            Box::pin(async { Ok((StatusCode::OK, String::new())) })
+        }
+    }
+
+    fn url() -> Url {
+        "https://usaco.org".parse().unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_client() {
+        let inner = RecordingClient::default();
+        let mut client = RateLimitedClient::new(inner.clone(), 5.).with_burst(2);
+
+        let start = Instant::now();
+        let futs = (0..6).map(|_| client.get(url())).collect::<Vec<_>>();
+        for fut in futs {
+            fut.await.unwrap();
+        }
+
+        let offsets = inner
+            .times
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|t| (*t - start).as_millis())
+            .collect::<Vec<_>>();
+        // two requests burst immediately, then one every 200ms
+        assert_eq!(offsets, [0, 0, 200, 400, 600, 800]);
+
+        // after idling, the bucket refills up to the burst size only
+        tokio::time::advance(Duration::from_secs(10)).await;
+        let start = Instant::now();
+        inner.times.lock().unwrap().clear();
+        let futs = (0..3).map(|_| client.get(url())).collect::<Vec<_>>();
+        for fut in futs {
+            fut.await.unwrap();
+        }
+        let offsets = inner
+            .times
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|t| (*t - start).as_millis())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [0, 0, 200]);
+    }
+
+    #[tokio::test]
+    async fn test_client_fn() {
+        let mut client =
+            client_fn(
+                |url: Url| async move { Ok::<_, Infallible>((StatusCode::OK, url.to_string())) },
+            );
+
+        assert_eq!(
+            client.get(url()).await.unwrap(),
+            (StatusCode::OK, "https://usaco.org/".to_string())
+        );
+    }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn test_caching_client() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+
+        let requested = Arc::new(Mutex::new(vec![]));
+        let inner = {
+            let requested = requested.clone();
+            client_fn(move |url: Url| {
+                requested.lock().unwrap().push(url.to_string());
+                let status = if url.path() == "/missing" {
+                    StatusCode::NOT_FOUND
+                } else if url.path() == "/down" {
+                    StatusCode::SERVICE_UNAVAILABLE
+                } else {
+                    StatusCode::OK
+                };
+
+                async move { Ok::<_, Infallible>((status, format!("page\n{url}"))) }
+            })
+        };
+        let mut client = CachingClient::new(inner, dir);
+
+        let page = |path| url().join(path).unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                client.get(page("/a")).await.unwrap(),
+                (StatusCode::OK, "page\nhttps://usaco.org/a".to_string())
+            );
+            assert_eq!(
+                client.get(page("/missing")).await.unwrap().0,
+                StatusCode::NOT_FOUND
+            );
+            assert_eq!(
+                client.get(page("/down")).await.unwrap().0,
+                StatusCode::SERVICE_UNAVAILABLE
+            );
+        }
+        // errors are requested again, successful responses are cached
+        assert_eq!(
+            *requested.lock().unwrap(),
+            [
+                "https://usaco.org/a",
+                "https://usaco.org/missing",
+                "https://usaco.org/down",
+                "https://usaco.org/missing",
+                "https://usaco.org/down",
+            ]
+        );
+
+        requested.lock().unwrap().clear();
+        let mut client = client.with_refresh(true);
+        client.get(page("/a")).await.unwrap();
+        assert_eq!(*requested.lock().unwrap(), ["https://usaco.org/a"]);
+    }
+}
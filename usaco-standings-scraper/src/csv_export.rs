@@ -0,0 +1,184 @@
+//! Exporting [`UsacoData`] as CSV, for loading into spreadsheets. Each function
+//! writes a header row followed by one row per record.
+
+use crate::{Graduation, IntlMedal, TestcaseResult, UsacoData};
+use std::io::Write;
+
+/// The graduation column, which is the year or "observer".
+fn graduation_field(graduation: Graduation) -> String {
+    match graduation {
+        Graduation::HighSchool { year } => year.to_string(),
+        Graduation::Observer => "observer".to_string(),
+    }
+}
+
+/// Testcase results in the same symbols as the results pages, with problems
+/// separated by `/` and `-` for unsubmitted problems, such as `**x/ttt/-`.
+fn results_field(results: &[Option<Vec<TestcaseResult>>]) -> String {
+    results
+        .iter()
+        .map(|r| match r {
+            Some(r) => r.iter().map(|r| r.symbol()).collect(),
+            None => "-".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Writes one row per participant of each contest in `data`, with columns
+/// `name`, `country`, `graduation` (a year or "observer"), `year`, `month`,
+/// `division`, `score`, and `results`. Months and divisions are as in the
+/// result URLs, such as "open" and "platinum". `results` is the testcase
+/// results of each problem, such as `**x/ttt/-` (see
+/// [`TestcaseResult`] for the symbols, `-` is an unsubmitted problem).
+pub fn write_contests_csv<W: Write>(data: &UsacoData, w: W) -> csv::Result<()> {
+    let mut w = csv::Writer::from_writer(w);
+    w.write_record([
+        "name",
+        "country",
+        "graduation",
+        "year",
+        "month",
+        "division",
+        "score",
+        "results",
+    ])?;
+
+    for c in &data.contests {
+        for p in &c.participants {
+            w.write_record([
+                p.name.clone(),
+                p.country.clone(),
+                graduation_field(p.graduation),
+                c.time.year.to_string(),
+                c.time.month.to_string(),
+                c.division.to_string(),
+                p.score.to_string(),
+                results_field(&p.submission_results),
+            ])?;
+        }
+    }
+
+    w.flush()?;
+    Ok(())
+}
+
+/// Writes one row per camp finalist in `data`, with columns `name`,
+/// `graduation`, `camp_year`, `school`, `state`, and `egoi` (`true` for
+/// finalists invited for EGOI selection).
+pub fn write_camps_csv<W: Write>(data: &UsacoData, w: W) -> csv::Result<()> {
+    let mut w = csv::Writer::from_writer(w);
+    w.write_record(["name", "graduation", "camp_year", "school", "state", "egoi"])?;
+
+    for c in &data.camps {
+        for p in &c.participants {
+            w.write_record([
+                p.name.clone(),
+                p.graduation_year.to_string(),
+                c.year.to_string(),
+                p.school.clone(),
+                p.state.clone(),
+                p.is_egoi.to_string(),
+            ])?;
+        }
+    }
+
+    w.flush()?;
+    Ok(())
+}
+
+/// Writes one row per IOI and EGOI record in `data`, with columns
+/// `competition` ("IOI" or "EGOI"), `year`, `name`, and `result` ("gold",
+/// "silver", "bronze", "none", or "visa issue").
+pub fn write_intl_csv<W: Write>(data: &UsacoData, w: W) -> csv::Result<()> {
+    let mut w = csv::Writer::from_writer(w);
+    w.write_record(["competition", "year", "name", "result"])?;
+
+    for (competition, records) in [
+        ("IOI", &data.intl_history.ioi),
+        ("EGOI", &data.intl_history.egoi),
+    ] {
+        for r in records {
+            let result = match r.result {
+                IntlMedal::VisaIssue => "visa issue",
+                IntlMedal::NoMedal => "none",
+                IntlMedal::Bronze => "bronze",
+                IntlMedal::Silver => "silver",
+                IntlMedal::Gold => "gold",
+            };
+
+            w.write_record([competition, &r.year.to_string(), &r.name, result])?;
+        }
+    }
+
+    w.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util, Camp, CampParticipant, Contest, ContestParticipant, Division, IntlHistory,
+        IntlParticipant, Month,
+    };
+
+    #[test]
+    fn test_csv() {
+        use TestcaseResult::*;
+
+        let data = UsacoData {
+            contests: vec![Contest {
+                participants: vec![ContestParticipant {
+                    graduation: Graduation::Observer,
+                    submission_results: vec![
+                        Some(vec![Correct, Correct, WrongAnswer]),
+                        Some(vec![Timeout]),
+                        None,
+                    ],
+                    ..test_util::contest_participant("Doe, Jane", 333)
+                }],
+                ..test_util::contest(2024, Month::Open, Division::Platinum)
+            }],
+            camps: vec![Camp {
+                year: 2024,
+                participants: vec![CampParticipant {
+                    graduation_year: 2025,
+                    name: "Jane Doe".to_string(),
+                    school: "Example High School".to_string(),
+                    state: "CA".to_string(),
+                    is_egoi: true,
+                }],
+            }],
+            intl_history: IntlHistory {
+                ioi: vec![IntlParticipant {
+                    year: 2024,
+                    name: "Jane Doe".to_string(),
+                    result: IntlMedal::Gold,
+                }],
+                egoi: vec![],
+            },
+        };
+
+        let to_string = |f: fn(&UsacoData, &mut Vec<u8>) -> csv::Result<()>| {
+            let mut out = vec![];
+            f(&data, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            to_string(|d, w| write_contests_csv(d, w)),
+            "name,country,graduation,year,month,division,score,results\n\
+            \"Doe, Jane\",USA,observer,2024,open,platinum,333,**x/t/-\n"
+        );
+        assert_eq!(
+            to_string(|d, w| write_camps_csv(d, w)),
+            "name,graduation,camp_year,school,state,egoi\n\
+            Jane Doe,2025,2024,Example High School,CA,true\n"
+        );
+        assert_eq!(
+            to_string(|d, w| write_intl_csv(d, w)),
+            "competition,year,name,result\nIOI,2024,Jane Doe,gold\n"
+        );
+    }
+}
@@ -22,21 +22,61 @@ There have so far been two weird contests:
 - [Open 2017 Gold](https://usaco.org/current/data/open17_gold_results.html) had an incorrect problem. Scores were calculated with that incorrect problem thrown out, but students who met the qualifying threshold when their score was calculated with the broken problem still promoted.
 
 ## Robustness
-The scrapers are designed to be robust. When faced with unexpected / malformed input, they will do their best to parse what they can and log relevant warnings using `tracing`. The parsing functions should never panic. The parsers should all work correctly as of December 2024.
+The scrapers are designed to be robust. When faced with unexpected / malformed input, they will do their best to parse what they can and log relevant warnings using `tracing`. The `_with_diagnostics` variants of each parser (such as [`parse_contest_page_with_diagnostics`]) also return these warnings as [`ParseWarning`]s. The parsing functions should never panic. To catch the parsers silently producing garbage (such as after USACO changes its pages), [`validate`] checks scraped data for suspicious results, and `examples/scrape.rs --validate` runs it as a CI friendly check. The parsers should all work correctly as of December 2024.
 
 ## Examples
 
 See `examples/scrape.rs` for an example on how to use the scraper.
 
+## HTTP clients
+[`parse_all`] makes its requests through a user supplied [`HttpClient`]. The simplest way to implement one is with a closure, using [`client_fn`]. This crate also provides some wrappers around clients, which can be composed:
+- [`RateLimitedClient`] limits the number of requests made per second.
+- [`CachingClient`] caches responses on disk, which is handy when working on the parsers. Requires the `cache` feature.
+
+Pages can also be scraped from Wayback Machine snapshots instead of usaco.org, to recover results USACO has since removed or changed. See [`ParseOptions::wayback_snapshot`].
+
 ## Features
 - `serde`: Enables serde support for (de)serializing the structs in this crate. Enabled by default.
+- `csv`: Enables [`write_contests_csv`], [`write_camps_csv`], and [`write_intl_csv`] for exporting to spreadsheets, and lets `examples/scrape.rs` output contest records with `--format csv`.
+- `split`: Enables [`write_split_by_season`] for archiving scraped data as one json file per season, and lets `examples/scrape.rs` do so with `--split-by-season --out-dir <dir>`. Implies `serde`.
+- `cache`: Enables [`CachingClient`].
+- `checkpoint`: Enables [`ParseOptions::checkpoint_dir`] and [`resume_from`], for resuming scrapes that failed partway through. Implies `serde`.
 */
 
+#[cfg(feature = "checkpoint")]
+mod checkpoint;
+mod clients;
+#[cfg(feature = "csv")]
+mod csv_export;
+#[cfg(feature = "split")]
+mod split;
+#[cfg(test)]
+mod test_util;
+mod validate;
+
+#[cfg(feature = "cache")]
+pub use clients::CachingClient;
+pub use clients::{client_fn, FnClient, RateLimitedClient};
+#[cfg(feature = "csv")]
+pub use csv_export::{write_camps_csv, write_contests_csv, write_intl_csv};
+#[cfg(feature = "split")]
+pub use split::write_split_by_season;
+pub use validate::{validate, Severity, ValidationIssue};
+
 use anyhow::anyhow;
 use http::StatusCode;
 use scraper::{ElementRef, Html, Node, Selector};
-use std::{collections::HashSet, future::Future};
-use tokio::task::JoinSet;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    future::Future,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, LazyLock,
+    },
+};
+use tokio::{sync::Semaphore, task::JoinSet};
 use tracing::{debug, instrument, warn};
 use url::Url;
 
@@ -59,6 +99,16 @@ pub enum Month {
 }
 
 impl Month {
+    /// Every month, in the order they happen in a season.
+    pub const ALL: [Month; 6] = [
+        Month::November,
+        Month::December,
+        Month::January,
+        Month::February,
+        Month::March,
+        Month::Open,
+    ];
+
     /// The short lowercase version of the month name used in the USACO result
     /// URLs.
     fn url_name(self) -> &'static str {
@@ -71,6 +121,45 @@ impl Month {
             Month::December => "dec",
         }
     }
+
+    /// The full lowercase name of the month. For [`Month::Open`], this is
+    /// "open".
+    fn full_name(self) -> &'static str {
+        match self {
+            Month::January => "january",
+            Month::February => "february",
+            Month::March => "march",
+            Month::Open => "open",
+            Month::November => "november",
+            Month::December => "december",
+        }
+    }
+}
+
+/// Formats the month as in the USACO result URLs, such as "jan" or "open".
+impl std::fmt::Display for Month {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.url_name())
+    }
+}
+
+/// Parses either the short name used in the USACO result URLs (as formatted by
+/// the [`Display`](std::fmt::Display) impl) or the full month name,
+/// case-insensitively.
+impl FromStr for Month {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        Month::ALL
+            .into_iter()
+            .find(|m| m.url_name().eq_ignore_ascii_case(s) || m.full_name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseNameError {
+                kind: "month",
+                input: s.to_string(),
+            })
+    }
 }
 
 /// A month, year tuple specifying the time a contest was held.
@@ -81,6 +170,18 @@ pub struct MonthYear {
     pub month: Month,
 }
 
+impl MonthYear {
+    /// The season this contest was held in, named after the year it ends. For
+    /// example, this is 2024 for the December 2023 contest.
+    pub fn season(self) -> u16 {
+        if matches!(self.month, Month::November | Month::December) {
+            self.year + 1
+        } else {
+            self.year
+        }
+    }
+}
+
 /// The division of a contest. Order goes bronze < silver < gold < plat.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -92,6 +193,14 @@ pub enum Division {
 }
 
 impl Division {
+    /// Every division, from lowest to highest.
+    pub const ALL: [Division; 4] = [
+        Division::Bronze,
+        Division::Silver,
+        Division::Gold,
+        Division::Platinum,
+    ];
+
     /// The lowercase version of the division name used in the USACO result
     /// URLs.
     fn url_name(self) -> &'static str {
@@ -102,8 +211,61 @@ impl Division {
             Division::Platinum => "platinum",
         }
     }
+
+    /// The division immediately above this one, or `None` for platinum.
+    pub fn next(self) -> Option<Division> {
+        match self {
+            Division::Bronze => Some(Division::Silver),
+            Division::Silver => Some(Division::Gold),
+            Division::Gold => Some(Division::Platinum),
+            Division::Platinum => None,
+        }
+    }
+}
+
+/// Formats the division as in the USACO result URLs, such as "platinum".
+impl std::fmt::Display for Division {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.url_name())
+    }
+}
+
+/// Parses the name used in the USACO result URLs (as formatted by the
+/// [`Display`](std::fmt::Display) impl) case-insensitively. "plat" is accepted
+/// too.
+impl FromStr for Division {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        Division::ALL
+            .into_iter()
+            .find(|d| d.url_name().eq_ignore_ascii_case(s))
+            .or_else(|| s.eq_ignore_ascii_case("plat").then_some(Division::Platinum))
+            .ok_or_else(|| ParseNameError {
+                kind: "division",
+                input: s.to_string(),
+            })
+    }
+}
+
+/// The error returned when parsing an unrecognized [`Month`], [`Division`], or
+/// [`IntlCompetition`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseNameError {
+    kind: &'static str,
+    input: String,
+}
+
+impl std::fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unrecognized {} `{}`", self.kind, self.input)
+    }
 }
 
+impl std::error::Error for ParseNameError {}
+
 /// The graduation date of a student.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -112,6 +274,18 @@ pub enum Graduation {
     Observer,
 }
 
+impl Graduation {
+    /// The grade of a student during `season` (as in [`MonthYear::season`]),
+    /// or `None` for observers. Grades outside of 9 to 12 are returned as is,
+    /// since they're real data, even if they're odd.
+    pub fn grade_at(self, season: u16) -> Option<i32> {
+        match self {
+            Graduation::HighSchool { year } => Some(12 - (year as i32 - season as i32)),
+            Graduation::Observer => None,
+        }
+    }
+}
+
 /// The result of a specific testcase for a problem.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -124,10 +298,25 @@ pub enum TestcaseResult {
     Empty,
 }
 
+impl TestcaseResult {
+    /// The symbol results pages use for this result.
+    fn symbol(self) -> &'static str {
+        match self {
+            TestcaseResult::Correct => "*",
+            TestcaseResult::WrongAnswer => "x",
+            TestcaseResult::Timeout => "t",
+            TestcaseResult::CompilationError => "c",
+            TestcaseResult::RunTimeError => "!",
+            TestcaseResult::Empty => "e",
+        }
+    }
+}
+
 /// A contest participant that showed up on the leaderboard.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ContestParticipant {
+    /// Normalized with [`normalize_country`].
     pub country: String,
     pub graduation: Graduation,
     pub name: String,
@@ -139,7 +328,42 @@ pub struct ContestParticipant {
     /// - 2011 November Bronze had 4 problems
     /// - 2017 Open Gold had a problem thrown out, and for some contestants,
     ///   only their scores but not submission results were revealed
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub submission_results: Vec<Option<Vec<TestcaseResult>>>,
+    /// The time of their last submission for each of the problems, as written
+    /// on the results page. Only some result pages have a time column for each
+    /// problem, so this is empty if the page has none. Otherwise, this is
+    /// aligned with `submission_results`, with `None` for blank times.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub submission_times: Vec<Option<String>>,
+    /// The points earned on each of the problems, aligned with
+    /// `submission_results`. This is estimated from the fraction of testcases
+    /// that were correct, so it may be off by a point from rounding. `None`
+    /// where `submission_results` is `None`, which is also the case when only
+    /// the aggregate score was revealed (as in 2017 Open Gold).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub problem_scores: Vec<Option<u16>>,
+}
+
+/// A problem of a contest, as listed in the header of the results table.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContestProblem {
+    /// The problem's header text. On most pages, this is just the problem
+    /// number.
+    pub name: String,
+    /// The number of points the problem is worth, if the header lists it in
+    /// parentheses after the name, such as "Problem 1 (333)".
+    pub max_score: Option<u16>,
 }
 
 /// All the data on a contest page.
@@ -148,7 +372,68 @@ pub struct ContestParticipant {
 pub struct Contest {
     pub time: MonthYear,
     pub division: Division,
+    /// The problems of the contest, aligned with each participant's
+    /// `submission_results`. This includes problems that were thrown out, such
+    /// as in 2017 Open Gold. Empty for data scraped before this was parsed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub problems: Vec<ContestProblem>,
     pub participants: Vec<ContestParticipant>,
+    /// Whether the division named in the page's heading disagreed with the
+    /// division the page was requested as, which suggests USACO served the
+    /// wrong page. `false` if the heading didn't name a division.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub division_mismatch: bool,
+}
+
+/// Whether the participants of a contest agree on how many problems it had.
+/// See [`Contest::problem_count_consistency`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProblemCountConsistency {
+    /// The most common length of the participants' `submission_results`, with
+    /// ties going to the larger length. `None` if there are no participants.
+    pub modal_count: Option<usize>,
+    /// The indices in [`Contest::participants`] of the participants whose
+    /// `submission_results` aren't of length `modal_count`, in increasing
+    /// order.
+    pub deviating: Vec<usize>,
+}
+
+impl ProblemCountConsistency {
+    /// Whether every participant has the same number of problems.
+    pub fn is_consistent(&self) -> bool {
+        self.deviating.is_empty()
+    }
+}
+
+impl Contest {
+    /// Checks that every participant has results for the same number of
+    /// problems, pinpointing the rows that don't. A row that disagrees with
+    /// the rest usually parsed wrong, though participants of 2017 Open Gold
+    /// can legitimately be missing results (see
+    /// [`ContestParticipant::submission_results`]).
+    pub fn problem_count_consistency(&self) -> ProblemCountConsistency {
+        let mut counts = HashMap::<usize, usize>::new();
+        for p in &self.participants {
+            *counts.entry(p.submission_results.len()).or_default() += 1;
+        }
+
+        let modal_count = counts
+            .into_iter()
+            .max_by_key(|&(len, count)| (count, len))
+            .map(|(len, _)| len);
+        let deviating = self
+            .participants
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| Some(p.submission_results.len()) != modal_count)
+            .map(|(i, _)| i)
+            .collect();
+
+        ProblemCountConsistency {
+            modal_count,
+            deviating,
+        }
+    }
 }
 
 /// A participant in a USACO camp.
@@ -212,38 +497,261 @@ pub struct UsacoData {
     pub intl_history: IntlHistory,
 }
 
+/// Something unexpected found while parsing a page, which was ignored. See
+/// [`parse_contest_page_with_diagnostics`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseWarning {
+    /// The HTML that couldn't be parsed. This is empty when the warning isn't
+    /// about any particular element.
+    pub snippet: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.snippet.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} in `{}`", self.message, self.snippet)
+        }
+    }
+}
+
+/// Logs a [`ParseWarning`] and adds it to `warnings`.
+fn report(
+    warnings: &mut Vec<ParseWarning>,
+    snippet: impl Into<String>,
+    message: impl Into<String>,
+) {
+    let warning = ParseWarning {
+        snippet: snippet.into(),
+        message: message.into(),
+    };
+    warn!("{warning}");
+    warnings.push(warning);
+}
+
 /// Normalize text nodes by dealing with nbsps and duplicate whitespace.
 fn normalize_text(s: &str) -> String {
     s.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+/// Full country names (lowercase) and their ISO 3166-1 alpha-3 codes, for
+/// [`normalize_country`]. USACO almost always lists the codes themselves, so
+/// this only needs the variants that have shown up or plausibly could.
+const COUNTRY_NAMES: &[(&str, &str)] = &[
+    ("united states", "USA"),
+    ("united states of america", "USA"),
+    ("us", "USA"),
+    ("china", "CHN"),
+    ("canada", "CAN"),
+    ("south korea", "KOR"),
+    ("korea", "KOR"),
+    ("taiwan", "TWN"),
+    ("japan", "JPN"),
+    ("india", "IND"),
+    ("singapore", "SGP"),
+    ("united kingdom", "GBR"),
+    ("uk", "GBR"),
+    ("germany", "DEU"),
+    ("russia", "RUS"),
+    ("vietnam", "VNM"),
+    ("hong kong", "HKG"),
+    ("australia", "AUS"),
+];
+
+/// A canonical form of a country as listed on a results page, so that the same
+/// country is always spelled the same way. Surrounding and duplicate whitespace
+/// is removed, known full names (such as "United States") are mapped to their
+/// ISO 3166-1 alpha-3 codes (such as "USA"), and anything else is uppercased,
+/// since USACO lists countries by their codes.
+pub fn normalize_country(raw: &str) -> Cow<'_, str> {
+    if !raw.is_empty() && raw.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Cow::Borrowed(raw);
+    }
+
+    let text = normalize_text(raw);
+    let lower = text.to_lowercase();
+    match COUNTRY_NAMES.iter().find(|&&(name, _)| name == lower) {
+        Some(&(_, code)) => Cow::Borrowed(code),
+        None => Cow::Owned(text.to_uppercase()),
+    }
+}
+
 /// The text content of `e`, normalized using [`normalize_text`].
 fn elem_text(e: ElementRef) -> String {
     normalize_text(&e.text().collect::<String>())
 }
 
+/// The text content of a testcase verdict cell. Some pages render verdicts as
+/// images (such as `check.png`) instead of text, in which case the image is
+/// mapped to the text symbol for that verdict. Unrecognized images are
+/// returned as `<img {src}>` so they show up in warnings.
+fn verdict_cell_text(cell: ElementRef, img_selector: &Selector) -> String {
+    let text = elem_text(cell);
+    if !text.is_empty() {
+        return text;
+    }
+
+    let Some(src) = cell.select(img_selector).find_map(|img| img.attr("src")) else {
+        return text;
+    };
+
+    // verdicts by the words of the file name that mark them. words are compared
+    // whole, since keywords show up inside each other ("correct" in "incorrect",
+    // "time" in "runtime"), and the failing verdicts are checked first in case a
+    // name has several keywords, like "not_correct"
+    const KEYWORDS: &[(&[&str], &str)] = &[
+        (&["incorrect", "wrong", "cross", "not", "x"], "x"),
+        (&["runtime", "rte", "crash"], "!"),
+        (&["compile", "compilation"], "c"),
+        (&["clock", "time", "timeout", "tle"], "t"),
+        (&["check", "checkmark", "correct", "tick", "ok"], "*"),
+    ];
+
+    // only look at the file name, in case the directory happens to contain one
+    // of the keywords
+    let file = src.rsplit('/').next().unwrap_or(src).to_lowercase();
+    let stem = file.rsplit_once('.').map_or(&*file, |(stem, _)| stem);
+    let words = stem
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .collect::<Vec<_>>();
+
+    KEYWORDS
+        .iter()
+        .find(|(keywords, _)| words.iter().any(|w| keywords.contains(w)))
+        .map_or_else(
+            || format!("<img {src}>"),
+            |(_, verdict)| verdict.to_string(),
+        )
+}
+
+/// A `&'static` [`Selector`] for the given CSS selector, which is only parsed
+/// the first time it's used rather than on every call since the parsers run
+/// over hundreds of pages.
+macro_rules! selector {
+    ($selector:literal) => {{
+        static SELECTOR: LazyLock<Selector> = LazyLock::new(|| Selector::parse($selector).unwrap());
+        &*SELECTOR
+    }};
+}
+
+/// The division named in the title or headings of a contest page, such as
+/// "USACO 2024 US Open Contest, Platinum". Abbreviations like "Plat" are
+/// accepted too.
+fn parse_page_division(doc: &Html) -> Option<Division> {
+    let heading_selector = selector!("title, h1, h2, h3");
+
+    doc.select(heading_selector).find_map(|heading| {
+        elem_text(heading)
+            .split(|c: char| !c.is_alphanumeric())
+            .find_map(|word| match &*word.to_lowercase() {
+                "bronze" => Some(Division::Bronze),
+                "silver" => Some(Division::Silver),
+                "gold" => Some(Division::Gold),
+                "platinum" | "plat" => Some(Division::Platinum),
+                _ => None,
+            })
+    })
+}
+
+/// Estimates the points earned on each problem from the testcase results. A
+/// problem without a listed `max_score` is assumed to be worth an equal share
+/// of 1000 points, which is how USACO has always scored contests.
+fn problem_scores(
+    problems: &[ContestProblem],
+    submission_results: &[Option<Vec<TestcaseResult>>],
+) -> Vec<Option<u16>> {
+    submission_results
+        .iter()
+        .enumerate()
+        .map(|(i, results)| {
+            let results = results.as_ref().filter(|r| !r.is_empty())?;
+            let max_score = problems
+                .get(i)
+                .and_then(|p| p.max_score)
+                .map(f64::from)
+                .unwrap_or(1000. / submission_results.len() as f64);
+            let correct = results
+                .iter()
+                .filter(|&&r| r == TestcaseResult::Correct)
+                .count();
+
+            Some((max_score * correct as f64 / results.len() as f64).round() as u16)
+        })
+        .collect()
+}
+
+/// Parses a problem header such as "Problem 1 (333)" into a [`ContestProblem`].
+fn parse_problem_header(text: String) -> ContestProblem {
+    if let Some((name, points)) = text.strip_suffix(')').and_then(|t| t.rsplit_once('(')) {
+        if let Ok(max_score) = points.trim().parse() {
+            return ContestProblem {
+                name: name.trim().to_string(),
+                max_score: Some(max_score),
+            };
+        }
+    }
+
+    ContestProblem {
+        name: text,
+        max_score: None,
+    }
+}
+
 /// Parses a contest results page, such as [this one](https://usaco.org/current/data/open24_platinum_results.html).
 /// This function should never panic. Instead, it will ignore unexpected data.
-#[instrument(skip(html))]
 pub fn parse_contest_page(time: MonthYear, division: Division, html: &str) -> Contest {
-    let doc = Html::parse_document(html);
+    parse_contest_page_with_diagnostics(time, division, html).0
+}
 
-    let table_selector = Selector::parse("table").unwrap();
-    let tr_selector = Selector::parse("tr").unwrap();
-    let th_selector = Selector::parse("th").unwrap();
-    let td_selector = Selector::parse("td").unwrap();
+/// Like [`parse_contest_page`], but also returns everything unexpected that
+/// was ignored. An empty list means the page was parsed cleanly.
+#[instrument(skip(html))]
+pub fn parse_contest_page_with_diagnostics(
+    time: MonthYear,
+    division: Division,
+    html: &str,
+) -> (Contest, Vec<ParseWarning>) {
+    let doc = Html::parse_document(html);
+    let mut warnings = vec![];
+
+    let table_selector = selector!("table");
+    let tr_selector = selector!("tr");
+    let th_selector = selector!("th");
+    let td_selector = selector!("td");
+    let img_selector = selector!("img");
+
+    let division_mismatch = match parse_page_division(&doc) {
+        Some(page_division) if page_division != division => {
+            report(
+                &mut warnings,
+                "",
+                format!(
+                    "page heading is for division {page_division:?}, not the requested division"
+                ),
+            );
+            true
+        }
+        Some(_) => false,
+        None => {
+            debug!("couldn't find division in page heading");
+            false
+        }
+    };
 
     let mut participants = vec![];
+    let mut contest_problems: Option<Vec<ContestProblem>> = None;
 
-    for table in doc.select(&table_selector) {
-        let mut rows = table.select(&tr_selector);
+    for table in doc.select(table_selector) {
+        let mut rows = table.select(tr_selector);
 
-        let (observers, col_widths) = match || -> anyhow::Result<_> {
+        let (observers, problems) = match || -> anyhow::Result<_> {
             // first row is header row (USACO doesn't use <thead>, instead all rows get
             // stuffed into <tbody>)
             let headers = rows.next().ok_or_else(|| anyhow!("missing header row"))?;
             let headers_text = headers
-                .select(&th_selector)
+                .select(th_selector)
                 .map(elem_text)
                 .collect::<Vec<_>>();
 
@@ -252,55 +760,84 @@ pub fn parse_contest_page(time: MonthYear, division: Division, html: &str) -> Co
 
             // columns look like:
             // country, year?, name, score, blank, p1, blank, p2, blank, p3
-            // where each testcase result of a problem is its own column, so col_widths
+            // where each testcase result of a problem is its own column, so the colspan
             // roughly stores the number of testcases for each problem. it seems like
             // there's a blank <td> at the end of each problem and part of its colspan
-            // though.
-            let Some(col_widths) = headers
-                .select(&th_selector)
+            // though. some pages additionally have a "time" column after each problem.
+            let mut problem_headers = headers
+                .select(th_selector)
                 .skip(if observers { 3 } else { 4 })
-                .enumerate()
-                .filter_map(|(i, x)| (i % 2 == 1).then_some(x))
-                .map(|c| c.attr("colspan").and_then(|c| c.parse::<u8>().ok()))
-                .collect::<Option<Vec<_>>>()
-            else {
-                anyhow::bail!("failed to parse colspan of problems");
-            };
+                .peekable();
+            let mut problems = vec![];
+            while problem_headers.next().is_some() {
+                let Some(problem) = problem_headers.next() else {
+                    break;
+                };
+                let col_width = problem
+                    .attr("colspan")
+                    .and_then(|c| c.parse::<u8>().ok())
+                    .ok_or_else(|| anyhow!("failed to parse colspan of problems"))?;
+                let timed = problem_headers
+                    .next_if(|h| elem_text(*h).eq_ignore_ascii_case("time"))
+                    .is_some();
+
+                problems.push((col_width, timed, parse_problem_header(elem_text(problem))));
+            }
 
-            Ok((observers, col_widths))
+            Ok((observers, problems))
         }() {
             Ok(x) => x,
             Err(e) => {
-                warn!("error when parsing table: {e:?}");
+                report(
+                    &mut warnings,
+                    table.html(),
+                    format!("error when parsing table: {e:?}"),
+                );
                 continue;
             }
         };
 
+        // every table should list the same problems
+        let table_problems = problems
+            .iter()
+            .map(|(_, _, p)| p.clone())
+            .collect::<Vec<_>>();
+        match &contest_problems {
+            None => contest_problems = Some(table_problems.clone()),
+            Some(p) if *p != table_problems => report(
+                &mut warnings,
+                "",
+                format!("tables list different problems: {p:?} and {table_problems:?}"),
+            ),
+            Some(_) => {}
+        }
+
         // parse each row of the standings
         for row in rows {
             let res = || -> anyhow::Result<_> {
-                let mut cells = row.select(&td_selector).map(elem_text);
+                let mut cells = row.select(td_selector);
                 let mut next_cell = || cells.next().ok_or_else(|| anyhow!("row is missing cells"));
 
-                let country = next_cell()?;
+                let country = normalize_country(&elem_text(next_cell()?)).into_owned();
                 let graduation = if observers {
                     Graduation::Observer
                 } else {
                     Graduation::HighSchool {
-                        year: next_cell()?.parse()?,
+                        year: elem_text(next_cell()?).parse()?,
                     }
                 };
-                let name = next_cell()?;
-                let score = next_cell()?.parse()?;
+                let name = elem_text(next_cell()?);
+                let score = elem_text(next_cell()?).parse()?;
 
                 let mut submission_results = vec![];
-                for &col_width in &col_widths {
+                let mut submission_times = vec![];
+                for &(col_width, timed, _) in &problems {
                     // this column should be an empty <td>
                     next_cell()?;
 
                     // the actual testcase results
                     let mut problem_res = (0..col_width)
-                        .map(|_| next_cell())
+                        .map(|_| next_cell().map(|c| verdict_cell_text(c, img_selector)))
                         .collect::<Result<Vec<_>, _>>()?;
 
                     // seems like there's just a trailing empty td after each problem for some
@@ -309,6 +846,13 @@ pub fn parse_contest_page(time: MonthYear, division: Division, html: &str) -> Co
                         problem_res.pop();
                     }
 
+                    let submission_time = if timed {
+                        Some(elem_text(next_cell()?)).filter(|t| !t.is_empty())
+                    } else {
+                        None
+                    };
+                    submission_times.push(submission_time);
+
                     if problem_res.iter().all(|s| s.is_empty()) {
                         // no submission
                         submission_results.push(None);
@@ -337,53 +881,234 @@ pub fn parse_contest_page(time: MonthYear, division: Division, html: &str) -> Co
                     graduation,
                     name,
                     score,
+                    problem_scores: problem_scores(&table_problems, &submission_results),
                     submission_results,
+                    submission_times: if problems.iter().any(|&(_, timed, _)| timed) {
+                        submission_times
+                    } else {
+                        vec![]
+                    },
                 });
 
                 Ok(())
             }();
 
             if let Err(e) = res {
-                warn!("error when parsing row `{}`: {e:?}", row.html());
+                report(
+                    &mut warnings,
+                    row.html(),
+                    format!("error when parsing row: {e:?}"),
+                );
             }
         }
     }
 
-    // deal with duplicate entries in pre-college global vs pre-college US
+    // deal with duplicate entries in pre-college global vs pre-college US.
+    // countries are normalized already, so differently spelled duplicates are
+    // caught too
     {
         let mut vis = HashSet::new();
         participants.retain(|c| vis.insert(c.clone()));
     }
 
-    Contest {
+    let contest = Contest {
         time,
         division,
+        problems: contest_problems.unwrap_or_default(),
         participants,
+        division_mismatch,
+    };
+
+    (contest, warnings)
+}
+
+/// Escapes text for use in HTML.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Regenerates a results page for `contest` in the same structure as USACO's,
+/// which is useful for archiving and for diffing against the live pages. This
+/// is the inverse of [`parse_contest_page`]: parsing the result gives back the
+/// same participants, in the same order other than observers being listed
+/// last.
+///
+/// The page only has the heading and the results tables, not the problem
+/// links or the other surrounding text. USACO puts every testcase result in
+/// its own column, so this assumes each problem has the same number of
+/// testcases for everyone who submitted to it. Participants with fewer
+/// results for a problem (as happened in 2017 Open Gold) can't be represented
+/// exactly, and won't be parsed back.
+pub fn contest_to_html(contest: &Contest) -> String {
+    use std::fmt::Write;
+
+    let problem_count = contest
+        .participants
+        .iter()
+        .map(|p| p.submission_results.len())
+        .chain([contest.problems.len()])
+        .max()
+        .unwrap_or_default();
+    let testcase_counts = (0..problem_count)
+        .map(|i| {
+            contest
+                .participants
+                .iter()
+                .filter_map(|p| p.submission_results.get(i)?.as_ref())
+                .map(|r| r.len())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let timed = contest
+        .participants
+        .iter()
+        .any(|p| !p.submission_times.is_empty());
+
+    let mut html = format!(
+        "<html><body><div class=\"panel\">\n<h2>USACO {} {} Contest, {}</h2>\n",
+        contest.time.year,
+        contest.time.month.url_name(),
+        contest.division.url_name(),
+    );
+
+    // students and observers are listed in separate tables, since observers
+    // don't have a year column
+    for observers in [false, true] {
+        let participants = contest
+            .participants
+            .iter()
+            .filter(|p| (p.graduation == Graduation::Observer) == observers)
+            .collect::<Vec<_>>();
+        if participants.is_empty() {
+            continue;
+        }
+
+        html.push_str("<table>\n<tr><th>Country</th>");
+        if !observers {
+            html.push_str("<th>Year</th>");
+        }
+        html.push_str("<th>Name</th><th>Score</th>");
+        for (i, &count) in testcase_counts.iter().enumerate() {
+            let name = match contest.problems.get(i) {
+                Some(ContestProblem {
+                    name,
+                    max_score: Some(max_score),
+                }) => format!("{} ({max_score})", escape_html(name)),
+                Some(ContestProblem { name, .. }) => escape_html(name),
+                None => (i + 1).to_string(),
+            };
+
+            // each problem's colspan includes a trailing blank column
+            write!(html, "<th></th><th colspan=\"{}\">{name}</th>", count + 1).unwrap();
+            if timed {
+                html.push_str("<th>Time</th>");
+            }
+        }
+        html.push_str("</tr>\n");
+
+        for p in participants {
+            write!(html, "<tr><td>{}</td>", escape_html(&p.country)).unwrap();
+            if let Graduation::HighSchool { year } = p.graduation {
+                write!(html, "<td>{year}</td>").unwrap();
+            }
+            write!(
+                html,
+                "<td>{}</td><td>{}</td>",
+                escape_html(&p.name),
+                p.score
+            )
+            .unwrap();
+
+            for (i, &count) in testcase_counts.iter().enumerate() {
+                html.push_str("<td></td>");
+
+                let results = p.submission_results.get(i).and_then(|r| r.as_deref());
+                for j in 0..count {
+                    let verdict = results.and_then(|r| r.get(j)).map_or("", |r| r.symbol());
+                    write!(html, "<td>{verdict}</td>").unwrap();
+                }
+                html.push_str("<td></td>");
+
+                if timed {
+                    let time = p.submission_times.get(i).and_then(|t| t.as_deref());
+                    write!(html, "<td>{}</td>", escape_html(time.unwrap_or_default())).unwrap();
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</div></body></html>\n");
+
+    html
+}
+
+/// Removes the preferred names in parentheses from `name`, such as
+/// "(Ben) Benjamin Qi" to "Benjamin Qi". Each group of parentheses followed by
+/// a space is removed on its own, so "Jane (JJ) Mary (M) Doe" becomes "Jane
+/// Mary Doe". This matches what the bot does for IOI and EGOI names.
+fn strip_preferred_name(name: &str) -> String {
+    let mut res = String::new();
+    let mut rest = name;
+
+    while let Some(open) = rest.find('(') {
+        res.push_str(&rest[..open]);
+        rest = &rest[open..];
+
+        // nested parentheses aren't a group, and neither is one without a space
+        // after it
+        match rest[1..].find(['(', ')']).map(|i| i + 1) {
+            Some(close) if rest[close..].starts_with(") ") => rest = &rest[close + 2..],
+            _ => {
+                res.push('(');
+                rest = &rest[1..];
+            }
+        }
     }
+    res.push_str(rest);
+
+    normalize_text(&res)
 }
 
 /// Parses a USACO finalists announcement page, such as [this one](https://usaco.org/index.php?page=finalists24).
 /// This function should never panic. Instead, it will ignore unexpected data.
-#[instrument(skip(html))]
 pub fn parse_camp_page(camp_year: u16, html: &str) -> Camp {
+    parse_camp_page_with_diagnostics(camp_year, html).0
+}
+
+/// Like [`parse_camp_page`], but also returns everything unexpected that was
+/// ignored.
+#[instrument(skip(html))]
+pub fn parse_camp_page_with_diagnostics(camp_year: u16, html: &str) -> (Camp, Vec<ParseWarning>) {
     let doc = Html::parse_document(html);
+    let mut warnings = vec![];
 
-    let table_selector = Selector::parse("table").unwrap();
-    let tr_selector = Selector::parse("tr").unwrap();
-    let td_selector = Selector::parse("td").unwrap();
+    let table_selector = selector!("table");
+    let tr_selector = selector!("tr");
+    let td_selector = selector!("td");
 
     let mut participants = vec![];
 
-    for (table_ind, table) in doc.select(&table_selector).enumerate() {
+    for (table_ind, table) in doc.select(table_selector).enumerate() {
         // should have at most two tables. second table, if it exists, should be EGOI
         // finalists.
         if table_ind >= 2 {
-            warn!("camp page should only have at most two tables");
+            report(
+                &mut warnings,
+                table.html(),
+                "camp page should only have at most two tables",
+            );
             continue;
         }
 
         // skip header row
-        let rows = table.select(&tr_selector).skip(1);
+        let rows = table.select(tr_selector).skip(1);
 
         for row in rows {
             // just randomly appears on 14 and 24 for some reason.
@@ -392,15 +1117,21 @@ pub fn parse_camp_page(camp_year: u16, html: &str) -> Camp {
             }
 
             let mut res = || -> anyhow::Result<_> {
-                let cells = row.select(&td_selector).map(elem_text).collect::<Vec<_>>();
+                let mut cells = row.select(td_selector).map(elem_text).collect::<Vec<_>>();
 
+                // columns are graduation year, name, school, and state. stray markup can
+                // leave extra cells at the end, which we ignore
+                if cells.len() > 4 {
+                    debug!("ignoring extra cells in row `{}`", row.html());
+                    cells.truncate(4);
+                }
                 let [graduation_year, name, school, state] = cells
                     .try_into()
                     .map_err(|_| anyhow::anyhow!("unexpected number of cells in row"))?;
 
                 participants.push(CampParticipant {
                     graduation_year: graduation_year.parse()?,
-                    name,
+                    name: strip_preferred_name(&name),
                     school,
                     state,
                     is_egoi: table_ind > 0,
@@ -410,33 +1141,55 @@ pub fn parse_camp_page(camp_year: u16, html: &str) -> Camp {
             };
 
             if let Err(e) = res() {
-                warn!("error when parsing row `{}`: {e:?}", row.html());
+                report(
+                    &mut warnings,
+                    row.html(),
+                    format!("error when parsing row: {e:?}"),
+                );
             }
         }
     }
 
-    Camp {
+    let camp = Camp {
         year: camp_year,
         participants,
-    }
+    };
+
+    (camp, warnings)
 }
 
 /// Parses [the history page](https://usaco.org/index.php?page=history).
 /// This function should never panic. Instead, it will ignore unexpected data.
-#[instrument(skip(html))]
 pub fn parse_history_page(html: &str) -> IntlHistory {
+    parse_history_page_with_diagnostics(html).0
+}
+
+/// Like [`parse_history_page`], but also returns everything unexpected that
+/// was ignored.
+pub fn parse_history_page_with_diagnostics(html: &str) -> (IntlHistory, Vec<ParseWarning>) {
+    parse_history_sections(html, &IntlCompetition::ALL)
+}
+
+/// Parses the sections of the history page for `competitions`, leaving the
+/// other competitions' results empty.
+#[instrument(skip(html))]
+fn parse_history_sections(
+    html: &str,
+    competitions: &[IntlCompetition],
+) -> (IntlHistory, Vec<ParseWarning>) {
     let doc = Html::parse_document(html);
+    let mut warnings = vec![];
 
-    let outer_div_selector = Selector::parse(".content > div").unwrap();
-    let inner_div_selector = Selector::parse("div.panel.historypanel").unwrap();
-    let h2_selector = Selector::parse("h2").unwrap();
+    let outer_div_selector = selector!(".content > div");
+    let inner_div_selector = selector!("div.panel.historypanel");
+    let h2_selector = selector!("h2");
 
     let mut ioi = vec![];
     let mut egoi = vec![];
 
     // history page is split into two outer divs, one for ioi and another for egoi
-    for outer in doc.select(&outer_div_selector) {
-        let Some(heading) = outer.select(&h2_selector).next() else {
+    for outer in doc.select(outer_div_selector) {
+        let Some(heading) = outer.select(h2_selector).next() else {
             continue;
         };
         let heading = heading.text().collect::<String>();
@@ -445,9 +1198,10 @@ pub fn parse_history_page(html: &str) -> IntlHistory {
         let is_egoi = heading.contains("EGOI");
 
         if is_ioi && is_egoi {
-            warn!(
-                "section contains both IOI and EGOI in its heading `{}`",
-                outer.html()
+            report(
+                &mut warnings,
+                outer.html(),
+                "section contains both IOI and EGOI in its heading",
             );
             continue;
         }
@@ -456,12 +1210,21 @@ pub fn parse_history_page(html: &str) -> IntlHistory {
             continue;
         }
 
+        let competition = if is_ioi {
+            IntlCompetition::Ioi
+        } else {
+            IntlCompetition::Egoi
+        };
+        if !competitions.contains(&competition) {
+            continue;
+        }
+
         let mut results = vec![];
 
         // within each ioi/egoi outer div are inner divs corresponding to each year
-        for year_div in outer.select(&inner_div_selector) {
+        for year_div in outer.select(inner_div_selector) {
             let Ok(year) = elem_text(year_div)[0..4].parse() else {
-                warn!("failed to parse year of `{}`", year_div.html());
+                report(&mut warnings, year_div.html(), "failed to parse year");
                 continue;
             };
 
@@ -524,10 +1287,10 @@ pub fn parse_history_page(html: &str) -> IntlHistory {
                 };
 
                 if let Err(e) = res() {
-                    warn!(
-                        "error when parsing year `{}` and contestant `{:?}`: {e:?}",
+                    report(
+                        &mut warnings,
                         year_div.html(),
-                        contestant
+                        format!("error when parsing contestant `{name}`: {e:?}"),
                     );
                 }
             }
@@ -535,12 +1298,12 @@ pub fn parse_history_page(html: &str) -> IntlHistory {
 
         if is_ioi {
             if !ioi.is_empty() {
-                warn!("ioi parsed twice");
+                report(&mut warnings, outer.html(), "ioi parsed twice");
             }
             ioi = results;
         } else {
             if !egoi.is_empty() {
-                warn!("egoi parsed twice");
+                report(&mut warnings, outer.html(), "egoi parsed twice");
             }
             egoi = results;
         }
@@ -550,12 +1313,13 @@ pub fn parse_history_page(html: &str) -> IntlHistory {
     ioi.sort_by_key(|c| c.year);
     egoi.sort_by_key(|c| c.year);
 
-    IntlHistory { ioi, egoi }
+    (IntlHistory { ioi, egoi }, warnings)
 }
 
 /// An HTTP client which can handle simple GET requests. This trait exists so
 /// users are free to implement behavior such as rate limiting, custom user
-/// agents, or progress reporting.
+/// agents, or progress reporting. Most clients can be written as a closure
+/// instead, see [`client_fn`].
 pub trait HttpClient {
     type Error;
     type Future: Future<Output = Result<(StatusCode, String), Self::Error>> + Send + 'static;
@@ -563,142 +1327,549 @@ pub trait HttpClient {
     fn get(&mut self, url: Url) -> Self::Future;
 }
 
-/// Parses all standings related data on the USACO website. Results are sorted
-/// in increasing order of time and division.
-///
-/// `max_year` is the maximum year to parse until. If it's year 2025, for
-/// example, standings up until and including the 2024-25 season will be parsed.
-///
-/// This function will immediately request `client` with around ~250 URLs. Then,
-/// pages will be parsed as each request completes.
-///
-/// We return an error only when the provided `client` errors on an HTTP
-/// request.
-pub async fn parse_all<E: Send + 'static>(
-    max_year: u16,
-    mut client: impl HttpClient<Error = E>,
-) -> Result<UsacoData, E> {
-    // wrapper around our HTTP service to log strange HTTP results.
-    let mut get_url = move |url: String| {
-        let fut = client.get(url.parse().expect("url should be valid"));
+/// The months USACO held contests in during `season`. There used to be 6
+/// contests a season until the 2014-15 season.
+fn season_months(season: u16) -> &'static [Month] {
+    if season <= 2014 {
+        &Month::ALL
+    } else {
+        &[
+            Month::December,
+            Month::January,
+            Month::February,
+            Month::Open,
+        ]
+    }
+}
 
-        async move {
-            let (code, html) = fut.await?;
+/// The divisions that existed in `season`. Platinum was introduced in the
+/// 2015-16 season.
+fn season_divisions(season: u16) -> &'static [Division] {
+    if season <= 2015 {
+        &[Division::Bronze, Division::Silver, Division::Gold]
+    } else {
+        &Division::ALL
+    }
+}
 
-            if !code.is_success() {
-                if code == StatusCode::NOT_FOUND {
-                    debug!("{url} NOT FOUND");
-                } else {
-                    warn!("unexpected status code {code} for url {url}");
-                }
-                Ok(None)
-            } else {
-                Ok(Some(html))
-            }
-        }
+/// The time of the contest held in `month` of `season`. November and December
+/// contests happen in the calendar year before the season ends.
+fn season_contest_time(season: u16, month: Month) -> MonthYear {
+    let year = if matches!(month, Month::November | Month::December) {
+        season - 1
+    } else {
+        season
     };
 
-    let mut join_set_contests = JoinSet::new();
-    let mut join_set_camps = JoinSet::new();
+    MonthYear { year, month }
+}
 
-    for season in 2012..=max_year {
-        // deal with some USACO format changes causing not every year to have same
-        // number of contests or divisions
-        let months = if season <= 2014 {
-            [
-                Month::November,
-                Month::December,
-                Month::January,
-                Month::February,
-                Month::March,
-                Month::Open,
-            ]
-            .iter()
-        } else {
-            [
-                Month::December,
-                Month::January,
-                Month::February,
-                Month::Open,
-            ]
-            .iter()
-        }
-        .copied();
-        let divisions = if season <= 2015 {
-            [Division::Bronze, Division::Silver, Division::Gold].iter()
+/// The URL of the results page for the `division` contest held at `time`.
+/// Note that `time` is when the contest was held, so the December contest of
+/// the 2024 season is `MonthYear { year: 2023, month: Month::December }`.
+///
+/// Together with [`parse_contest_page`], this can be used to parse a single
+/// contest without going through [`parse_all`].
+pub fn contest_results_url(time: MonthYear, division: Division) -> Url {
+    format!(
+        "https://usaco.org/current/data/{}{}_{}_results.html",
+        time.month.url_name(),
+        time.year % 100,
+        division.url_name(),
+    )
+    .parse()
+    .expect("url should be valid")
+}
+
+/// Rewrites the usaco.org URLs [`parse_all_with_options`] requests. See
+/// [`ParseOptions::rewrite_url`].
+pub type UrlRewriter = Arc<dyn Fn(Url) -> Url + Send + Sync>;
+
+/// The URL of the [Wayback Machine](https://web.archive.org) snapshot of `url`
+/// closest to `timestamp`, which is of the form `YYYYMMDDhhmmss` (any prefix
+/// works too, such as `2019` or `201906`). Appending `id_` to the timestamp
+/// gets the page as originally served, without the archive.org toolbar.
+pub fn wayback_snapshot_url(timestamp: &str, url: &Url) -> Url {
+    format!("https://web.archive.org/web/{timestamp}/{url}")
+        .parse()
+        .expect("url should be valid")
+}
+
+/// The URL of the history page, which has IOI and EGOI results.
+const HISTORY_URL: &str = "https://usaco.org/index.php?page=history";
+
+/// The body of a response from `url` if it was successful, logging strange
+/// HTTP results.
+fn successful_page(url: &Url, code: StatusCode, html: String) -> Option<String> {
+    if !code.is_success() {
+        if code == StatusCode::NOT_FOUND {
+            debug!("{url} NOT FOUND");
         } else {
-            [
-                Division::Bronze,
-                Division::Silver,
-                Division::Gold,
-                Division::Platinum,
-            ]
-            .iter()
+            warn!("unexpected status code {code} for url {url}");
         }
-        .copied();
+        None
+    } else {
+        Some(html)
+    }
+}
 
-        for month in months {
-            let year = if matches!(month, Month::November | Month::December) {
-                season - 1
-            } else {
-                season
-            };
+/// How many of the requests made by [`parse_all_with_options`] have finished.
+/// The total includes every contest, camp, and history page request, and is
+/// the same every time progress is reported.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Progress {
+    pub completed: usize,
+    pub total: usize,
+}
 
-            for division in divisions.clone() {
-                let url = format!(
-                    "https://usaco.org/current/data/{}{}_{}_results.html",
-                    month.url_name(),
-                    year % 100,
-                    division.url_name(),
-                );
-                let req = get_url(url);
-
-                join_set_contests.spawn(async move {
-                    req.await.map(|res| {
-                        res.map(|html| {
-                            parse_contest_page(MonthYear { month, year }, division, &html)
-                        })
-                    })
-                });
-            }
-        }
+/// Receives [`Progress`] updates. See [`ParseOptions::progress`].
+pub type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
 
-        {
-            let url = format!("https://usaco.org/index.php?page=finalists{}", season % 100);
-            let req = get_url(url);
+/// Options controlling what [`parse_all_with_options`] scrapes.
+#[derive(Clone)]
+pub struct ParseOptions {
+    /// The maximum year to parse until. If it's year 2025, for example,
+    /// standings up until and including the 2024-25 season will be parsed.
+    pub max_year: u16,
+    /// The divisions to scrape contests for. Defaults to all four divisions.
+    ///
+    /// This only filters the divisions that existed in each season, so for
+    /// example no platinum contests will be requested before the 2015-16
+    /// season regardless of this option.
+    pub divisions: Vec<Division>,
+    /// Whether to check for USACO format changes, such as a return to holding
+    /// contests in November or March. Enabled by default.
+    ///
+    /// The months and divisions held each season are hardcoded, so a format
+    /// change would otherwise silently show up as missing results. When
+    /// enabled, we additionally request the results pages of the most recent
+    /// season's contests (for each division in `divisions`) in months that we
+    /// don't expect to have contests, and log a warning if any of them exist.
+    /// This can't detect brand new divisions, since we can't guess their URLs.
+    pub probe_format_changes: bool,
+    /// If set, every usaco.org URL is passed through this before being
+    /// requested. `None` by default.
+    ///
+    /// This can be used with [`ParseOptions::wayback_snapshot`] to scrape
+    /// results USACO has since removed or changed.
+    pub rewrite_url: Option<UrlRewriter>,
+    /// If set, this is called every time a request finishes, whether or not
+    /// it succeeded. `None` by default.
+    pub progress: Option<ProgressCallback>,
+    /// The maximum number of requests in flight at once. Unlimited (as in
+    /// [`Semaphore::MAX_PERMITS`]) by default.
+    ///
+    /// Requests are only passed to the client once they're allowed to run, so
+    /// this works even with clients that start requesting as soon as
+    /// [`HttpClient::get`] is called.
+    pub max_concurrent: usize,
+    /// When to send each request. [`Scheduling::AllAtOnce`] by default.
+    pub scheduling: Scheduling,
+    /// If set, every contest and camp page is saved to this directory (which
+    /// is created if it doesn't exist) as soon as it's parsed, and pages
+    /// already saved there are loaded instead of requested. `None` by
+    /// default. See [`resume_from`].
+    ///
+    /// Each page is saved as JSON named after its URL: contests as
+    /// `open24_platinum.json` and camps as `finalists24.json`. Pages that
+    /// don't exist are saved as `null`. The history page and format change
+    /// probes are cheap, so they're always requested.
+    #[cfg(feature = "checkpoint")]
+    pub checkpoint_dir: Option<std::path::PathBuf>,
+    /// The international competitions to parse results for. Defaults to both
+    /// IOI and EGOI.
+    ///
+    /// These are all on the same history page, so it's still requested unless
+    /// this is empty. Only the sections for other competitions are skipped,
+    /// and come back empty.
+    pub intl_competitions: Vec<IntlCompetition>,
+}
 
-            join_set_camps.spawn(async move {
-                req.await
-                    .map(|res| res.map(|html| parse_camp_page(season, &html)))
-            });
+/// An international competition USACO selects a team for, with results on the
+/// history page. See [`ParseOptions::intl_competitions`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IntlCompetition {
+    Ioi,
+    Egoi,
+}
+
+impl IntlCompetition {
+    /// Every competition, in the order they're listed on the history page.
+    pub const ALL: [IntlCompetition; 2] = [IntlCompetition::Ioi, IntlCompetition::Egoi];
+
+    /// The acronym of the competition, such as "IOI".
+    fn acronym(self) -> &'static str {
+        match self {
+            IntlCompetition::Ioi => "IOI",
+            IntlCompetition::Egoi => "EGOI",
         }
     }
+}
 
-    let intl_history = async {
-        get_url("https://usaco.org/index.php?page=history".to_string())
-            .await
-            .map(|res| {
-                // if we couldn't load the history page, we'll just parse the empty string and
-                // return an empty result
-                parse_history_page(&res.unwrap_or_default())
-            })
-    };
+/// Formats the competition as its acronym, such as "IOI".
+impl std::fmt::Display for IntlCompetition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.acronym())
+    }
+}
 
-    let (contests, camps, intl_history) = tokio::join!(
-        join_set_contests.join_all(),
-        join_set_camps.join_all(),
-        intl_history
-    );
-    let intl_history = intl_history?;
+/// Parses the acronym of the competition (as formatted by the
+/// [`Display`](std::fmt::Display) impl) case-insensitively.
+impl FromStr for IntlCompetition {
+    type Err = ParseNameError;
 
-    let mut contests = contests
-        .into_iter()
-        .filter_map(|x| x.transpose())
-        .collect::<Result<Vec<_>, _>>()?;
-    let mut camps = camps
-        .into_iter()
-        .filter_map(|x| x.transpose())
-        .collect::<Result<Vec<_>, _>>()?;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        IntlCompetition::ALL
+            .into_iter()
+            .find(|c| c.acronym().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseNameError {
+                kind: "competition",
+                input: s.to_string(),
+            })
+    }
+}
+
+/// How [`parse_all_with_options`] schedules its requests. See
+/// [`ParseOptions::scheduling`]. The returned data doesn't depend on this.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Scheduling {
+    /// Send every request immediately. This is the fastest, since the
+    /// scrape only takes as long as the slowest request (subject to
+    /// [`ParseOptions::max_concurrent`] and any rate limiting done by the
+    /// client).
+    #[default]
+    AllAtOnce,
+    /// Send every request for one season at once, but only start on the next
+    /// season once all of the previous season's requests finish. Load on
+    /// usaco.org is more predictable and progress is reported season by
+    /// season, but every season waits on its slowest request, so with a dozen
+    /// or so seasons this is usually several times slower than
+    /// [`Scheduling::AllAtOnce`]. The history page is requested along with
+    /// the first season.
+    SequentialSeasons,
+}
+
+impl std::fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("ParseOptions");
+        f.field("max_year", &self.max_year)
+            .field("divisions", &self.divisions)
+            .field("probe_format_changes", &self.probe_format_changes)
+            .field("rewrite_url", &self.rewrite_url.as_ref().map(|_| ".."))
+            .field("progress", &self.progress.as_ref().map(|_| ".."))
+            .field("max_concurrent", &self.max_concurrent)
+            .field("scheduling", &self.scheduling)
+            .field("intl_competitions", &self.intl_competitions);
+        #[cfg(feature = "checkpoint")]
+        f.field("checkpoint_dir", &self.checkpoint_dir);
+        f.finish()
+    }
+}
+
+impl ParseOptions {
+    /// The default options for scraping up until `max_year`.
+    pub fn new(max_year: u16) -> Self {
+        Self {
+            max_year,
+            divisions: Division::ALL.to_vec(),
+            probe_format_changes: true,
+            rewrite_url: None,
+            progress: None,
+            max_concurrent: Semaphore::MAX_PERMITS,
+            scheduling: Scheduling::AllAtOnce,
+            #[cfg(feature = "checkpoint")]
+            checkpoint_dir: None,
+            intl_competitions: IntlCompetition::ALL.to_vec(),
+        }
+    }
+
+    /// Scrape from the Wayback Machine snapshots closest to `timestamp`
+    /// instead of from usaco.org directly. See [`wayback_snapshot_url`].
+    ///
+    /// archive.org rate limits aggressively, so the client should be wrapped
+    /// in a [`RateLimitedClient`] allowing at most around one request per
+    /// second.
+    pub fn wayback_snapshot(self, timestamp: impl Into<String>) -> Self {
+        let timestamp = timestamp.into();
+
+        Self {
+            rewrite_url: Some(Arc::new(move |url| wayback_snapshot_url(&timestamp, &url))),
+            ..self
+        }
+    }
+}
+
+/// Parses all standings related data on the USACO website using the default
+/// [`ParseOptions`]. See [`parse_all_with_options`] for details.
+pub async fn parse_all<E: Send + 'static>(
+    max_year: u16,
+    client: impl HttpClient<Error = E> + Send + 'static,
+) -> Result<UsacoData, E> {
+    parse_all_with_options(ParseOptions::new(max_year), client).await
+}
+
+/// Parses all standings related data on the USACO website, as configured by
+/// `options`. Results are sorted in increasing order of time and division.
+///
+/// This function will immediately request `client` with around ~250 URLs. Then,
+/// pages will be parsed as each request completes. To be polite to usaco.org,
+/// consider wrapping `client` in a [`RateLimitedClient`] or setting
+/// [`ParseOptions::max_concurrent`].
+///
+/// We return an error only when the provided `client` errors on an HTTP
+/// request.
+pub async fn parse_all_with_options<E: Send + 'static>(
+    options: ParseOptions,
+    client: impl HttpClient<Error = E> + Send + 'static,
+) -> Result<UsacoData, E> {
+    // figure out every request up front, so that the total is known before any
+    // progress is reported
+    let mut contest_requests = vec![];
+    let mut camp_requests = vec![];
+    for season in 2012..=options.max_year {
+        for &month in season_months(season) {
+            let time = season_contest_time(season, month);
+
+            for &division in season_divisions(season)
+                .iter()
+                .filter(|d| options.divisions.contains(d))
+            {
+                contest_requests.push((season, time, division));
+            }
+        }
+
+        camp_requests.push(season);
+    }
+
+    // look for contests in the latest season that we don't expect to exist, in
+    // case USACO changed its format. see `ParseOptions::probe_format_changes`.
+    let mut probe_requests = vec![];
+    if options.probe_format_changes {
+        let season = options.max_year;
+
+        for month in Month::ALL
+            .into_iter()
+            .filter(|m| !season_months(season).contains(m))
+        {
+            for &division in season_divisions(season)
+                .iter()
+                .filter(|d| options.divisions.contains(d))
+            {
+                probe_requests.push(contest_results_url(
+                    season_contest_time(season, month),
+                    division,
+                ));
+            }
+        }
+    }
+
+    let mut contests = vec![];
+    let mut camps = vec![];
+
+    // skip pages that were already checkpointed
+    #[cfg(feature = "checkpoint")]
+    if let Some(dir) = &options.checkpoint_dir {
+        checkpoint::create_dir(dir).await;
+
+        let mut remaining = vec![];
+        for req @ (_, time, division) in contest_requests {
+            match checkpoint::load(&dir.join(checkpoint::contest_file_name(time, division))).await {
+                Some(contest) => contests.push(Ok(contest)),
+                None => remaining.push(req),
+            }
+        }
+        contest_requests = remaining;
+
+        let mut remaining = vec![];
+        for season in camp_requests {
+            match checkpoint::load(&dir.join(checkpoint::camp_file_name(season))).await {
+                Some(camp) => camps.push(Ok(camp)),
+                None => remaining.push(season),
+            }
+        }
+        camp_requests = remaining;
+    }
+
+    // the history page is the one extra request, unless there's nothing to parse
+    // from it
+    let history_requests = usize::from(!options.intl_competitions.is_empty());
+    let total =
+        contest_requests.len() + camp_requests.len() + probe_requests.len() + history_requests;
+    let completed = Arc::new(AtomicUsize::new(0));
+
+    // wrapper around our HTTP service to rewrite urls, report progress, and log
+    // strange HTTP results.
+    let rewrite_url = options.rewrite_url.clone();
+    let progress = options.progress.clone();
+    let semaphore = Arc::new(Semaphore::new(
+        options.max_concurrent.clamp(1, Semaphore::MAX_PERMITS),
+    ));
+    // the client is shared so that requests can be started from inside their
+    // futures, once a permit is available
+    let client = Arc::new(std::sync::Mutex::new(client));
+    let get_url = move |mut url: Url| {
+        if let Some(rewrite_url) = &rewrite_url {
+            url = rewrite_url(url);
+        }
+        let client = client.clone();
+        let completed = completed.clone();
+        let progress = progress.clone();
+        let semaphore = semaphore.clone();
+
+        async move {
+            let permit = semaphore
+                .acquire()
+                .await
+                .expect("semaphore should never be closed");
+            // the lock is only held while starting the request
+            let fut = client
+                .lock()
+                .expect("client lock shouldn't be poisoned")
+                .get(url.clone());
+            let res = fut.await;
+            drop(permit);
+
+            let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+            if let Some(progress) = progress {
+                progress(Progress { completed, total });
+            }
+
+            let (code, html) = res?;
+            Ok(successful_page(&url, code, html))
+        }
+    };
+
+    // every batch of seasons is requested only once the previous one finishes.
+    // the history page goes with the first batch
+    let seasons = 2012..=options.max_year;
+    let mut batches = match options.scheduling {
+        Scheduling::AllAtOnce => vec![],
+        Scheduling::SequentialSeasons => seasons.clone().map(|s| s..=s).collect(),
+    };
+    if batches.is_empty() {
+        batches.push(seasons);
+    }
+
+    let mut probes = vec![];
+    let mut intl_history = options.intl_competitions.is_empty().then(|| IntlHistory {
+        ioi: vec![],
+        egoi: vec![],
+    });
+    for batch in batches {
+        let mut join_set_contests = JoinSet::new();
+        for &(_, time, division) in contest_requests
+            .iter()
+            .filter(|(season, ..)| batch.contains(season))
+        {
+            let req = get_url(contest_results_url(time, division));
+            #[cfg(feature = "checkpoint")]
+            let checkpoint_path = options
+                .checkpoint_dir
+                .as_ref()
+                .map(|dir| dir.join(checkpoint::contest_file_name(time, division)));
+
+            join_set_contests.spawn(async move {
+                let contest = req
+                    .await?
+                    .map(|html| parse_contest_page(time, division, &html));
+                #[cfg(feature = "checkpoint")]
+                if let Some(path) = checkpoint_path {
+                    checkpoint::save(&path, &contest).await;
+                }
+
+                Ok(contest)
+            });
+        }
+
+        let mut join_set_camps = JoinSet::new();
+        for &season in camp_requests.iter().filter(|s| batch.contains(s)) {
+            let url = format!("https://usaco.org/index.php?page=finalists{}", season % 100);
+            let req = get_url(url.parse().expect("url should be valid"));
+            #[cfg(feature = "checkpoint")]
+            let checkpoint_path = options
+                .checkpoint_dir
+                .as_ref()
+                .map(|dir| dir.join(checkpoint::camp_file_name(season)));
+
+            join_set_camps.spawn(async move {
+                let camp = req.await?.map(|html| parse_camp_page(season, &html));
+                #[cfg(feature = "checkpoint")]
+                if let Some(path) = checkpoint_path {
+                    checkpoint::save(&path, &camp).await;
+                }
+
+                Ok(camp)
+            });
+        }
+
+        // probes are all for the latest season
+        let mut join_set_probes = JoinSet::new();
+        if batch.contains(&options.max_year) {
+            for url in probe_requests.drain(..) {
+                let req = get_url(url.clone());
+
+                join_set_probes.spawn(async move { (url, req.await.map(|res| res.is_some())) });
+            }
+        }
+
+        let history_req = intl_history
+            .is_none()
+            .then(|| get_url(HISTORY_URL.parse().expect("url should be valid")));
+        let history = async {
+            match history_req {
+                Some(req) => req.await.map(|res| {
+                    // if we couldn't load the history page, we'll just parse the empty string
+                    // and return an empty result
+                    Some(
+                        parse_history_sections(
+                            &res.unwrap_or_default(),
+                            &options.intl_competitions,
+                        )
+                        .0,
+                    )
+                }),
+                None => Ok(None),
+            }
+        };
+
+        let (batch_contests, batch_camps, history, batch_probes) = tokio::join!(
+            join_set_contests.join_all(),
+            join_set_camps.join_all(),
+            history,
+            join_set_probes.join_all()
+        );
+        if let Some(history) = history? {
+            intl_history = Some(history);
+        }
+        contests.extend(batch_contests);
+        camps.extend(batch_camps);
+        probes.extend(batch_probes);
+    }
+    let intl_history = intl_history.expect("history should be requested with the first batch");
+
+    // probes are only diagnostics, so failing to request one doesn't fail the
+    // whole scrape
+    for (url, res) in probes {
+        match res {
+            Ok(true) => warn!(
+                "found unexpected contest results at {url}. USACO may have changed its contest \
+                format, so results could be missing!"
+            ),
+            Ok(false) => {}
+            Err(_) => warn!("failed to request {url} to check for contest format changes"),
+        }
+    }
+
+    let mut contests = contests
+        .into_iter()
+        .filter_map(|x| x.transpose())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut camps = camps
+        .into_iter()
+        .filter_map(|x| x.transpose())
+        .collect::<Result<Vec<_>, _>>()?;
 
     contests.sort_unstable_by_key(|c| (c.time, c.division));
     camps.sort_unstable_by_key(|c| c.year);
@@ -710,9 +1881,96 @@ pub async fn parse_all<E: Send + 'static>(
     })
 }
 
+/// Resumes a scrape that checkpointed to `checkpoint_dir` (see
+/// [`ParseOptions::checkpoint_dir`]) but failed partway through. Pages that
+/// were already parsed are loaded from `checkpoint_dir`, and the rest are
+/// requested through `client` and checkpointed as usual. `options` should be
+/// the same as for the original scrape.
+#[cfg(feature = "checkpoint")]
+pub async fn resume_from<E: Send + 'static>(
+    checkpoint_dir: impl Into<std::path::PathBuf>,
+    options: ParseOptions,
+    client: impl HttpClient<Error = E> + Send + 'static,
+) -> Result<UsacoData, E> {
+    let options = ParseOptions {
+        checkpoint_dir: Some(checkpoint_dir.into()),
+        ..options
+    };
+
+    parse_all_with_options(options, client).await
+}
+
+/// Parses only the [history](https://usaco.org/index.php?page=history) page,
+/// which has IOI and EGOI results. This makes a single request, so it's a cheap
+/// way to pick up new results without calling [`parse_all`].
+///
+/// Like [`parse_all`], we return an error only when `client` errors, and an
+/// unsuccessful response is treated as an empty page.
+pub async fn parse_history<E>(mut client: impl HttpClient<Error = E>) -> Result<IntlHistory, E> {
+    let url: Url = HISTORY_URL.parse().expect("url should be valid");
+    let (code, html) = client.get(url.clone()).await?;
+
+    Ok(parse_history_page(
+        &successful_page(&url, code, html).unwrap_or_default(),
+    ))
+}
+
+/// The rank of each participant of `contest` by score, in the same order as
+/// [`Contest::participants`]. Participants with the same score share the best
+/// rank, so scores of 900, 800, 800, and 700 are ranked 1, 2, 2, and 4.
+pub fn placements(contest: &Contest) -> Vec<u32> {
+    let mut scores = contest
+        .participants
+        .iter()
+        .map(|p| p.score)
+        .collect::<Vec<_>>();
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+
+    contest
+        .participants
+        .iter()
+        .map(|p| 1 + scores.partition_point(|&s| s > p.score) as u32)
+        .collect()
+}
+
+/// Replaces every participant name in `data`, including IOI and EGOI results,
+/// with a pseudonym such as "Competitor #12", so that the data can be shared
+/// without identifying anyone. Everything else is kept as is.
+///
+/// Pseudonyms are assigned per name in order of first appearance, so records
+/// with the same name keep the same pseudonym, and anonymizing the same data
+/// always gives the same result. Note that camp records still include each
+/// finalist's school and state.
+pub fn anonymize(data: &mut UsacoData) {
+    let mut pseudonyms = HashMap::new();
+    let mut rename = |name: &mut String| {
+        let next = pseudonyms.len() + 1;
+        *name = pseudonyms
+            .entry(std::mem::take(name))
+            .or_insert_with(|| format!("Competitor #{next}"))
+            .clone();
+    };
+
+    for p in data.contests.iter_mut().flat_map(|c| &mut c.participants) {
+        rename(&mut p.name);
+    }
+    for p in data.camps.iter_mut().flat_map(|c| &mut c.participants) {
+        rename(&mut p.name);
+    }
+    for p in data
+        .intl_history
+        .ioi
+        .iter_mut()
+        .chain(&mut data.intl_history.egoi)
+    {
+        rename(&mut p.name);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::{convert::Infallible, pin::Pin, sync::Mutex, time::Duration};
 
     #[test]
     fn test_month_ord() {
@@ -766,6 +2024,76 @@ mod tests {
         assert!(hs2024 < observer);
     }
 
+    #[test]
+    fn test_month_division_from_str() {
+        for month in Month::ALL {
+            assert_eq!(month.to_string().parse(), Ok(month));
+        }
+        assert_eq!("OPEN".parse(), Ok(Month::Open));
+        assert_eq!(" January ".parse(), Ok(Month::January));
+        assert_eq!("dec".parse(), Ok(Month::December));
+        assert_eq!(
+            "sept".parse::<Month>().unwrap_err().to_string(),
+            "unrecognized month `sept`"
+        );
+
+        for division in Division::ALL {
+            assert_eq!(division.to_string().parse(), Ok(division));
+        }
+        assert_eq!("Plat".parse(), Ok(Division::Platinum));
+        assert_eq!(
+            "diamond".parse::<Division>().unwrap_err().to_string(),
+            "unrecognized division `diamond`"
+        );
+    }
+
+    #[test]
+    fn test_intl_competition_from_str() {
+        for comp in IntlCompetition::ALL {
+            assert_eq!(comp.to_string().parse(), Ok(comp));
+        }
+        assert_eq!("egoi".parse(), Ok(IntlCompetition::Egoi));
+        assert_eq!(
+            "usaco".parse::<IntlCompetition>().unwrap_err().to_string(),
+            "unrecognized competition `usaco`"
+        );
+    }
+
+    #[test]
+    fn test_division_next() {
+        assert_eq!(Division::Bronze.next(), Some(Division::Silver));
+        assert_eq!(Division::Gold.next(), Some(Division::Platinum));
+        assert_eq!(Division::Platinum.next(), None);
+    }
+
+    #[test]
+    fn test_season_and_grade() {
+        let time = |year, month| MonthYear { year, month };
+        assert_eq!(time(2023, Month::December).season(), 2024);
+        assert_eq!(time(2023, Month::November).season(), 2024);
+        assert_eq!(time(2024, Month::Open).season(), 2024);
+        assert_eq!(season_contest_time(2024, Month::December).season(), 2024);
+
+        let graduation = Graduation::HighSchool { year: 2025 };
+        assert_eq!(graduation.grade_at(2025), Some(12));
+        assert_eq!(graduation.grade_at(2022), Some(9));
+        // odd data is returned as is
+        assert_eq!(graduation.grade_at(2019), Some(6));
+        assert_eq!(graduation.grade_at(2027), Some(14));
+        assert_eq!(Graduation::Observer.grade_at(2025), None);
+    }
+
+    #[test]
+    fn test_normalize_country() {
+        assert!(matches!(normalize_country("USA"), Cow::Borrowed("USA")));
+        assert_eq!(normalize_country(" usa "), "USA");
+        assert_eq!(normalize_country("United  States"), "USA");
+        assert_eq!(normalize_country("china"), "CHN");
+        assert_eq!(normalize_country("South Korea"), "KOR");
+        assert_eq!(normalize_country("Bhutan"), "BHUTAN");
+        assert_eq!(normalize_country(""), "");
+    }
+
     #[test]
     fn test_normalize_text() {
         assert_eq!(normalize_text("A   B   C"), "A B C");
@@ -776,4 +2104,926 @@ mod tests {
         assert_eq!(normalize_text("   \t\n"), "");
         assert_eq!(normalize_text("Word"), "Word");
     }
+
+    /// A minimal contest results page with the given heading and one
+    /// participant.
+    fn contest_page(heading: &str) -> String {
+        format!(
+            r#"<html><body><div class="panel">
+<h2>{heading}</h2>
+<h2>Final Results</h2>
+<table>
+<tr><th>Country</th><th>Year</th><th>Name</th><th>Score</th><th></th><th colspan="3">1</th><th></th><th colspan="3">2</th></tr>
+<tr><td>USA</td><td>2025</td><td>Jane Doe</td><td>750</td><td></td><td>*</td><td>x</td><td></td><td></td><td>*</td><td>*</td><td></td></tr>
+</table>
+</div></body></html>"#
+        )
+    }
+
+    #[test]
+    fn test_parse_problem_header() {
+        assert_eq!(
+            parse_problem_header("Problem 1. Cow Race (333)".to_string()),
+            ContestProblem {
+                name: "Problem 1. Cow Race".to_string(),
+                max_score: Some(333),
+            }
+        );
+        assert_eq!(
+            parse_problem_header("Problem 2 (Hard)".to_string()),
+            ContestProblem {
+                name: "Problem 2 (Hard)".to_string(),
+                max_score: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_submission_times() {
+        let page = r#"<html><body><div class="panel">
+<h2>USACO 2024 US Open Contest, Gold</h2>
+<table>
+<tr><th>Country</th><th>Year</th><th>Name</th><th>Score</th><th></th><th colspan="3">1</th><th>Time</th><th></th><th colspan="3">2</th><th>Time</th></tr>
+<tr><td>USA</td><td>2025</td><td>Jane Doe</td><td>750</td><td></td><td>*</td><td>x</td><td></td><td>01:23</td><td></td><td></td><td></td><td></td><td></td></tr>
+</table>
+</div></body></html>"#;
+
+        let contest = parse_contest_page(
+            MonthYear {
+                year: 2024,
+                month: Month::Open,
+            },
+            Division::Gold,
+            page,
+        );
+
+        assert_eq!(contest.participants.len(), 1);
+        assert_eq!(
+            contest.participants[0].submission_results,
+            [
+                Some(vec![TestcaseResult::Correct, TestcaseResult::WrongAnswer]),
+                None,
+            ]
+        );
+        assert_eq!(
+            contest.participants[0].submission_times,
+            [Some("01:23".to_string()), None]
+        );
+        assert_eq!(contest.participants[0].problem_scores, [Some(250), None]);
+        assert_eq!(
+            contest.problems,
+            [
+                ContestProblem {
+                    name: "1".to_string(),
+                    max_score: None
+                },
+                ContestProblem {
+                    name: "2".to_string(),
+                    max_score: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contest_to_html_round_trip() {
+        use TestcaseResult::*;
+
+        let participant = |graduation, name, score, submission_results| ContestParticipant {
+            graduation,
+            submission_results,
+            ..test_util::contest_participant(name, score)
+        };
+        let mut contest = Contest {
+            problems: vec![
+                ContestProblem {
+                    name: "Problem 1. Jane's Problem".to_string(),
+                    max_score: Some(333),
+                },
+                ContestProblem {
+                    name: "Problem 2".to_string(),
+                    max_score: None,
+                },
+            ],
+            participants: vec![
+                participant(
+                    Graduation::HighSchool { year: 2025 },
+                    "Jane <Doe> & Co",
+                    750,
+                    vec![
+                        Some(vec![Correct, WrongAnswer, Timeout]),
+                        Some(vec![CompilationError, RunTimeError]),
+                    ],
+                ),
+                participant(
+                    Graduation::HighSchool { year: 2026 },
+                    "John Doe",
+                    500,
+                    vec![None, Some(vec![Empty, Correct])],
+                ),
+                participant(
+                    Graduation::Observer,
+                    "Jim Doe",
+                    250,
+                    vec![Some(vec![Correct, Correct, Correct]), None],
+                ),
+            ],
+            ..test_util::contest(2024, Month::Open, Division::Platinum)
+        };
+
+        for p in &mut contest.participants {
+            p.problem_scores = problem_scores(&contest.problems, &p.submission_results);
+        }
+
+        let round_trip = |contest: &Contest| {
+            parse_contest_page(contest.time, contest.division, &contest_to_html(contest))
+        };
+
+        let parsed = round_trip(&contest);
+        assert!(!parsed.division_mismatch);
+        assert_eq!(parsed.problems, contest.problems);
+        assert_eq!(parsed.participants, contest.participants);
+
+        for (p, times) in contest.participants.iter_mut().zip([
+            [Some("01:23"), Some("02:34")],
+            [None, Some("03:45")],
+            [Some("00:12"), None],
+        ]) {
+            p.submission_times = times.map(|t| t.map(str::to_string)).to_vec();
+        }
+        assert_eq!(round_trip(&contest).participants, contest.participants);
+    }
+
+    #[test]
+    fn test_camp_preferred_names() {
+        let page = r#"<html><body>
+<table>
+<tr><th>Grad Year</th><th>Name</th><th>School</th><th>State</th></tr>
+<tr><td>2025</td><td>(Jane) Janet Doe</td><td>Example High School</td><td>CA</td></tr>
+<tr><td>2026</td><td>John (Johnny) Smith</td><td>Example High School</td><td>CA</td><td>(he/him)</td></tr>
+<tr><td>2026</td><td>Jim Doe</td><td>Example High School</td></tr>
+<tr><td>2027</td><td>Jane (JJ) Mary (M) Doe</td><td>Example High School</td><td>NY</td></tr>
+</table>
+</body></html>"#;
+
+        let camp = parse_camp_page(2024, page);
+        let names = camp
+            .participants
+            .iter()
+            .map(|p| (p.name.as_str(), p.state.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("Janet Doe", "CA"),
+                ("John Smith", "CA"),
+                ("Jane Mary Doe", "NY")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let time = MonthYear {
+            year: 2024,
+            month: Month::Open,
+        };
+
+        let page = contest_page("USACO 2024 US Open Contest, Gold");
+        let (contest, warnings) = parse_contest_page_with_diagnostics(time, Division::Gold, &page);
+        assert_eq!(contest.participants.len(), 1);
+        assert_eq!(warnings, []);
+
+        let bad_row = "<tr><td>USA</td><td>20XX</td><td>John Smith</td><td>500</td></tr>";
+        let page = page.replace("</table>", &format!("{bad_row}\n</table>"));
+        let (contest, warnings) = parse_contest_page_with_diagnostics(time, Division::Gold, &page);
+        assert_eq!(contest.participants.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].snippet, bad_row);
+        assert!(warnings[0].message.contains("error when parsing row"));
+
+        let page = r#"<html><body>
+<table>
+<tr><th>Grad Year</th><th>Name</th><th>School</th><th>State</th></tr>
+<tr><td>2025</td><td>Jane Doe</td><td>Example High School</td><td>CA</td></tr>
+<tr><td>2026</td><td>Jim Doe</td><td>Example High School</td></tr>
+</table>
+</body></html>"#;
+        let (camp, warnings) = parse_camp_page_with_diagnostics(2024, page);
+        assert_eq!(camp.participants.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].snippet.contains("Jim Doe"));
+
+        let (_, warnings) = parse_history_page_with_diagnostics("");
+        assert_eq!(warnings, []);
+    }
+
+    #[test]
+    fn test_division_mismatch() {
+        let time = MonthYear {
+            year: 2024,
+            month: Month::Open,
+        };
+
+        let contest = parse_contest_page(
+            time,
+            Division::Gold,
+            &contest_page("USACO 2024 US Open Contest, Gold"),
+        );
+        assert!(!contest.division_mismatch);
+        assert_eq!(
+            contest.participants,
+            [ContestParticipant {
+                country: "USA".to_string(),
+                graduation: Graduation::HighSchool { year: 2025 },
+                name: "Jane Doe".to_string(),
+                score: 750,
+                submission_results: vec![
+                    Some(vec![TestcaseResult::Correct, TestcaseResult::WrongAnswer]),
+                    Some(vec![TestcaseResult::Correct, TestcaseResult::Correct]),
+                ],
+                submission_times: vec![],
+                problem_scores: vec![Some(250), Some(500)],
+            }]
+        );
+
+        let contest = parse_contest_page(
+            time,
+            Division::Platinum,
+            &contest_page("USACO 2024 US Open Contest, Gold"),
+        );
+        assert!(contest.division_mismatch);
+        // the data is still parsed, just flagged
+        assert_eq!(contest.participants.len(), 1);
+
+        let contest = parse_contest_page(
+            time,
+            Division::Platinum,
+            &contest_page("USACO 2024 US Open Contest, Plat"),
+        );
+        assert!(!contest.division_mismatch);
+
+        let contest = parse_contest_page(time, Division::Platinum, &contest_page("Final Results"));
+        assert!(!contest.division_mismatch);
+    }
+
+    /// Serves `pages` keyed by URL, responding with a 404 to anything else.
+    /// Records every requested URL.
+    #[derive(Clone, Default)]
+    struct FakeClient {
+        pages: HashMap<String, String>,
+        requested: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpClient for FakeClient {
+        type Error = Infallible;
+        type Future = std::future::Ready<Result<(StatusCode, String), Self::Error>>;
+
+        fn get(&mut self, url: Url) -> Self::Future {
+            self.requested.lock().unwrap().push(url.to_string());
+
+            std::future::ready(Ok(match self.pages.get(url.as_str()) {
+                Some(page) => (StatusCode::OK, page.clone()),
+                None => (StatusCode::NOT_FOUND, String::new()),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_options_divisions() {
+        let client = FakeClient::default();
+        let options = ParseOptions {
+            divisions: vec![Division::Platinum],
+            probe_format_changes: false,
+            ..ParseOptions::new(2016)
+        };
+        parse_all_with_options(options, client.clone())
+            .await
+            .unwrap();
+
+        let contest_urls = client
+            .requested
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|u| u.contains("_results"))
+            .cloned()
+            .collect::<Vec<_>>();
+        // platinum only exists starting from the 2015-16 season
+        assert_eq!(
+            contest_urls,
+            [
+                "https://usaco.org/current/data/dec15_platinum_results.html",
+                "https://usaco.org/current/data/jan16_platinum_results.html",
+                "https://usaco.org/current/data/feb16_platinum_results.html",
+                "https://usaco.org/current/data/open16_platinum_results.html",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wayback_snapshot_url() {
+        let url = "https://usaco.org/current/data/open16_platinum_results.html"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            wayback_snapshot_url("20160601id_", &url).as_str(),
+            "https://web.archive.org/web/20160601id_/https://usaco.org/current/data/open16_platinum_results.html"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_url() {
+        let client = FakeClient::default();
+        let options = ParseOptions {
+            divisions: vec![],
+            probe_format_changes: false,
+            ..ParseOptions::new(2012).wayback_snapshot("2013")
+        };
+        parse_all_with_options(options, client.clone())
+            .await
+            .unwrap();
+
+        let mut requested = client.requested.lock().unwrap().clone();
+        requested.sort();
+        assert_eq!(
+            requested,
+            [
+                "https://web.archive.org/web/2013/https://usaco.org/index.php?page=finalists12",
+                "https://web.archive.org/web/2013/https://usaco.org/index.php?page=history",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_history() {
+        let client = FakeClient::default();
+        let history = parse_history(client.clone()).await.unwrap();
+
+        assert!(history.ioi.is_empty() && history.egoi.is_empty());
+        assert_eq!(*client.requested.lock().unwrap(), [HISTORY_URL]);
+    }
+
+    #[tokio::test]
+    async fn test_intl_competitions() {
+        let page = r#"<html><body><div class="content">
+<div><h2>USA IOI Team</h2>
+<div class="panel historypanel"><h3>2024</h3><img src="current/images/medal_gold.png">Jane Doe<br></div>
+</div>
+<div><h2>USA EGOI Team</h2>
+<div class="panel historypanel"><h3>2024</h3><img src="current/images/medal_silver.png">Janet Doe<br></div>
+</div>
+</div></body></html>"#;
+        let client = FakeClient {
+            pages: HashMap::from([(HISTORY_URL.to_string(), page.to_string())]),
+            ..Default::default()
+        };
+        let parse = |intl_competitions| {
+            let options = ParseOptions {
+                divisions: vec![],
+                probe_format_changes: false,
+                intl_competitions,
+                ..ParseOptions::new(2012)
+            };
+            let client = client.clone();
+            async move {
+                client.requested.lock().unwrap().clear();
+                let history = parse_all_with_options(options, client.clone())
+                    .await
+                    .unwrap()
+                    .intl_history;
+                let requested_history = client
+                    .requested
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .any(|u| u == HISTORY_URL);
+                (history, requested_history)
+            }
+        };
+
+        let (history, _) = parse(vec![IntlCompetition::Ioi, IntlCompetition::Egoi]).await;
+        assert_eq!((history.ioi.len(), history.egoi.len()), (1, 1));
+
+        let (history, _) = parse(vec![IntlCompetition::Egoi]).await;
+        assert!(history.ioi.is_empty());
+        assert_eq!(history.egoi[0].name, "Janet Doe");
+
+        let (history, _) = parse(vec![IntlCompetition::Ioi]).await;
+        assert_eq!(history.ioi[0].name, "Jane Doe");
+        assert!(history.egoi.is_empty());
+
+        let (history, requested) = parse(vec![]).await;
+        assert!(history.ioi.is_empty() && history.egoi.is_empty());
+        assert!(!requested);
+    }
+
+    #[tokio::test]
+    async fn test_progress() {
+        let client = FakeClient::default();
+        let reported = Arc::new(Mutex::new(vec![]));
+        let options = ParseOptions {
+            progress: Some({
+                let reported = reported.clone();
+                Arc::new(move |p| reported.lock().unwrap().push(p))
+            }),
+            ..ParseOptions::new(2016)
+        };
+        parse_all_with_options(options, client.clone())
+            .await
+            .unwrap();
+
+        let requested = client.requested.lock().unwrap().len();
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), requested);
+        assert!(reported.iter().all(|p| p.total == requested));
+
+        let mut completed = reported.iter().map(|p| p.completed).collect::<Vec<_>>();
+        completed.sort_unstable();
+        assert_eq!(completed, (1..=requested).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_contest_results_url() {
+        let time = season_contest_time(2024, Month::December);
+        assert_eq!(time.year, 2023);
+        assert_eq!(
+            contest_results_url(time, Division::Silver).as_str(),
+            "https://usaco.org/current/data/dec23_silver_results.html"
+        );
+        assert_eq!(
+            contest_results_url(
+                MonthYear {
+                    year: 2024,
+                    month: Month::Open
+                },
+                Division::Platinum
+            )
+            .as_str(),
+            "https://usaco.org/current/data/open24_platinum_results.html"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_concurrent() {
+        #[derive(Default, Clone)]
+        struct SlowClient {
+            in_flight: Arc<Mutex<(usize, usize)>>,
+        }
+
+        impl HttpClient for SlowClient {
+            type Error = Infallible;
+            type Future =
+                Pin<Box<dyn Future<Output = Result<(StatusCode, String), Infallible>> + Send>>;
+
+            fn get(&mut self, _url: Url) -> Self::Future {
+                // count the request as started as soon as it's passed to us,
+                // like a client that starts requesting eagerly would
+                {
+                    let (cur, max) = &mut *self.in_flight.lock().unwrap();
+                    *cur += 1;
+                    *max = (*max).max(*cur);
+                }
+                let in_flight = self.in_flight.clone();
+
+                Box::pin(async move {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    in_flight.lock().unwrap().0 -= 1;
+
+                    Ok((StatusCode::NOT_FOUND, String::new()))
+                })
+            }
+        }
+
+        let client = SlowClient::default();
+        let options = ParseOptions {
+            max_concurrent: 3,
+            ..ParseOptions::new(2016)
+        };
+        parse_all_with_options(options, client.clone())
+            .await
+            .unwrap();
+        assert_eq!(*client.in_flight.lock().unwrap(), (0, 3));
+    }
+
+    #[tokio::test]
+    async fn test_sequential_seasons() {
+        let client = FakeClient {
+            pages: HashMap::from([(
+                "https://usaco.org/current/data/dec12_bronze_results.html".to_string(),
+                contest_page("USACO 2012 December Contest, Bronze"),
+            )]),
+            ..Default::default()
+        };
+        let options = || ParseOptions::new(2014);
+
+        let all_at_once = parse_all_with_options(options(), client.clone())
+            .await
+            .unwrap();
+        let mut all_at_once_requests = std::mem::take(&mut *client.requested.lock().unwrap());
+
+        let sequential = parse_all_with_options(
+            ParseOptions {
+                scheduling: Scheduling::SequentialSeasons,
+                ..options()
+            },
+            client.clone(),
+        )
+        .await
+        .unwrap();
+        let requested = client.requested.lock().unwrap().clone();
+
+        // requests for the 2012-13 season only start after the 2011-12 season's
+        // camp page, which is requested after its contests
+        let position = |pat: &str| requested.iter().position(|u| u.contains(pat)).unwrap();
+        assert!(position("finalists12") < position("nov12_"));
+        assert!(position("finalists13") < position("nov13_"));
+
+        assert_eq!(sequential.contests.len(), 1);
+        assert_eq!(
+            sequential.contests[0].participants,
+            all_at_once.contests[0].participants
+        );
+        let mut sequential_requests = requested;
+        all_at_once_requests.sort_unstable();
+        sequential_requests.sort_unstable();
+        assert_eq!(sequential_requests, all_at_once_requests);
+    }
+
+    #[cfg(feature = "checkpoint")]
+    #[tokio::test]
+    async fn test_resume_from() {
+        /// Fails requests to `fail`, and otherwise defers to `inner`.
+        #[derive(Clone)]
+        struct FailingClient {
+            inner: FakeClient,
+            fail: String,
+        }
+
+        impl HttpClient for FailingClient {
+            type Error = String;
+            type Future = std::future::Ready<Result<(StatusCode, String), String>>;
+
+            fn get(&mut self, url: Url) -> Self::Future {
+                if url.as_str() == self.fail {
+                    self.inner.requested.lock().unwrap().push(url.to_string());
+                    return std::future::ready(Err(format!("failed to request {url}")));
+                }
+
+                let Ok(res) = self.inner.get(url).into_inner();
+                std::future::ready(Ok(res))
+            }
+        }
+
+        // the checkpoint directory is created if it doesn't exist
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("checkpoints");
+
+        let gold = "https://usaco.org/current/data/dec15_gold_results.html";
+        let plat = "https://usaco.org/current/data/dec15_platinum_results.html";
+        let client = FakeClient {
+            pages: HashMap::from([
+                (
+                    gold.to_string(),
+                    contest_page("USACO 2015 December Contest, Gold"),
+                ),
+                (
+                    plat.to_string(),
+                    contest_page("USACO 2015 December Contest, Platinum"),
+                ),
+            ]),
+            ..Default::default()
+        };
+        let options = || ParseOptions {
+            checkpoint_dir: Some(dir.clone()),
+            ..ParseOptions::new(2016)
+        };
+
+        let failing = FailingClient {
+            inner: client.clone(),
+            fail: plat.to_string(),
+        };
+        assert!(parse_all_with_options(options(), failing).await.is_err());
+        assert!(dir.join("dec15_gold.json").exists());
+        assert!(dir.join("finalists16.json").exists());
+        assert!(!dir.join("dec15_platinum.json").exists());
+
+        client.requested.lock().unwrap().clear();
+        let data = resume_from(&dir, options(), client.clone()).await.unwrap();
+
+        // only the failed page, the history page, and probes are requested again
+        let requested = client.requested.lock().unwrap();
+        assert!(requested.iter().any(|u| u == plat));
+        assert!(!requested
+            .iter()
+            .any(|u| u == gold || u.contains("finalists")));
+        assert_eq!(
+            data.contests
+                .iter()
+                .map(|c| (c.time, c.division))
+                .collect::<Vec<_>>(),
+            [
+                (season_contest_time(2016, Month::December), Division::Gold),
+                (
+                    season_contest_time(2016, Month::December),
+                    Division::Platinum
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_format_changes() {
+        let client = FakeClient::default();
+        let options = ParseOptions {
+            divisions: vec![Division::Gold],
+            ..ParseOptions::new(2025)
+        };
+        parse_all_with_options(options, client.clone())
+            .await
+            .unwrap();
+
+        let requested = client.requested.lock().unwrap();
+        // only the latest season is probed
+        for url in [
+            "https://usaco.org/current/data/nov24_gold_results.html",
+            "https://usaco.org/current/data/mar25_gold_results.html",
+        ] {
+            assert!(requested.iter().any(|u| u == url), "{url} not probed");
+        }
+        assert!(!requested.iter().any(|u| u.contains("mar24")));
+    }
+
+    #[tokio::test]
+    async fn test_probe_error_ignored() {
+        /// Fails requests to the probed `url`, and otherwise defers to `inner`.
+        #[derive(Clone)]
+        struct ProbeFailingClient {
+            inner: FakeClient,
+            url: &'static str,
+        }
+
+        impl HttpClient for ProbeFailingClient {
+            type Error = String;
+            type Future = std::future::Ready<Result<(StatusCode, String), String>>;
+
+            fn get(&mut self, url: Url) -> Self::Future {
+                if url.as_str() == self.url {
+                    return std::future::ready(Err(format!("timed out requesting {url}")));
+                }
+
+                let Ok(res) = self.inner.get(url).into_inner();
+                std::future::ready(Ok(res))
+            }
+        }
+
+        let contest = "https://usaco.org/current/data/dec24_gold_results.html";
+        let client = ProbeFailingClient {
+            inner: FakeClient {
+                pages: HashMap::from([(
+                    contest.to_string(),
+                    contest_page("USACO 2024 December Contest, Gold"),
+                )]),
+                ..FakeClient::default()
+            },
+            url: "https://usaco.org/current/data/nov24_gold_results.html",
+        };
+        let options = ParseOptions {
+            divisions: vec![Division::Gold],
+            ..ParseOptions::new(2025)
+        };
+
+        let data = parse_all_with_options(options, client).await.unwrap();
+        assert_eq!(data.contests.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_probe_hit_warns() {
+        /// Collects everything logged into a shared buffer.
+        #[derive(Clone, Default)]
+        struct Logs(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Logs {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let probed = "https://usaco.org/current/data/nov24_gold_results.html";
+        let client = FakeClient {
+            pages: HashMap::from([(
+                probed.to_string(),
+                contest_page("USACO 2024 November Contest, Gold"),
+            )]),
+            ..FakeClient::default()
+        };
+        let options = ParseOptions {
+            divisions: vec![Division::Gold],
+            ..ParseOptions::new(2025)
+        };
+
+        let logs = Logs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer({
+                let logs = logs.clone();
+                move || logs.clone()
+            })
+            .with_ansi(false)
+            .finish();
+        let data = {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            parse_all_with_options(options, client).await.unwrap()
+        };
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            logs.contains(&format!("found unexpected contest results at {probed}")),
+            "no warning logged for probe hit:\n{logs}"
+        );
+        // the probed page is only reported, not parsed as a contest
+        assert!(data.contests.is_empty());
+    }
+
+    #[test]
+    fn test_image_verdict_names() {
+        let verdict = |src: &str| {
+            let html = Html::parse_fragment(&format!(
+                r#"<table><tr><td><img src="{src}"></td></tr></table>"#
+            ));
+            let cell = html.select(selector!("td")).next().unwrap();
+            verdict_cell_text(cell, selector!("img"))
+        };
+
+        assert_eq!(verdict("/images/check.png"), "*");
+        assert_eq!(verdict("/images/correct.gif"), "*");
+        assert_eq!(verdict("/images/incorrect.png"), "x");
+        assert_eq!(verdict("/images/not_correct.png"), "x");
+        assert_eq!(verdict("/images/wrong-answer.png"), "x");
+        assert_eq!(verdict("/images/runtime.png"), "!");
+        assert_eq!(verdict("/images/runtime_error.png"), "!");
+        assert_eq!(verdict("/images/timeout.png"), "t");
+        assert_eq!(verdict("/images/clock.png"), "t");
+        // keywords in the directory don't count
+        assert_eq!(verdict("/check/sparkles.png"), "<img /check/sparkles.png>");
+    }
+
+    #[test]
+    fn test_image_verdicts() {
+        let img = |name: &str| format!(r#"<td><img src="/current/images/{name}.png"></td>"#);
+        let page = format!(
+            r#"<table>
+<tr><th>Country</th><th>Year</th><th>Name</th><th>Score</th><th></th><th colspan="4">1</th><th></th><th colspan="4">2</th></tr>
+<tr><td>USA</td><td>2025</td><td>Jane Doe</td><td>500</td><td></td>{}{}{}<td></td><td></td><td></td><td></td><td></td><td></td></tr>
+<tr><td>USA</td><td>2026</td><td>John Doe</td><td>0</td><td></td>{}<td>x</td><td>x</td><td></td><td></td><td></td><td></td><td></td><td></td></tr>
+</table>"#,
+            img("check"),
+            img("cross"),
+            img("clock"),
+            img("sparkles"),
+        );
+
+        let contest = parse_contest_page(
+            MonthYear {
+                year: 2024,
+                month: Month::Open,
+            },
+            Division::Gold,
+            &page,
+        );
+
+        // the row with an unrecognized image is dropped
+        assert_eq!(contest.participants.len(), 1);
+        assert_eq!(
+            contest.participants[0].submission_results,
+            [
+                Some(vec![
+                    TestcaseResult::Correct,
+                    TestcaseResult::WrongAnswer,
+                    TestcaseResult::Timeout,
+                ]),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_problem_count_consistency() {
+        let participant = |name, problems| ContestParticipant {
+            submission_results: vec![None; problems],
+            ..test_util::contest_participant(name, 0)
+        };
+        let mut contest = Contest {
+            participants: vec![
+                participant("A", 3),
+                participant("B", 2),
+                participant("C", 3),
+            ],
+            ..test_util::contest(2024, Month::Open, Division::Gold)
+        };
+
+        let consistency = contest.problem_count_consistency();
+        assert!(!consistency.is_consistent());
+        assert_eq!(consistency.modal_count, Some(3));
+        assert_eq!(consistency.deviating, [1]);
+        assert_eq!(contest.participants[consistency.deviating[0]].name, "B");
+
+        contest.participants.remove(1);
+        assert!(contest.problem_count_consistency().is_consistent());
+
+        contest.participants.clear();
+        assert_eq!(
+            contest.problem_count_consistency(),
+            ProblemCountConsistency {
+                modal_count: None,
+                deviating: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_placements() {
+        let mut contest = parse_contest_page(
+            MonthYear {
+                year: 2024,
+                month: Month::Open,
+            },
+            Division::Gold,
+            &contest_page("USACO 2024 US Open Contest, Gold"),
+        );
+        let participant = contest.participants[0].clone();
+        contest.participants = [700, 900, 800, 800]
+            .into_iter()
+            .map(|score| ContestParticipant {
+                score,
+                ..participant.clone()
+            })
+            .collect();
+
+        assert_eq!(placements(&contest), [4, 1, 2, 2]);
+        // the contest isn't reordered
+        assert_eq!(contest.participants[0].score, 700);
+    }
+
+    #[test]
+    fn test_anonymize() {
+        use test_util::contest_participant as contestant;
+
+        let contest = |division, participants| Contest {
+            participants,
+            ..test_util::contest(2024, Month::Open, division)
+        };
+
+        let original = UsacoData {
+            contests: vec![
+                contest(
+                    Division::Gold,
+                    vec![contestant("Jane Doe", 1000), contestant("John Smith", 800)],
+                ),
+                contest(Division::Platinum, vec![contestant("Jane Doe", 500)]),
+            ],
+            camps: vec![Camp {
+                year: 2024,
+                participants: vec![CampParticipant {
+                    graduation_year: 2025,
+                    name: "Jane Doe".to_string(),
+                    school: "Example High School".to_string(),
+                    state: "CA".to_string(),
+                    is_egoi: false,
+                }],
+            }],
+            intl_history: IntlHistory {
+                ioi: vec![IntlParticipant {
+                    year: 2024,
+                    result: IntlMedal::Gold,
+                    name: "Jane Doe".to_string(),
+                }],
+                egoi: vec![],
+            },
+        };
+        let mut data = original.clone();
+        anonymize(&mut data);
+
+        let jane = &data.contests[0].participants[0].name;
+        let john = &data.contests[0].participants[1].name;
+        assert_ne!(jane, "Jane Doe");
+        assert_ne!(jane, john);
+        assert_eq!(&data.contests[1].participants[0].name, jane);
+        assert_eq!(&data.camps[0].participants[0].name, jane);
+        assert_eq!(&data.intl_history.ioi[0].name, jane);
+        assert_eq!(data.contests[1].participants[0].score, 500);
+
+        // deterministic
+        let mut again = original;
+        anonymize(&mut again);
+        assert_eq!(
+            again.contests[0].participants,
+            data.contests[0].participants
+        );
+    }
 }
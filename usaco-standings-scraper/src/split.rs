@@ -0,0 +1,116 @@
+//! Writing [`UsacoData`] as one json file per season, which is friendlier to
+//! version control than one big file, since rescraping only changes the files
+//! of seasons whose results changed. See [`write_split_by_season`].
+
+use crate::{Camp, Contest, UsacoData};
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// The contents of a season's file.
+#[derive(Default, Serialize)]
+struct Season<'a> {
+    contests: Vec<&'a Contest>,
+    camps: Vec<&'a Camp>,
+}
+
+/// Writes `data` to `dir`, creating it if needed, split into:
+///
+/// - `<season>.json` for each season (as in [`MonthYear::season`]), such as
+///   `2024.json` for the 2023-24 season, with `contests` and `camps` fields
+///   holding that season's [`Contest`]s and [`Camp`]s in the same order as in
+///   `data`. A season's camp is the one held at the end of it.
+/// - `history.json`, holding the [`IntlHistory`], which isn't split since it's
+///   a single page.
+///
+/// Only seasons with at least one contest or camp get a file. Existing files
+/// in `dir` are overwritten, but files of seasons missing from `data` are left
+/// alone.
+///
+/// [`MonthYear::season`]: crate::MonthYear::season
+/// [`IntlHistory`]: crate::IntlHistory
+pub fn write_split_by_season(data: &UsacoData, dir: &Path) -> anyhow::Result<()> {
+    let mut seasons = BTreeMap::<u16, Season>::new();
+    for c in &data.contests {
+        seasons.entry(c.time.season()).or_default().contests.push(c);
+    }
+    for c in &data.camps {
+        seasons.entry(c.year).or_default().camps.push(c);
+    }
+
+    std::fs::create_dir_all(dir)?;
+    for (season, contents) in seasons {
+        std::fs::write(
+            dir.join(format!("{season}.json")),
+            serde_json::to_string(&contents)?,
+        )?;
+    }
+    std::fs::write(
+        dir.join("history.json"),
+        serde_json::to_string(&data.intl_history)?,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Division, IntlHistory, IntlMedal, IntlParticipant, Month};
+
+    fn contest(year: u16, month: Month) -> Contest {
+        crate::test_util::contest(year, month, Division::Gold)
+    }
+
+    #[test]
+    fn test_write_split_by_season() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+
+        let data = UsacoData {
+            contests: vec![
+                contest(2022, Month::December),
+                contest(2023, Month::Open),
+                contest(2023, Month::December),
+            ],
+            camps: vec![Camp {
+                year: 2023,
+                participants: vec![],
+            }],
+            intl_history: IntlHistory {
+                ioi: vec![IntlParticipant {
+                    year: 2023,
+                    name: "Jane Doe".to_string(),
+                    result: IntlMedal::Gold,
+                }],
+                egoi: vec![],
+            },
+        };
+        write_split_by_season(&data, dir).unwrap();
+
+        let mut files = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["2023.json", "2024.json", "history.json"]);
+
+        let read = |file: &str| {
+            serde_json::from_str::<serde_json::Value>(
+                &std::fs::read_to_string(dir.join(file)).unwrap(),
+            )
+            .unwrap()
+        };
+        // December 2022 is in the 2022-23 season, along with its camp
+        let season = read("2023.json");
+        let contests = serde_json::from_value::<Vec<Contest>>(season["contests"].clone()).unwrap();
+        assert_eq!(
+            contests.iter().map(|c| c.time).collect::<Vec<_>>(),
+            [data.contests[0].time, data.contests[1].time]
+        );
+        assert_eq!(season["camps"].as_array().unwrap().len(), 1);
+        assert_eq!(read("2024.json")["camps"].as_array().unwrap().len(), 0);
+        let history = serde_json::from_value::<IntlHistory>(read("history.json")).unwrap();
+        assert_eq!(history.ioi.len(), 1);
+        assert_eq!(history.ioi[0].name, "Jane Doe");
+    }
+}
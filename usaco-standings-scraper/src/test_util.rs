@@ -0,0 +1,29 @@
+//! Fixtures shared by the tests of several modules. Tests override whichever
+//! fields they care about with struct update syntax.
+
+use crate::{Contest, ContestParticipant, Division, Graduation, Month, MonthYear};
+
+/// A contest without any problems or participants.
+pub(crate) fn contest(year: u16, month: Month, division: Division) -> Contest {
+    Contest {
+        time: MonthYear { year, month },
+        division,
+        problems: vec![],
+        participants: vec![],
+        division_mismatch: false,
+    }
+}
+
+/// A participant from the USA in the class of 2025, without any submission
+/// details.
+pub(crate) fn contest_participant(name: &str, score: u16) -> ContestParticipant {
+    ContestParticipant {
+        country: "USA".to_string(),
+        graduation: Graduation::HighSchool { year: 2025 },
+        name: name.to_string(),
+        score,
+        submission_results: vec![],
+        submission_times: vec![],
+        problem_scores: vec![],
+    }
+}
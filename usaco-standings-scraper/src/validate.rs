@@ -0,0 +1,237 @@
+//! Sanity checks over scraped [`UsacoData`], for noticing when the parsers
+//! produce garbage, such as after USACO changes the format of its pages. See
+//! [`validate`].
+
+use crate::{MonthYear, UsacoData};
+use std::{collections::HashSet, fmt::Display};
+
+/// How bad a [`ValidationIssue`] is.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Severity {
+    /// Odd, but could be legitimate, such as a participant without a name.
+    Warning,
+    /// Almost certainly a parsing bug or a wrong page, such as a contest with
+    /// no participants.
+    Critical,
+}
+
+/// Something suspicious found by [`validate`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        };
+
+        write!(f, "{severity}: {}", self.message)
+    }
+}
+
+/// Checks `data` for signs that it was scraped incorrectly. Issues are
+/// returned in no particular order. Critical issues are:
+///
+/// - having no contests, camps, or IOI results at all,
+/// - contests or camps with no participants,
+/// - contests where every participant scored 0, and
+/// - contests whose heading named a different division (see
+///   [`Contest::division_mismatch`]).
+///
+/// Warnings are raised for contests listed more than once, participants
+/// without a name, scores above the contest's maximum, and participants with a
+/// different number of results than the contest has problems.
+///
+/// [`Contest::division_mismatch`]: crate::Contest::division_mismatch
+pub fn validate(data: &UsacoData) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+    let mut report = |severity, message: String| {
+        issues.push(ValidationIssue { severity, message });
+    };
+
+    for (records, what) in [
+        (data.contests.len(), "contests"),
+        (data.camps.len(), "camps"),
+        (data.intl_history.ioi.len(), "IOI results"),
+    ] {
+        if records == 0 {
+            report(Severity::Critical, format!("no {what} were found"));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for c in &data.contests {
+        let MonthYear { year, month } = c.time;
+        let contest = format!("{month} {year} {}", c.division);
+
+        if !seen.insert((c.time, c.division)) {
+            report(
+                Severity::Warning,
+                format!("{contest} is listed more than once"),
+            );
+        }
+        if c.division_mismatch {
+            report(
+                Severity::Critical,
+                format!("{contest}'s page heading names a different division"),
+            );
+        }
+        if c.participants.is_empty() {
+            report(Severity::Critical, format!("{contest} has no participants"));
+            continue;
+        }
+        if c.participants.iter().all(|p| p.score == 0) {
+            report(
+                Severity::Critical,
+                format!("every participant of {contest} scored 0"),
+            );
+        }
+
+        let unnamed = c.participants.iter().filter(|p| p.name.is_empty()).count();
+        if unnamed > 0 {
+            report(
+                Severity::Warning,
+                format!("{contest} has {unnamed} participant(s) without a name"),
+            );
+        }
+
+        // only known if every problem lists its points
+        let max_score = c
+            .problems
+            .iter()
+            .map(|p| p.max_score)
+            .sum::<Option<u16>>()
+            .filter(|_| !c.problems.is_empty());
+        if let Some(max_score) = max_score {
+            let over = c
+                .participants
+                .iter()
+                .filter(|p| p.score > max_score)
+                .count();
+            if over > 0 {
+                report(
+                    Severity::Warning,
+                    format!("{contest} has {over} participant(s) scoring above {max_score}"),
+                );
+            }
+        }
+
+        // problems aren't known for data scraped before they were parsed
+        if !c.problems.is_empty() {
+            let misaligned = c
+                .participants
+                .iter()
+                .filter(|p| p.submission_results.len() != c.problems.len())
+                .count();
+            if misaligned > 0 {
+                report(
+                    Severity::Warning,
+                    format!(
+                        "{contest} has {misaligned} participant(s) without results for exactly \
+                        its {} problem(s)",
+                        c.problems.len()
+                    ),
+                );
+            }
+        }
+    }
+
+    for c in &data.camps {
+        if c.participants.is_empty() {
+            report(
+                Severity::Critical,
+                format!("the {} camp has no participants", c.year),
+            );
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util, Camp, CampParticipant, Contest, ContestParticipant, ContestProblem, Division,
+        IntlHistory, IntlMedal, IntlParticipant, Month,
+    };
+
+    fn contest(division: Division, scores: &[u16]) -> Contest {
+        Contest {
+            problems: vec![
+                ContestProblem {
+                    name: "Problem 1".to_string(),
+                    max_score: Some(500),
+                },
+                ContestProblem {
+                    name: "Problem 2".to_string(),
+                    max_score: Some(500),
+                },
+            ],
+            participants: scores
+                .iter()
+                .map(|&score| ContestParticipant {
+                    submission_results: vec![Some(vec![]), Some(vec![])],
+                    ..test_util::contest_participant("Jane Doe", score)
+                })
+                .collect(),
+            ..test_util::contest(2024, Month::Open, division)
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut data = UsacoData {
+            contests: vec![
+                contest(Division::Platinum, &[1000, 500]),
+                contest(Division::Gold, &[700]),
+            ],
+            camps: vec![Camp {
+                year: 2024,
+                participants: vec![CampParticipant {
+                    graduation_year: 2025,
+                    name: "Jane Doe".to_string(),
+                    school: "Example High School".to_string(),
+                    state: "CA".to_string(),
+                    is_egoi: false,
+                }],
+            }],
+            intl_history: IntlHistory {
+                ioi: vec![IntlParticipant {
+                    year: 2024,
+                    name: "Jane Doe".to_string(),
+                    result: IntlMedal::Gold,
+                }],
+                egoi: vec![],
+            },
+        };
+        assert_eq!(validate(&data), []);
+
+        data.contests.push(contest(Division::Silver, &[0, 0]));
+        data.contests.push(contest(Division::Bronze, &[]));
+        data.contests[0].participants[1].score = 1200;
+        data.contests[1].participants[0].submission_results.pop();
+        data.camps.clear();
+
+        let mut issues = validate(&data)
+            .into_iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        issues.sort();
+        assert_eq!(
+            issues,
+            [
+                "critical: every participant of open 2024 silver scored 0",
+                "critical: no camps were found",
+                "critical: open 2024 bronze has no participants",
+                "warning: open 2024 gold has 1 participant(s) without results for exactly its 2 \
+                problem(s)",
+                "warning: open 2024 platinum has 1 participant(s) scoring above 1000",
+            ]
+        );
+    }
+}